@@ -0,0 +1,62 @@
+//! Compares positioning the table at random rows of a database of a million
+//! entries by iterating from the start and by seeking with the `RowCache`.
+//!
+//! Run it with `cargo bench --bench row_cache`.
+
+// The editor uses more of the cache than this benchmark, and its tests aren't run from it.
+#[allow(dead_code, unused_imports)]
+#[path = "../src/row_cache.rs"]
+mod row_cache;
+
+use std::fs;
+use std::time::Instant;
+
+use heed::types::ByteSlice;
+use heed::{Database, EnvOpenOptions};
+use row_cache::RowCache;
+
+const NUM_ENTRIES: u64 = 1_000_000;
+const NUM_SEEKS: usize = 100;
+/// The number of rows a viewport shows after each seek.
+const VIEWPORT_ROWS: usize = 30;
+
+fn main() -> anyhow::Result<()> {
+    let path = std::env::temp_dir().join(format!("lmdb-editor-row-cache-{}", std::process::id()));
+    fs::create_dir_all(&path)?;
+    let env = EnvOpenOptions::new().map_size(1024 * 1024 * 1024).open(&path)?;
+
+    let mut wtxn = env.write_txn()?;
+    let database: Database<ByteSlice, ByteSlice> = env.create_database(&mut wtxn, None)?;
+    for i in 0..NUM_ENTRIES {
+        database.append(&mut wtxn, &i.to_be_bytes(), b"some data")?;
+    }
+    wtxn.commit()?;
+
+    // Pseudo random rows, the same ones for both strategies.
+    let rows: Vec<usize> = (0..NUM_SEEKS as u64)
+        .map(|i| (i.wrapping_mul(0x9E37_79B9_7F4A_7C15) % NUM_ENTRIES) as usize)
+        .collect();
+
+    let rtxn = env.read_txn()?;
+
+    let before = Instant::now();
+    for &row in &rows {
+        let visible = database.iter(&rtxn)?.skip(row).take(VIEWPORT_ROWS);
+        assert_eq!(visible.count(), VIEWPORT_ROWS.min(NUM_ENTRIES as usize - row));
+    }
+    println!("iterating from the start: {:?} per seek", before.elapsed() / NUM_SEEKS as u32);
+
+    let mut cache = RowCache::default();
+    let before = Instant::now();
+    for &row in &rows {
+        let visible = cache.iter_from(&database, &rtxn, row)?.take(VIEWPORT_ROWS);
+        let first_key = visible.map(Result::unwrap).next().map(|(key, _)| key.to_vec());
+        assert_eq!(first_key, Some((row as u64).to_be_bytes().to_vec()));
+    }
+    println!("seeking with the row cache: {:?} per seek", before.elapsed() / NUM_SEEKS as u32);
+
+    drop(rtxn);
+    env.prepare_for_closing().wait();
+    fs::remove_dir_all(&path)?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+use heed::types::ByteSlice;
+use heed::{Database, Env};
+
+/// The number of entries deleted between two progress reports.
+const REPORT_EVERY: u64 = 10_000;
+
+enum Message {
+    Deleted(u64),
+    Done(heed::Result<()>),
+}
+
+/// Clears a database in a background thread, in its own write txn that is committed once
+/// all the entries are deleted. LMDB only has one write txn at a time, no other one must
+/// be opened until it is done.
+pub struct BackgroundClear {
+    receiver: Receiver<Message>,
+    /// The number of entries deleted so far.
+    pub deleted: u64,
+    /// The number of entries of the database when it started.
+    pub total: u64,
+}
+
+impl BackgroundClear {
+    pub fn spawn(
+        env: &Env,
+        database: Database<ByteSlice, ByteSlice>,
+        total: u64,
+    ) -> BackgroundClear {
+        let (sender, receiver) = mpsc::channel();
+        // The thread owns a handle of the environment, it stays open until the thread is done.
+        let env = env.clone();
+        thread::spawn(move || {
+            let result = clear(&env, database, &sender);
+            let _ = sender.send(Message::Done(result));
+        });
+        BackgroundClear { receiver, deleted: 0, total }
+    }
+
+    /// Receives the progress reported since the last poll, and returns the outcome once done.
+    pub fn poll(&mut self) -> Option<heed::Result<()>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Message::Deleted(deleted)) => self.deleted = deleted,
+                Ok(Message::Done(result)) => return Some(result),
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    return Some(Err(heed::Error::Io(std::io::Error::other(
+                        "the thread clearing the database stopped",
+                    ))))
+                }
+            }
+        }
+    }
+}
+
+/// Deletes the entries one by one to report the progress, `Database::clear` can't.
+fn clear(
+    env: &Env,
+    database: Database<ByteSlice, ByteSlice>,
+    sender: &Sender<Message>,
+) -> heed::Result<()> {
+    let mut wtxn = env.write_txn()?;
+    let mut deleted = 0;
+    let mut iter = database.iter_mut(&mut wtxn)?;
+    while iter.next().transpose()?.is_some() {
+        // Safety: we don't keep any reference to the entries of the database.
+        unsafe { iter.del_current()? };
+        deleted += 1;
+        if deleted % REPORT_EVERY == 0 {
+            let _ = sender.send(Message::Deleted(deleted));
+        }
+    }
+    drop(iter);
+    wtxn.commit()
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use heed::{CompactionOption, Env};
+
+/// Copies the environment into a folder in a background thread, with `mdb_env_copy`.
+///
+/// The copy is a consistent snapshot of the last commit, it uses its own read txn.
+/// Without compaction it briefly waits for the write txn, if any, to be done.
+pub struct Backup {
+    receiver: Receiver<heed::Result<()>>,
+    /// The data file the environment is copied into.
+    pub path: PathBuf,
+}
+
+impl Backup {
+    pub fn spawn(env: &Env, folder: PathBuf, compact: bool) -> Backup {
+        let path = folder.join("data.mdb");
+        let option = if compact { CompactionOption::Enabled } else { CompactionOption::Disabled };
+        let (sender, receiver) = mpsc::channel();
+        let file_path = path.clone();
+        let env = env.clone();
+        thread::spawn(move || {
+            let result = env.copy_to_file(&file_path, option).map(drop);
+            let _ = sender.send(result);
+        });
+        Backup { receiver, path }
+    }
+
+    /// Returns the outcome of the copy once it is done.
+    pub fn poll(&self) -> Option<heed::Result<()>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(heed::Error::Io(std::io::Error::other(
+                "the thread copying the environment stopped",
+            )))),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::{Read, Write};
+
+/// The decompressed values larger than this are not shown, a small compressed value can
+/// expand to gigabytes.
+const MAX_DECOMPRESSED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// How the values of a database are compressed, they are decompressed to be shown and
+/// the inserted data is compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 2] = [Compression::Gzip, Compression::Zstd];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the compression the bytes start with the magic number of.
+    pub fn detect(bytes: &[u8]) -> Option<Compression> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    pub fn compress(self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Compression::Gzip => {
+                let level = flate2::Compression::default();
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), level);
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            Compression::Zstd => zstd::encode_all(bytes, zstd::DEFAULT_COMPRESSION_LEVEL),
+        }
+    }
+}
+
+/// Decompresses the bytes with the compression they are detected to use.
+pub fn decompress(bytes: &[u8]) -> Result<(Compression, Vec<u8>), String> {
+    let compression = Compression::detect(bytes).ok_or("not gzip nor zstd")?;
+    let reader: Box<dyn Read> = match compression {
+        Compression::Gzip => Box::new(flate2::read::GzDecoder::new(bytes)),
+        Compression::Zstd => Box::new(zstd::Decoder::new(bytes).map_err(|e| e.to_string())?),
+    };
+    let mut decompressed = Vec::new();
+    let read = reader.take(MAX_DECOMPRESSED_BYTES + 1).read_to_end(&mut decompressed);
+    read.map_err(|e| format!("invalid {}: {e}", compression.label()))?;
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return Err(format!("more than {} MiB decompressed", MAX_DECOMPRESSED_BYTES >> 20));
+    }
+    Ok((compression, decompressed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decompress, Compression, MAX_DECOMPRESSED_BYTES};
+
+    #[test]
+    fn round_trips_both_compressions() {
+        let bytes = b"hello hello hello hello";
+        for compression in Compression::ALL {
+            let compressed = compression.compress(bytes).unwrap();
+            assert_eq!(Compression::detect(&compressed), Some(compression));
+            assert_eq!(decompress(&compressed).unwrap(), (compression, bytes.to_vec()));
+        }
+        assert_eq!(Compression::detect(bytes), None);
+        assert_eq!(decompress(bytes).unwrap_err(), "not gzip nor zstd");
+    }
+
+    #[test]
+    fn refuses_the_values_that_expand_too_much() {
+        let zeros = vec![0; MAX_DECOMPRESSED_BYTES as usize + 1];
+        let compressed = Compression::Zstd.compress(&zeros).unwrap();
+        assert_eq!(decompress(&compressed).unwrap_err(), "more than 64 MiB decompressed");
+        let truncated = Compression::Gzip.compress(b"hello").unwrap()[..12].to_vec();
+        assert!(decompress(&truncated).unwrap_err().starts_with("invalid gzip: "));
+    }
+}
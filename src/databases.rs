@@ -0,0 +1,330 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Bound;
+use std::{mem, str};
+
+use heed::types::ByteSlice;
+use heed::{Database, Env, RoTxn, RwTxn};
+
+/// The size of the `MDB_db` record LMDB stores as the value of a named database.
+const DATABASE_RECORD_LEN: usize = 4 + 2 + 2 + 5 * mem::size_of::<usize>();
+
+/// The magic number at the start of the meta pages of an environment.
+const MDB_MAGIC: u32 = 0xBEEF_C0DE;
+
+/// The version of the layout of the data file, the meta pages are laid out for it.
+const MDB_DATA_VERSION: u32 = 1;
+
+/// The flag of the databases whose keys are compared from their end.
+const MDB_REVERSEKEY: u16 = 0x02;
+
+/// The flag of the databases that can store multiple sorted values per key.
+const MDB_DUPSORT: u16 = 0x04;
+
+/// The statistics LMDB keeps in the record of a named database.
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseStat {
+    pub flags: u16,
+    /// The depth of the B-tree.
+    pub depth: u16,
+    pub branch_pages: usize,
+    pub leaf_pages: usize,
+    pub overflow_pages: usize,
+    pub entries: usize,
+}
+
+impl DatabaseStat {
+    /// Parses an `MDB_db` record, it is stored in the native endianness.
+    fn from_record(record: &[u8]) -> Option<DatabaseStat> {
+        if record.len() != DATABASE_RECORD_LEN {
+            return None;
+        }
+
+        let usize_at = |i: usize| {
+            let offset = 8 + i * mem::size_of::<usize>();
+            usize::from_ne_bytes(record[offset..][..mem::size_of::<usize>()].try_into().unwrap())
+        };
+
+        Some(DatabaseStat {
+            flags: u16::from_ne_bytes([record[4], record[5]]),
+            depth: u16::from_ne_bytes([record[6], record[7]]),
+            branch_pages: usize_at(0),
+            leaf_pages: usize_at(1),
+            overflow_pages: usize_at(2),
+            entries: usize_at(3),
+        })
+    }
+
+    pub fn is_dup_sort(&self) -> bool {
+        self.flags & MDB_DUPSORT != 0
+    }
+
+    pub fn is_reverse_key(&self) -> bool {
+        self.flags & MDB_REVERSEKEY != 0
+    }
+
+    /// The number of pages of the database.
+    pub fn pages(&self) -> u64 {
+        (self.branch_pages + self.leaf_pages + self.overflow_pages) as u64
+    }
+
+    /// The number of bytes used by all the pages of the database, with the page size
+    /// of its environment, see `page_size`.
+    pub fn total_bytes(&self, page_size: u64) -> u64 {
+        self.pages() * page_size
+    }
+}
+
+/// How much of the map the environment uses, as of the last commit.
+#[derive(Debug, Clone, Copy)]
+pub struct SpaceUsage {
+    /// The bytes of the pages up to the last one used, what the data file needs.
+    pub used_bytes: u64,
+    /// The bytes of the pages of the databases, the other used pages are free.
+    pub live_bytes: u64,
+}
+
+impl SpaceUsage {
+    /// The bytes of the free pages, reused by the next writes once the read txns that can
+    /// still see them ended.
+    pub fn free_bytes(&self) -> u64 {
+        self.used_bytes.saturating_sub(self.live_bytes)
+    }
+}
+
+/// Measures the pages used by the environment, reading the records of all the databases.
+///
+/// heed's `non_free_pages_size` panics on the keys of the unnamed database that aren't UTF-8,
+/// so we sum the records of the named databases ourselves.
+pub fn space_usage(env: &Env) -> heed::Result<SpaceUsage> {
+    let (page_size, main_stat) = main_database_stat(env)?;
+    let rtxn = env.read_txn()?;
+    let mut live_pages = main_stat.pages();
+    for name in named_databases(env, &rtxn)? {
+        live_pages += database_stat(env, &rtxn, &name)?.map_or(0, |stat| stat.pages());
+    }
+    let used_pages = env.info().last_page_number as u64 + 1;
+    Ok(SpaceUsage { used_bytes: used_pages * page_size, live_bytes: live_pages * page_size })
+}
+
+/// Returns the page size the environment was created with, the one of the OS then.
+pub fn page_size(env: &Env) -> heed::Result<u64> {
+    main_database_stat(env).map(|(page_size, _)| page_size)
+}
+
+/// Returns the page size of the environment and the statistics of the unnamed database, read
+/// from the meta page of the last commit: LMDB keeps them there, not in a record.
+///
+/// The meta pages are read while another process can be writing them, a meta page that
+/// doesn't start with the magic number and version of the layout it is parsed with is
+/// `MdbError::Invalid`, like a page size that isn't a power of two.
+fn main_database_stat(env: &Env) -> heed::Result<(u64, DatabaseStat)> {
+    let word = mem::size_of::<usize>();
+    // The page header, then the magic, the version, the address and the size of the map.
+    let databases_offset = word + 8 + 4 + 4 + 2 * word;
+    // The free pages and the unnamed database records, then the last page and txn ids.
+    let meta_len = databases_offset + 2 * DATABASE_RECORD_LEN + 2 * word;
+    let invalid = || heed::Error::Mdb(heed::MdbError::Invalid);
+
+    let mut file = File::open(env.path().join("data.mdb"))?;
+    let mut read_meta = |offset: u64| -> heed::Result<Vec<u8>> {
+        let mut meta = vec![0; meta_len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut meta)?;
+        let u32_at = |offset: usize| u32::from_ne_bytes(meta[offset..][..4].try_into().unwrap());
+        if u32_at(word + 8) != MDB_MAGIC || u32_at(word + 12) != MDB_DATA_VERSION {
+            return Err(invalid());
+        }
+        Ok(meta)
+    };
+    // The page size is stored in the padding of the free pages record.
+    let first = read_meta(0)?;
+    let page_size = u32::from_ne_bytes(first[databases_offset..][..4].try_into().unwrap());
+    if !page_size.is_power_of_two() || (page_size as usize) < meta_len {
+        return Err(invalid());
+    }
+    let second = read_meta(page_size as u64)?;
+
+    // The two meta pages are written in turn, the one of the last commit has the larger txn id.
+    let txn_id = |meta: &[u8]| usize::from_ne_bytes(meta[meta_len - word..].try_into().unwrap());
+    let meta = if txn_id(&second) > txn_id(&first) { second } else { first };
+    let record = &meta[databases_offset + DATABASE_RECORD_LEN..][..DATABASE_RECORD_LEN];
+    let stat = DatabaseStat::from_record(record).ok_or_else(invalid)?;
+    Ok((page_size as u64, stat))
+}
+
+/// Returns the statistics of a named database as of the last commit, LMDB
+/// only updates the records of the databases modified by a write txn on commit.
+///
+/// heed doesn't expose `mdb_stat`, so we read the record from the unnamed database.
+pub fn database_stat(env: &Env, rtxn: &RoTxn, name: &str) -> heed::Result<Option<DatabaseStat>> {
+    let main_db: Database<ByteSlice, ByteSlice> = match env.open_database(rtxn, None)? {
+        Some(database) => database,
+        None => return Ok(None),
+    };
+    Ok(main_db.get(rtxn, name.as_bytes())?.and_then(DatabaseStat::from_record))
+}
+
+/// Returns whether the named database was created with `MDB_DUPSORT` elsewhere,
+/// heed can't create such databases but it can open and edit them.
+pub fn is_dup_sort(env: &Env, rtxn: &RoTxn, name: &str) -> bool {
+    matches!(database_stat(env, rtxn, name), Ok(Some(stat)) if stat.is_dup_sort())
+}
+
+/// Returns whether the named database was created with `MDB_REVERSEKEY` elsewhere.
+///
+/// LMDB compares its keys from the end with the flag stored in its record, whatever the
+/// flags it is opened with, but the keys next to each other then share a suffix rather
+/// than a prefix and the prefix and range logic must know about it.
+pub fn is_reverse_key(env: &Env, rtxn: &RoTxn, name: &str) -> bool {
+    matches!(database_stat(env, rtxn, name), Ok(Some(stat)) if stat.is_reverse_key())
+}
+
+/// Compares two keys the way LMDB does, from their end when `reverse_key` is set.
+pub fn compare_keys(a: &[u8], b: &[u8], reverse_key: bool) -> Ordering {
+    if reverse_key {
+        a.iter().rev().cmp(b.iter().rev())
+    } else {
+        a.cmp(b)
+    }
+}
+
+/// Entries borrowed from a txn, in some order.
+pub type Entries<'t> = Box<dyn Iterator<Item = heed::Result<(&'t [u8], &'t [u8])>> + 't>;
+
+/// The entries whose key starts with `prefix`, in ascending or descending order.
+///
+/// In a `MDB_REVERSEKEY` database the entries next to each other share the end of their
+/// key, the `prefix` is then matched at the end of the keys, they are collected to be
+/// listed in descending order.
+pub fn prefix_entries<'t>(
+    database: &Database<ByteSlice, ByteSlice>,
+    rtxn: &'t RoTxn,
+    prefix: &[u8],
+    reverse: bool,
+    reverse_key: bool,
+) -> heed::Result<Entries<'t>> {
+    if !reverse_key {
+        return if reverse {
+            Ok(Box::new(database.rev_prefix_iter(rtxn, prefix)?))
+        } else {
+            Ok(Box::new(database.prefix_iter(rtxn, prefix)?))
+        };
+    }
+
+    let suffix = prefix.to_vec();
+    let entries = database.range(rtxn, &(Bound::Included(prefix), Bound::Unbounded))?.take_while(
+        move |entry| match entry {
+            Ok((key, _)) => key.ends_with(&suffix),
+            Err(_) => true,
+        },
+    );
+    if reverse {
+        let entries: Vec<_> = entries.collect();
+        Ok(Box::new(entries.into_iter().rev()))
+    } else {
+        Ok(Box::new(entries))
+    }
+}
+
+/// Returns whether a `MDB_DUPSORT` database has the key and data pair, LMDB doesn't add
+/// the pairs it already has.
+pub fn has_duplicate(
+    database: &Database<ByteSlice, ByteSlice>,
+    rtxn: &RoTxn,
+    key: &[u8],
+    data: &[u8],
+) -> heed::Result<bool> {
+    for entry in database.range(rtxn, &(Bound::Included(key), Bound::Included(key)))? {
+        let (entry_key, entry_data) = entry?;
+        if entry_key != key {
+            break;
+        }
+        if entry_data == data {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Deletes a single key and data pair of a `MDB_DUPSORT` database,
+/// where `Database::delete` would remove all the data of the key.
+pub fn delete_duplicate(
+    database: &Database<ByteSlice, ByteSlice>,
+    wtxn: &mut RwTxn,
+    key: &[u8],
+    data: &[u8],
+) -> heed::Result<bool> {
+    let mut iter = database.prefix_iter_mut(wtxn, key)?;
+    while let Some((entry_key, entry_data)) = iter.next().transpose()? {
+        if entry_key == key && entry_data == data {
+            // Safety: we don't keep any reference to the entries of the database.
+            return unsafe { iter.del_current() };
+        }
+    }
+    Ok(false)
+}
+
+/// Deletes up to `max` entries whose key starts with `prefix` and returns how many were
+/// deleted, there are none left when it is less than `max`.
+///
+/// The `prefix` is matched at the end of the keys of a `MDB_REVERSEKEY` database,
+/// like in `prefix_entries`.
+pub fn delete_prefix(
+    database: &Database<ByteSlice, ByteSlice>,
+    wtxn: &mut RwTxn,
+    prefix: &[u8],
+    max: u64,
+    reverse_key: bool,
+) -> heed::Result<u64> {
+    let mut deleted = 0;
+    if reverse_key {
+        let mut iter = database.range_mut(wtxn, &(Bound::Included(prefix), Bound::Unbounded))?;
+        while deleted < max {
+            match iter.next().transpose()? {
+                Some((key, _)) if key.ends_with(prefix) => (),
+                _ => break,
+            }
+            // Safety: we don't keep any reference to the entries of the database.
+            unsafe { iter.del_current()? };
+            deleted += 1;
+        }
+        return Ok(deleted);
+    }
+
+    let mut iter = database.prefix_iter_mut(wtxn, prefix)?;
+    while deleted < max && iter.next().transpose()?.is_some() {
+        // Safety: we don't keep any reference to the entries of the database.
+        unsafe { iter.del_current()? };
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+/// Lists the names of the named databases, they are stored as keys in the unnamed one.
+///
+/// The unnamed database can also hold user data, so we only keep the keys that are valid
+/// database names, with a value that looks like a database record, and that LMDB opens.
+pub fn named_databases(env: &Env, rtxn: &RoTxn) -> heed::Result<Vec<String>> {
+    let main_db: Database<ByteSlice, ByteSlice> = match env.open_database(rtxn, None)? {
+        Some(database) => database,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut names = Vec::new();
+    for result in main_db.iter(rtxn)? {
+        let (key, data) = result?;
+        let name = match str::from_utf8(key) {
+            Ok(name) if !name.contains('\0') && data.len() == DATABASE_RECORD_LEN => name,
+            _ => continue,
+        };
+
+        // Opening a key that holds user data fails with an incompatible error.
+        if let Ok(Some(_)) = env.open_database::<ByteSlice, ByteSlice>(rtxn, Some(name)) {
+            names.push(name.to_owned());
+        }
+    }
+
+    Ok(names)
+}
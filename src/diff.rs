@@ -0,0 +1,150 @@
+use std::cmp::Ordering;
+
+use heed::types::ByteSlice;
+use heed::{Database, RoTxn};
+
+use crate::databases;
+
+/// The maximum number of differences listed, the comparison stops after them.
+const MAX_DIFFERENCES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    A,
+    B,
+}
+
+#[derive(Debug)]
+pub enum Difference {
+    /// An entry only one side has.
+    OnlyIn { side: Side, key: Vec<u8>, data: Vec<u8> },
+    /// A key both sides have with different data.
+    Changed { key: Vec<u8>, a: Vec<u8>, b: Vec<u8> },
+}
+
+#[derive(Debug)]
+pub struct DatabaseDiff {
+    pub differences: Vec<Difference>,
+    /// Whether there are more differences than the ones listed.
+    pub truncated: bool,
+}
+
+/// Compares two databases by walking both of them in order at the same time, LMDB sorts
+/// the keys, which only keeps the differences in memory.
+///
+/// When `pairs` is set, for the dup-sort databases, the key and data pairs are compared
+/// instead of the keys, a key with different data is then on both sides.
+/// Both databases must compare their keys the same way, from the end with `reverse_key`.
+pub fn diff(
+    a: &Database<ByteSlice, ByteSlice>,
+    b: &Database<ByteSlice, ByteSlice>,
+    rtxn: &RoTxn,
+    pairs: bool,
+    reverse_key: bool,
+) -> heed::Result<DatabaseDiff> {
+    let compare = |a_key: &[u8], b_key: &[u8]| databases::compare_keys(a_key, b_key, reverse_key);
+    let mut a_entries = a.iter(rtxn)?.peekable();
+    let mut b_entries = b.iter(rtxn)?.peekable();
+    let mut differences = Vec::new();
+
+    loop {
+        if differences.len() == MAX_DIFFERENCES {
+            let truncated = a_entries.peek().is_some() || b_entries.peek().is_some();
+            return Ok(DatabaseDiff { differences, truncated });
+        }
+        let ordering = match (a_entries.peek(), b_entries.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(Err(_)), _) => return Err(a_entries.next().unwrap().unwrap_err()),
+            (_, Some(Err(_))) => return Err(b_entries.next().unwrap().unwrap_err()),
+            (Some(Ok((a_key, a_data))), Some(Ok((b_key, b_data)))) if pairs => {
+                compare(a_key, b_key).then_with(|| a_data.cmp(b_data))
+            }
+            (Some(Ok((a_key, _))), Some(Ok((b_key, _)))) => compare(a_key, b_key),
+        };
+        match ordering {
+            Ordering::Less => {
+                let (key, data) = a_entries.next().unwrap()?;
+                differences.push(Difference::OnlyIn {
+                    side: Side::A,
+                    key: key.to_vec(),
+                    data: data.to_vec(),
+                });
+            }
+            Ordering::Greater => {
+                let (key, data) = b_entries.next().unwrap()?;
+                differences.push(Difference::OnlyIn {
+                    side: Side::B,
+                    key: key.to_vec(),
+                    data: data.to_vec(),
+                });
+            }
+            Ordering::Equal => {
+                let (key, a_data) = a_entries.next().unwrap()?;
+                let (_, b_data) = b_entries.next().unwrap()?;
+                if a_data != b_data {
+                    differences.push(Difference::Changed {
+                        key: key.to_vec(),
+                        a: a_data.to_vec(),
+                        b: b_data.to_vec(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(DatabaseDiff { differences, truncated: false })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, DatabaseDiff, Difference, MAX_DIFFERENCES};
+    use crate::test_env::TestEnv;
+
+    /// The differences as text, the entries only on one side prefixed by their side.
+    fn described(diff: &DatabaseDiff) -> Vec<String> {
+        let text = |bytes: &[u8]| String::from_utf8(bytes.to_vec()).unwrap();
+        let describe = |difference: &Difference| match difference {
+            Difference::OnlyIn { side, key, data } => {
+                format!("{side:?} {}={}", text(key), text(data))
+            }
+            Difference::Changed { key, a, b } => format!("{} {}→{}", text(key), text(a), text(b)),
+        };
+        diff.differences.iter().map(describe).collect()
+    }
+
+    #[test]
+    fn lists_the_entries_only_on_one_side_and_the_changed_ones() {
+        let env = TestEnv::new("diff");
+        let a = env.database(Some("a"), &[("k1", "1"), ("k2", "2"), ("k3", "3")]);
+        let b = env.database(Some("b"), &[("k2", "2"), ("k3", "x"), ("k4", "4")]);
+        let rtxn = env.read_txn().unwrap();
+        let differences = diff(&a, &b, &rtxn, false, false).unwrap();
+        assert_eq!(described(&differences), ["A k1=1", "k3 3→x", "B k4=4"]);
+        assert!(!differences.truncated);
+    }
+
+    #[test]
+    fn compares_the_pairs_of_a_dup_sort_database() {
+        let env = TestEnv::with_dup_sort("diff-pairs", "a", &[("k", "1"), ("k", "2"), ("l", "3")]);
+        let a = env.dup_sort_database("a");
+        let b = env.database(Some("b"), &[("k", "2"), ("l", "4")]);
+        let rtxn = env.read_txn().unwrap();
+        let differences = diff(&a, &b, &rtxn, true, false).unwrap();
+        assert_eq!(described(&differences), ["A k=1", "A l=3", "B l=4"]);
+    }
+
+    #[test]
+    fn stops_after_the_maximum_number_of_differences() {
+        let env = TestEnv::new("diff-truncated");
+        let keys: Vec<_> = (0..=MAX_DIFFERENCES).map(|i| format!("{i:04}")).collect();
+        let entries: Vec<_> = keys.iter().map(|key| (key.as_str(), "")).collect();
+        let a = env.database(Some("a"), &entries);
+        let b = env.database(Some("b"), &[]);
+        let rtxn = env.read_txn().unwrap();
+        let differences = diff(&a, &b, &rtxn, false, false).unwrap();
+        assert_eq!(differences.differences.len(), MAX_DIFFERENCES);
+        assert!(differences.truncated);
+    }
+}
@@ -0,0 +1,90 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// How raw bytes are turned into text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValueEncoding {
+    #[default]
+    Stfu8,
+    Hex,
+    Base64,
+    /// Replaces the invalid UTF-8 sequences, it only round-trips valid UTF-8.
+    Utf8Lossy,
+}
+
+impl ValueEncoding {
+    pub const ALL: [ValueEncoding; 4] =
+        [ValueEncoding::Stfu8, ValueEncoding::Hex, ValueEncoding::Base64, ValueEncoding::Utf8Lossy];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ValueEncoding::Stfu8 => "STFU-8",
+            ValueEncoding::Hex => "hex",
+            ValueEncoding::Base64 => "base64",
+            ValueEncoding::Utf8Lossy => "UTF-8 (lossy)",
+        }
+    }
+
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            ValueEncoding::Stfu8 => stfu8::encode_u8_pretty(bytes),
+            ValueEncoding::Hex => hex::encode(bytes),
+            ValueEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+            ValueEncoding::Utf8Lossy => String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
+
+    pub fn decode(self, text: &str) -> anyhow::Result<Vec<u8>> {
+        match self {
+            ValueEncoding::Stfu8 => Ok(stfu8::decode_u8(text)?),
+            ValueEncoding::Hex => Ok(hex::decode(text)?),
+            ValueEncoding::Base64 => Ok(base64::engine::general_purpose::STANDARD.decode(text)?),
+            ValueEncoding::Utf8Lossy => Ok(text.as_bytes().to_vec()),
+        }
+    }
+}
+
+/// Shows valid UTF-8 as it is, only escaping the control characters other than the
+/// new lines and tabs, returns `None` when the bytes aren't valid UTF-8.
+pub fn plain_utf8(bytes: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    let mut plain = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_control() && c != '\n' && c != '\t' {
+            plain.extend(c.escape_default());
+        } else {
+            plain.push(c);
+        }
+    }
+    Some(plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plain_utf8, ValueEncoding};
+
+    #[test]
+    fn round_trips_the_bytes() {
+        let bytes = b"key\x00\xff\n\"\\";
+        for encoding in [ValueEncoding::Stfu8, ValueEncoding::Hex, ValueEncoding::Base64] {
+            assert_eq!(encoding.decode(&encoding.encode(bytes)).unwrap(), bytes);
+        }
+        let text = "é\tñ".as_bytes();
+        let lossy = ValueEncoding::Utf8Lossy;
+        assert_eq!(lossy.decode(&lossy.encode(text)).unwrap(), text);
+        assert_eq!(lossy.encode(b"\xff"), "\u{fffd}");
+    }
+
+    #[test]
+    fn rejects_the_invalid_text() {
+        assert!(ValueEncoding::Hex.decode("abc").is_err());
+        assert!(ValueEncoding::Base64.decode("a").is_err());
+        assert!(ValueEncoding::Stfu8.decode("\\x").is_err());
+    }
+
+    #[test]
+    fn escapes_the_control_characters_of_plain_utf8() {
+        assert_eq!(plain_utf8(b"a\tb\nc\x07").as_deref(), Some("a\tb\nc\\u{7}"));
+        assert_eq!(plain_utf8(b"\xff"), None);
+    }
+}
@@ -0,0 +1,38 @@
+pub trait OrReport<T> {
+    /// Returns the successful value or pushes a message describing the error,
+    /// prefixed by `context`, into the `errors` to display to the user. An error
+    /// already displayed isn't pushed again, the UI reports some every frame they last.
+    fn or_report(self, errors: &mut Vec<String>, context: &str) -> Option<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> OrReport<T> for Result<T, E> {
+    fn or_report(self, errors: &mut Vec<String>, context: &str) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(error) => {
+                let message = format!("{context}: {}", describe(&error.into()));
+                if !errors.contains(&message) {
+                    errors.push(message);
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Returns whether the error is that the map of the environment is full,
+/// it can be grown by reopening the environment.
+pub fn is_map_full(error: &heed::Error) -> bool {
+    matches!(error, heed::Error::Mdb(heed::MdbError::MapFull))
+}
+
+fn describe(error: &anyhow::Error) -> String {
+    use heed::{Error, MdbError};
+
+    match error.downcast_ref::<Error>() {
+        Some(Error::Mdb(MdbError::MapFull)) => {
+            "environment map full, reopen it with a larger map size".to_owned()
+        }
+        _ => format!("{error:#}"),
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Debug, Default)]
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::ValueEncoding;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct EscapedEntry {
     pub key: String,
     pub data: String,
@@ -10,11 +14,21 @@ impl EscapedEntry {
         self.data.clear();
     }
 
-    pub fn decoded_key(&self) -> Result<Vec<u8>, stfu8::DecodeError> {
-        stfu8::decode_u8(&self.key)
+    pub fn decoded_key(&self, encoding: ValueEncoding) -> anyhow::Result<Vec<u8>> {
+        encoding.decode(&self.key)
+    }
+
+    pub fn decoded_data(&self, encoding: ValueEncoding) -> anyhow::Result<Vec<u8>> {
+        encoding.decode(&self.data)
     }
 
-    pub fn decoded_data(&self) -> Result<Vec<u8>, stfu8::DecodeError> {
-        stfu8::decode_u8(&self.data)
+    /// Re-encodes the key and data from one encoding to another,
+    /// the fields that can't be decoded are left untouched.
+    pub fn reencode(&mut self, from: ValueEncoding, to: ValueEncoding) {
+        for field in [&mut self.key, &mut self.data] {
+            if let Ok(bytes) = from.decode(field) {
+                *field = to.encode(&bytes);
+            }
+        }
     }
 }
@@ -0,0 +1,151 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::encoding::ValueEncoding;
+use crate::escaped_entry::EscapedEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    /// Writes the entries in this format, the CSV cells in the `encoding`.
+    pub fn write<'a>(
+        self,
+        entries: impl Iterator<Item = heed::Result<(&'a [u8], &'a [u8])>>,
+        path: &Path,
+        encoding: ValueEncoding,
+    ) -> anyhow::Result<u64> {
+        match self {
+            ExportFormat::Json => write_json(entries, path),
+            ExportFormat::Csv => write_csv(entries, path, encoding),
+        }
+    }
+}
+
+/// Writes the entries as a JSON array of escaped entries, one entry at a time, and returns
+/// the number of entries written. The file can be read back with `import::read_json`.
+pub fn write_json<'a>(
+    entries: impl Iterator<Item = heed::Result<(&'a [u8], &'a [u8])>>,
+    path: &Path,
+) -> anyhow::Result<u64> {
+    write_json_to(entries, File::create(path)?)
+}
+
+/// Writes the entries as a JSON array of escaped entries into a file already opened.
+pub fn write_json_to<'a>(
+    entries: impl Iterator<Item = heed::Result<(&'a [u8], &'a [u8])>>,
+    file: File,
+) -> anyhow::Result<u64> {
+    let mut writer = BufWriter::new(file);
+    let mut count = 0;
+
+    writer.write_all(b"[")?;
+    for result in entries {
+        let (key, data) = result?;
+        if count != 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(b"\n  ")?;
+        let entry =
+            EscapedEntry { key: stfu8::encode_u8_pretty(key), data: stfu8::encode_u8_pretty(data) };
+        serde_json::to_writer(&mut writer, &entry)?;
+        count += 1;
+    }
+    writer.write_all(b"\n]\n")?;
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Writes the entries as a two columns CSV file, one entry at a time, and returns
+/// the number of entries written.
+pub fn write_csv<'a>(
+    entries: impl Iterator<Item = heed::Result<(&'a [u8], &'a [u8])>>,
+    path: &Path,
+    encoding: ValueEncoding,
+) -> anyhow::Result<u64> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut count = 0;
+
+    writer.write_all(b"key,value\n")?;
+    for result in entries {
+        let (key, data) = result?;
+        write_csv_field(&mut writer, &encoding.encode(key))?;
+        writer.write_all(b",")?;
+        write_csv_field(&mut writer, &encoding.encode(data))?;
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Writes the field, quoted when it contains a comma, a quote or a line break.
+fn write_csv_field(writer: &mut impl Write, field: &str) -> io::Result<()> {
+    if field.contains([',', '"', '\n', '\r']) {
+        write!(writer, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        writer.write_all(field.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{write_csv, write_csv_field, write_json};
+    use crate::encoding::ValueEncoding;
+    use crate::import::read_json;
+
+    fn quoted(field: &str) -> String {
+        let mut written = Vec::new();
+        write_csv_field(&mut written, field).unwrap();
+        String::from_utf8(written).unwrap()
+    }
+
+    #[test]
+    fn quotes_the_csv_fields_when_needed() {
+        assert_eq!(quoted("plain"), "plain");
+        assert_eq!(quoted("a,b"), "\"a,b\"");
+        assert_eq!(quoted("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quoted("two\nlines"), "\"two\nlines\"");
+        assert_eq!(quoted("\r"), "\"\r\"");
+    }
+
+    #[test]
+    fn writes_the_files_the_import_reads() {
+        let entries: [(&[u8], &[u8]); 2] = [(b"k,1", b"\x00\xff"), (b"k2", b"say \"hi\"")];
+        let path = std::env::temp_dir().join(format!("lmdb-editor-export-{}", std::process::id()));
+
+        let written =
+            write_csv(entries.iter().map(|&entry| Ok(entry)), &path, ValueEncoding::Stfu8);
+        assert_eq!(written.unwrap(), 2);
+        let csv = fs::read_to_string(&path).unwrap();
+        assert_eq!(csv, "key,value\n\"k,1\",\\x00\\xFF\nk2,\"say \"\"hi\"\"\"\n");
+
+        assert_eq!(write_json(entries.iter().map(|&entry| Ok(entry)), &path).unwrap(), 2);
+        let read = read_json(&path).unwrap();
+        let expected: Vec<_> = entries.iter().map(|(k, d)| (k.to_vec(), d.to_vec())).collect();
+        assert_eq!(read, expected);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Context;
+use heed::types::ByteSlice;
+use heed::{Database, RwTxn};
+
+use crate::{databases, export, import};
+
+/// The number of entries that can be edited at once, the file is read back in memory.
+const MAX_EDITED_ENTRIES: usize = 100_000;
+
+/// The number of names tried for the temporary file before giving up.
+const MAX_FILE_ATTEMPTS: u64 = 100;
+
+/// Tells apart the files of the edits of this process, of several environments or databases
+/// with the same name.
+static NEXT_FILE: AtomicU64 = AtomicU64::new(0);
+
+pub enum EditState {
+    /// The editor is running on the file.
+    Editing(Child),
+    /// The editor exited and the file was read back.
+    Edited(EditChanges),
+    /// The editor failed or the file can't be read back, it can be edited again.
+    Failed(String),
+}
+
+/// The entries to delete and to put, what differs between the file and the entries
+/// written to it.
+pub struct EditChanges {
+    pub deletions: Vec<(Vec<u8>, Vec<u8>)>,
+    pub puts: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Entries written to a temporary JSON file, like the JSON export, for the user to edit
+/// with `$VISUAL` or `$EDITOR`.
+pub struct ExternalEdit {
+    pub path: PathBuf,
+    /// The entries as they were written to the file.
+    original: Vec<(Vec<u8>, Vec<u8>)>,
+    pub state: EditState,
+}
+
+impl ExternalEdit {
+    /// Writes the `entries` to a temporary file named after the database and opens it
+    /// in the editor.
+    pub fn start<'a>(
+        entries: impl Iterator<Item = heed::Result<(&'a [u8], &'a [u8])>>,
+        name: &str,
+    ) -> anyhow::Result<ExternalEdit> {
+        let mut original = Vec::new();
+        for entry in entries {
+            if original.len() == MAX_EDITED_ENTRIES {
+                anyhow::bail!(
+                    "more than {MAX_EDITED_ENTRIES} entries, go to a key prefix to edit fewer"
+                );
+            }
+            let (key, data) = entry?;
+            original.push((key.to_vec(), data.to_vec()));
+        }
+
+        let (path, file) = create_temp_file(name).context("creating the file to edit")?;
+        let entries = original.iter().map(|(key, data)| Ok((key.as_slice(), data.as_slice())));
+        if let Err(e) = export::write_json_to(entries, file) {
+            let _ = std::fs::remove_file(&path);
+            return Err(e.context("writing the entries to edit"));
+        }
+        let child = launch_editor(&path)?;
+        Ok(ExternalEdit { path, original, state: EditState::Editing(child) })
+    }
+
+    /// Opens the file in the editor again, after it failed or to fix the file.
+    pub fn edit_again(&mut self) -> anyhow::Result<()> {
+        self.state = EditState::Editing(launch_editor(&self.path)?);
+        Ok(())
+    }
+
+    /// Reads the file back once the editor exited, the changes are empty when it wasn't saved.
+    pub fn poll(&mut self) {
+        let EditState::Editing(child) = &mut self.state else { return };
+        self.state = match child.try_wait() {
+            Ok(None) => return,
+            Ok(Some(status)) if !status.success() => {
+                EditState::Failed(format!("the editor exited with {status}"))
+            }
+            Ok(Some(_)) => match self.read_changes() {
+                Ok(changes) => EditState::Edited(changes),
+                Err(e) => EditState::Failed(format!("{e:#}")),
+            },
+            Err(e) => EditState::Failed(format!("waiting for the editor: {e}")),
+        };
+    }
+
+    fn read_changes(&self) -> anyhow::Result<EditChanges> {
+        let edited = import::read_json(&self.path)?;
+        let original: HashSet<_> = self.original.iter().collect();
+        let kept: HashSet<_> = edited.iter().collect();
+        let deletions =
+            self.original.iter().filter(|entry| !kept.contains(entry)).cloned().collect();
+        let puts = edited.iter().filter(|entry| !original.contains(entry)).cloned().collect();
+        Ok(EditChanges { deletions, puts })
+    }
+}
+
+impl EditChanges {
+    pub fn is_empty(&self) -> bool {
+        self.deletions.is_empty() && self.puts.is_empty()
+    }
+
+    /// Deletes the entries removed from the file then puts the added ones, the data of a
+    /// key that isn't a duplicate is replaced instead of deleted when the key is put again.
+    /// Returns the number of entries put and deleted.
+    pub fn apply(
+        &self,
+        database: &Database<ByteSlice, ByteSlice>,
+        wtxn: &mut RwTxn,
+        dup_sort: bool,
+    ) -> heed::Result<(u64, u64)> {
+        let put_keys: HashSet<_> = self.puts.iter().map(|(key, _)| key).collect();
+        let mut deleted = 0;
+        for (key, data) in &self.deletions {
+            let done = if dup_sort {
+                databases::delete_duplicate(database, wtxn, key, data)?
+            } else if put_keys.contains(key) {
+                continue;
+            } else {
+                database.delete(wtxn, key)?
+            };
+            deleted += u64::from(done);
+        }
+        for (key, data) in &self.puts {
+            database.put(wtxn, key, data)?;
+        }
+        Ok((self.puts.len() as u64, deleted))
+    }
+}
+
+impl Drop for ExternalEdit {
+    /// The file is left to the editor still running on it.
+    fn drop(&mut self) {
+        if !matches!(self.state, EditState::Editing(_)) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Creates a temporary file named after the database that didn't exist, the temporary
+/// folder is shared: a file or a symlink already there could be another user's.
+fn create_temp_file(name: &str) -> io::Result<(PathBuf, File)> {
+    // The names of the databases can have any character.
+    let name: String = name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect();
+    for _ in 0..MAX_FILE_ATTEMPTS {
+        let n = NEXT_FILE.fetch_add(1, Ordering::Relaxed);
+        let file_name = format!("lmdb-editor-{}-{n}-{name}.json", std::process::id());
+        let path = std::env::temp_dir().join(file_name);
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        // The entries are only for the user to read.
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "all the file names tried are taken"))
+}
+
+/// Runs `$VISUAL` or `$EDITOR` on the file, the command can have arguments,
+/// like `code --wait`.
+fn launch_editor(path: &std::path::Path) -> anyhow::Result<Child> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .context("set $EDITOR to the command of a text editor")?;
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("$EDITOR is empty")?;
+    Command::new(program).args(words).arg(path).spawn().with_context(|| format!("running {editor}"))
+}
@@ -0,0 +1,178 @@
+use heed::types::ByteSlice;
+use heed::{Database, RoTxn};
+
+use crate::databases::{self, Entries};
+use crate::row_cache::KeyStart;
+
+/// Returns whether the whole `text` matches the glob `pattern`, where `*` matches any
+/// sequence of characters and `?` any single one. There is no escaping, the backslash is
+/// already the escape of STFU-8.
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` is in the pattern and the text it matches up to, to backtrack.
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // The last `*` matches one more character.
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The part of the `pattern` before its first wildcard, all the matching texts start with it.
+pub fn literal_prefix(pattern: &str) -> &str {
+    let end = pattern.find(['*', '?']).unwrap_or(pattern.len());
+    &pattern[..end]
+}
+
+/// Scans a database for the entries whose key matches, a bounded number of entries per
+/// frame to keep the UI responsive on large databases, resuming where it stopped.
+#[derive(Debug)]
+pub struct GlobScan {
+    /// What the entries were scanned from, see `TreeBehavior::entries_version`.
+    pub version: (u64, u64, u64),
+    /// The bytes all the matching keys start with, the scan seeks to them and stops after them.
+    seek: Vec<u8>,
+    /// The last scanned key and the number of its entries scanned so far,
+    /// a dup-sort database can store many entries under a key.
+    resume: Option<(KeyStart, usize)>,
+    /// The number of entries scanned so far.
+    pub scanned: u64,
+    pub matches: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Whether all the entries that could match were scanned.
+    pub done: bool,
+}
+
+impl GlobScan {
+    pub fn new(version: (u64, u64, u64), seek: Vec<u8>) -> GlobScan {
+        GlobScan { version, seek, resume: None, scanned: 0, matches: Vec::new(), done: false }
+    }
+
+    /// Scans up to `budget` more entries, until there are `wanted` matches.
+    pub fn step(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &RoTxn,
+        reverse: bool,
+        budget: usize,
+        wanted: usize,
+        is_match: impl Fn(&[u8]) -> bool,
+    ) -> heed::Result<()> {
+        let (entries, skip): (Entries, usize) = match &self.resume {
+            Some((key_start, scanned)) if !key_start.starts_at_end(reverse) => {
+                (Box::new(key_start.entries(database, rtxn, reverse)?), *scanned)
+            }
+            // The rows of the first key in descending order are where the scan started.
+            Some((_, scanned)) => {
+                (databases::prefix_entries(database, rtxn, &self.seek, reverse, false)?, *scanned)
+            }
+            None => (databases::prefix_entries(database, rtxn, &self.seek, reverse, false)?, 0),
+        };
+
+        let mut budget = budget;
+        for entry in entries.skip(skip) {
+            if budget == 0 || self.matches.len() >= wanted {
+                return Ok(());
+            }
+            let (key, data) = entry?;
+            if !key.starts_with(&self.seek) {
+                break;
+            }
+            self.resume = match self.resume.take() {
+                Some((last, scanned)) if last.key == key => Some((last, scanned + 1)),
+                last => {
+                    let key_before = last.as_ref().map(|(last, _)| last.key.as_slice());
+                    Some((KeyStart::new(key, key_before, reverse), 1))
+                }
+            };
+            if is_match(key) {
+                self.matches.push((key.to_vec(), data.to_vec()));
+            }
+            self.scanned += 1;
+            budget -= 1;
+        }
+        self.done = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{literal_prefix, matches, GlobScan};
+    use crate::test_env::TestEnv;
+
+    #[test]
+    fn matches_the_wildcards() {
+        assert!(matches("user:*", "user:42"));
+        assert!(matches("user:*", "user:"));
+        assert!(matches("?é?", "aéb"));
+        assert!(!matches("?é?", "aé"));
+        assert!(matches("a*b*c", "axxbyybc"));
+        assert!(!matches("a*b*c", "axxbyybcd"));
+        assert!(matches("**", ""));
+        assert!(!matches("", "a"));
+        assert!(!matches("user", "user:1"));
+    }
+
+    #[test]
+    fn finds_the_literal_prefix() {
+        assert_eq!(literal_prefix("user:*:name"), "user:");
+        assert_eq!(literal_prefix("a?b"), "a");
+        assert_eq!(literal_prefix("*"), "");
+        assert_eq!(literal_prefix("user"), "user");
+    }
+
+    #[test]
+    fn resumes_the_scan_in_the_duplicates_of_a_key() {
+        let entries = [
+            ("a:1", "x"),
+            ("a:1", "y"),
+            ("a:1", "z"),
+            ("a:10", "x"),
+            ("a:2", "x"),
+            ("a:3", "x"),
+            ("a:3", "y"),
+            ("b:1", "x"),
+        ];
+        let env = TestEnv::with_dup_sort("glob", "dups", &entries);
+        let database = env.dup_sort_database("dups");
+        let rtxn = env.read_txn().unwrap();
+        let is_match = |key: &[u8]| matches("a:?", std::str::from_utf8(key).unwrap());
+        let mut expected: Vec<_> = entries
+            .iter()
+            .filter(|(key, _)| is_match(key.as_bytes()))
+            .map(|(key, data)| (key.as_bytes().to_vec(), data.as_bytes().to_vec()))
+            .collect();
+
+        for reverse in [false, true] {
+            let mut scan = GlobScan::new((0, 0, 0), b"a:".to_vec());
+            let mut steps = 0;
+            while !scan.done {
+                scan.step(&database, &rtxn, reverse, 2, usize::MAX, is_match).unwrap();
+                steps += 1;
+            }
+            assert_eq!(scan.matches, expected);
+            assert_eq!(scan.scanned, 7);
+            assert_eq!(steps, 4);
+            expected.reverse();
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/// The number of bytes shown on each line of a hex dump.
+pub const BYTES_PER_LINE: usize = 16;
+
+/// Returns the number of lines of the hex dump of `len` bytes.
+pub fn num_lines(len: usize) -> usize {
+    len.div_ceil(BYTES_PER_LINE)
+}
+
+/// Formats a line of a classic hex dump: the offset, up to
+/// 16 hex bytes and an ASCII gutter with dots for non-printables.
+pub fn line(bytes: &[u8], line_index: usize) -> String {
+    let offset = line_index * BYTES_PER_LINE;
+    let end = bytes.len().min(offset + BYTES_PER_LINE);
+    let chunk = &bytes[offset..end];
+
+    let mut line = format!("{offset:08x}  ");
+    for i in 0..BYTES_PER_LINE {
+        match chunk.get(i) {
+            Some(byte) => line.push_str(&format!("{byte:02x} ")),
+            None => line.push_str("   "),
+        }
+        // An extra space splits the bytes in two groups of eight.
+        if i == BYTES_PER_LINE / 2 - 1 {
+            line.push(' ');
+        }
+    }
+
+    line.push(' ');
+    line.push('|');
+    line.extend(
+        chunk.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }),
+    );
+    line.push('|');
+    line
+}
@@ -0,0 +1,85 @@
+use anyhow::Context;
+use eframe::egui;
+use egui::Color32;
+
+use crate::hex_dump::BYTES_PER_LINE;
+
+/// Edits bytes as a grid of hex cells, like a hex editor.
+pub struct HexEditor {
+    /// The text of the cells, one per byte, a cell may not be a valid byte while it is edited.
+    cells: Vec<String>,
+    /// The index of the cell the bytes are inserted before and deleted at.
+    cursor: usize,
+}
+
+impl HexEditor {
+    pub fn new(bytes: &[u8]) -> HexEditor {
+        HexEditor { cells: bytes.iter().map(|byte| format!("{byte:02x}")).collect(), cursor: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the edited bytes, fails when a cell isn't a hex byte.
+    pub fn bytes(&self) -> anyhow::Result<Vec<u8>> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                u8::from_str_radix(cell, 16)
+                    .with_context(|| format!("byte {i} is not hex: {cell:?}"))
+            })
+            .collect()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, id_source: impl std::hash::Hash) {
+        ui.horizontal(|ui| {
+            ui.label(format!("{} bytes", self.cells.len()));
+            if ui.button("insert").on_hover_text("insert a byte before the cursor").clicked() {
+                self.cells.insert(self.cursor, "00".to_owned());
+            }
+            let delete =
+                ui.add_enabled(self.cursor < self.cells.len(), egui::Button::new("delete"));
+            if delete.on_hover_text("delete the byte at the cursor").clicked() {
+                self.cells.remove(self.cursor);
+            }
+            if ui.button("append").on_hover_text("append a byte").clicked() {
+                self.cells.push("00".to_owned());
+                self.cursor = self.cells.len() - 1;
+            }
+        });
+        self.cursor = self.cursor.min(self.cells.len());
+
+        let id = egui::Id::new(id_source);
+        egui::ScrollArea::vertical().id_source(id.with("scroll")).max_height(200.0).show(
+            ui,
+            |ui| {
+                egui::Grid::new(id.with("grid")).spacing([2.0, 2.0]).show(ui, |ui| {
+                    for (i, cell) in self.cells.iter_mut().enumerate() {
+                        let valid = u8::from_str_radix(cell, 16).is_ok();
+                        let edit = egui::TextEdit::singleline(cell)
+                            .char_limit(2)
+                            .desired_width(18.0)
+                            .font(egui::TextStyle::Monospace)
+                            .text_color_opt((!valid).then_some(Color32::RED));
+                        let response = ui.add(edit);
+                        if response.gained_focus() || response.clicked() {
+                            self.cursor = i;
+                        }
+                        if i == self.cursor {
+                            ui.painter().rect_stroke(
+                                response.rect,
+                                2.0,
+                                ui.visuals().selection.stroke,
+                            );
+                        }
+                        if (i + 1) % BYTES_PER_LINE == 0 {
+                            ui.end_row();
+                        }
+                    }
+                });
+            },
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::Context;
+use heed::types::ByteSlice;
+use heed::{Database, MdbError, RoTxn, RwTxn};
+
+use crate::encoding::ValueEncoding;
+use crate::escaped_entry::EscapedEntry;
+use crate::key_type::KeyType;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImportSummary {
+    pub inserted: u64,
+    pub skipped: u64,
+}
+
+/// What an import would do to the existing keys, counted without writing anything.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImportPreview {
+    pub new: u64,
+    /// The existing keys with another data.
+    pub different: u64,
+    /// The entries already in the database.
+    pub identical: u64,
+}
+
+impl ImportPreview {
+    /// Counts an imported entry, `existing` is the data of its key in the database.
+    pub fn count(&mut self, existing: Option<&[u8]>, data: &[u8]) {
+        match existing {
+            None => self.new += 1,
+            Some(existing) if existing == data => self.identical += 1,
+            Some(_) => self.different += 1,
+        }
+    }
+
+    pub fn describe(&self, overwrite: bool) -> String {
+        let different = if overwrite { "to overwrite" } else { "skipped" };
+        format!(
+            "{} new keys, {} existing keys with another data {different}, {} identical entries",
+            self.new, self.different, self.identical
+        )
+    }
+}
+
+/// Reads the entries of a JSON file produced by the JSON export.
+///
+/// The whole file is decoded before anything is written, a malformed
+/// file leaves the database untouched.
+pub fn read_json(path: &Path) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let reader = BufReader::new(File::open(path)?);
+    let entries: Vec<EscapedEntry> =
+        serde_json::from_reader(reader).context("malformed JSON file")?;
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            // The exported files always use STFU-8, whatever the display encoding.
+            let key = entry.decoded_key(ValueEncoding::Stfu8);
+            let key = key.with_context(|| format!("invalid key of entry {i}"))?;
+            let data = entry.decoded_data(ValueEncoding::Stfu8);
+            let data = data.with_context(|| format!("invalid data of entry {i}"))?;
+            Ok((key, data))
+        })
+        .collect()
+}
+
+/// Reads pasted entries, one per line with the key and the data separated by a tab,
+/// in the display encoding of the table. The empty lines are ignored.
+pub fn read_pasted(
+    text: &str,
+    key_type: KeyType,
+    encoding: ValueEncoding,
+) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let line_number = i + 1;
+            let (key, data) = line
+                .split_once('\t')
+                .with_context(|| format!("line {line_number} has no tab after the key"))?;
+            let key = key_type.decode(key, encoding);
+            let key = key.with_context(|| format!("invalid key on line {line_number}"))?;
+            let data = encoding.decode(data);
+            let data = data.with_context(|| format!("invalid data on line {line_number}"))?;
+            Ok((key, data))
+        })
+        .collect()
+}
+
+/// Puts many entries into a database, in order, optionally with `MDB_APPEND`.
+///
+/// Appending is much faster but only works for keys greater than all the keys
+/// of the database, the insert stops at the first entry that isn't and can
+/// resume from there with normal puts.
+pub struct BulkInsert {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The index of the next entry to put.
+    next: usize,
+    /// Whether the existing keys are replaced, they are skipped otherwise.
+    overwrite: bool,
+    pub summary: ImportSummary,
+}
+
+impl BulkInsert {
+    pub fn new(entries: Vec<(Vec<u8>, Vec<u8>)>, overwrite: bool) -> BulkInsert {
+        BulkInsert { entries, next: 0, overwrite, summary: ImportSummary::default() }
+    }
+
+    /// The index of the next entry to put, the one out of order after a stopped append.
+    pub fn next(&self) -> usize {
+        self.next
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Looks the keys up to tell what putting the entries would do.
+    pub fn preview(
+        &self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &RoTxn,
+    ) -> heed::Result<ImportPreview> {
+        let mut preview = ImportPreview::default();
+        for (key, data) in &self.entries[self.next..] {
+            preview.count(database.get(rtxn, key)?, data);
+        }
+        Ok(preview)
+    }
+
+    /// Puts the remaining entries and returns whether they were all put, `false` when
+    /// appending stopped at an entry whose key isn't greater than the last one.
+    pub fn put_remaining(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        wtxn: &mut RwTxn,
+        append: bool,
+    ) -> heed::Result<bool> {
+        while let Some((key, data)) = self.entries.get(self.next) {
+            if !self.overwrite && database.get(wtxn, key)?.is_some() {
+                self.summary.skipped += 1;
+            } else if append {
+                match database.append(wtxn, key, data) {
+                    Ok(()) => self.summary.inserted += 1,
+                    Err(heed::Error::Mdb(MdbError::KeyExist)) => return Ok(false),
+                    Err(e) => return Err(e),
+                }
+            } else {
+                database.put(wtxn, key, data)?;
+                self.summary.inserted += 1;
+            }
+            self.next += 1;
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_pasted, BulkInsert};
+    use crate::encoding::ValueEncoding;
+    use crate::key_type::KeyType;
+    use crate::test_env::TestEnv;
+
+    fn entries(entries: &[(&str, &str)]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        entries
+            .iter()
+            .map(|(key, data)| (key.as_bytes().to_vec(), data.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_the_pasted_lines() {
+        let pasted = read_pasted("a\t1\n\nb \t\\x00\n", KeyType::Bytes, ValueEncoding::Stfu8);
+        assert_eq!(pasted.unwrap(), [(b"a".to_vec(), b"1".to_vec()), (b"b ".to_vec(), vec![0])]);
+
+        let pasted = read_pasted("7\tx\n", KeyType::U32, ValueEncoding::Stfu8).unwrap();
+        assert_eq!(pasted, [(7u32.to_ne_bytes().to_vec(), b"x".to_vec())]);
+
+        let error = read_pasted("a\t1\nb", KeyType::Bytes, ValueEncoding::Stfu8).unwrap_err();
+        assert_eq!(error.to_string(), "line 2 has no tab after the key");
+        let error = read_pasted("x\t1", KeyType::U64, ValueEncoding::Stfu8).unwrap_err();
+        assert_eq!(error.to_string(), "invalid key on line 1");
+    }
+
+    #[test]
+    fn previews_without_writing() {
+        let env = TestEnv::new("import-preview");
+        let database = env.database(None, &[("a", "1"), ("b", "2")]);
+        let insert = BulkInsert::new(entries(&[("a", "1"), ("b", "3"), ("c", "4")]), false);
+        let rtxn = env.read_txn().unwrap();
+        let preview = insert.preview(&database, &rtxn).unwrap();
+        assert_eq!((preview.new, preview.different, preview.identical), (1, 1, 1));
+        assert_eq!(
+            preview.describe(true),
+            "1 new keys, 1 existing keys with another data to overwrite, 1 identical entries"
+        );
+        assert_eq!(database.len(&rtxn).unwrap(), 2);
+    }
+
+    #[test]
+    fn resumes_with_puts_after_a_stopped_append() {
+        let env = TestEnv::new("import-append");
+        let database = env.database(None, &[("b", "1")]);
+        let mut insert =
+            BulkInsert::new(entries(&[("c", "2"), ("d", "3"), ("a", "4"), ("b", "5")]), false);
+        let mut wtxn = env.write_txn().unwrap();
+        assert!(!insert.put_remaining(&database, &mut wtxn, true).unwrap());
+        assert_eq!(insert.next(), 2);
+        assert!(insert.put_remaining(&database, &mut wtxn, false).unwrap());
+        assert_eq!((insert.summary.inserted, insert.summary.skipped), (3, 1));
+        assert_eq!(database.get(&wtxn, b"b").unwrap(), Some(&b"1"[..]));
+        assert_eq!(database.len(&wtxn).unwrap(), 4);
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::ValueEncoding;
+
+/// How the keys of a database are interpreted, `MDB_INTEGERKEY`
+/// databases store native-endian unsigned integers as keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyType {
+    #[default]
+    Bytes,
+    U32,
+    U64,
+    I32,
+    I64,
+}
+
+impl KeyType {
+    pub const ALL: [KeyType; 5] =
+        [KeyType::Bytes, KeyType::U32, KeyType::U64, KeyType::I32, KeyType::I64];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyType::Bytes => "bytes",
+            KeyType::U32 => "u32",
+            KeyType::U64 => "u64",
+            KeyType::I32 => "i32",
+            KeyType::I64 => "i64",
+        }
+    }
+
+    /// Formats an integer key in decimal, the bytes keys and the keys that
+    /// don't have the width of the integer are encoded with `encoding`.
+    pub fn encode(self, key: &[u8], encoding: ValueEncoding) -> String {
+        let integer = match self {
+            KeyType::Bytes => None,
+            KeyType::U32 => key.try_into().ok().map(|b| u32::from_ne_bytes(b).to_string()),
+            KeyType::U64 => key.try_into().ok().map(|b| u64::from_ne_bytes(b).to_string()),
+            KeyType::I32 => key.try_into().ok().map(|b| i32::from_ne_bytes(b).to_string()),
+            KeyType::I64 => key.try_into().ok().map(|b| i64::from_ne_bytes(b).to_string()),
+        };
+        integer.unwrap_or_else(|| encoding.encode(key))
+    }
+
+    /// Parses a decimal integer, surrounded by whitespace or not, into a native-endian key of
+    /// the right width, the bytes keys are decoded with `encoding` as they are.
+    pub fn decode(self, text: &str, encoding: ValueEncoding) -> anyhow::Result<Vec<u8>> {
+        let context = || format!("{text:?} is not a valid {} key", self.label());
+        let integer = text.trim();
+        match self {
+            KeyType::Bytes => encoding.decode(text),
+            KeyType::U32 => {
+                Ok(integer.parse::<u32>().with_context(context)?.to_ne_bytes().to_vec())
+            }
+            KeyType::U64 => {
+                Ok(integer.parse::<u64>().with_context(context)?.to_ne_bytes().to_vec())
+            }
+            KeyType::I32 => {
+                Ok(integer.parse::<i32>().with_context(context)?.to_ne_bytes().to_vec())
+            }
+            KeyType::I64 => {
+                Ok(integer.parse::<i64>().with_context(context)?.to_ne_bytes().to_vec())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyType;
+    use crate::encoding::ValueEncoding;
+
+    #[test]
+    fn round_trips_the_integer_keys() {
+        let keys = [
+            (KeyType::U32, "4294967295", 4),
+            (KeyType::U64, "18446744073709551615", 8),
+            (KeyType::I32, "-2147483648", 4),
+            (KeyType::I64, "-1", 8),
+        ];
+        for (key_type, text, width) in keys {
+            let key = key_type.decode(text, ValueEncoding::Stfu8).unwrap();
+            assert_eq!(key.len(), width);
+            assert_eq!(key_type.encode(&key, ValueEncoding::Stfu8), text);
+        }
+    }
+
+    #[test]
+    fn trims_the_integer_keys_only() {
+        let key = KeyType::U32.decode(" 42\n", ValueEncoding::Stfu8).unwrap();
+        assert_eq!(key, 42u32.to_ne_bytes());
+        assert_eq!(KeyType::Bytes.decode("foo ", ValueEncoding::Stfu8).unwrap(), b"foo ");
+        assert!(KeyType::Bytes.decode(" 66 ", ValueEncoding::Hex).is_err());
+    }
+
+    #[test]
+    fn encodes_the_keys_of_another_width_as_bytes() {
+        assert_eq!(KeyType::U32.encode(b"abc", ValueEncoding::Stfu8), "abc");
+        assert_eq!(KeyType::I64.encode(b"abc", ValueEncoding::Hex), "616263");
+        assert!(KeyType::U32.decode("-1", ValueEncoding::Stfu8).is_err());
+        assert!(KeyType::I32.decode("2147483648", ValueEncoding::Stfu8).is_err());
+    }
+}
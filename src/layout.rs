@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use egui_tiles::{Tile, Tiles, Tree};
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::ValueEncoding;
+use crate::key_type::KeyType;
+
+/// The part of a tab that is remembered between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SavedPane {
+    DatabaseEntries { database_name: Option<String> },
+    OpenNew,
+}
+
+/// The key under which the layout of the tabs of an environment is persisted.
+pub fn layout_key(env_path: &Path) -> String {
+    format!("layout {}", env_path.display())
+}
+
+/// How the entries of a database were last shown, it is reapplied when it is opened again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedDisplay {
+    pub encoding: ValueEncoding,
+    pub key_type: KeyType,
+}
+
+/// The displays of the databases of an environment, by name of their database.
+pub type SavedDisplays = HashMap<Option<String>, SavedDisplay>;
+
+/// The key under which the displays of the databases of an environment are persisted.
+pub fn displays_key(env_path: &Path) -> String {
+    format!("displays {}", env_path.display())
+}
+
+/// The key under which whether the modification times of an environment are recorded
+/// is persisted.
+pub fn record_timestamps_key(env_path: &Path) -> String {
+    format!("record timestamps {}", env_path.display())
+}
+
+/// The widths of the keys and values columns of the tables, by name of their database.
+pub type ColumnWidths = HashMap<Option<String>, [f32; 2]>;
+
+/// The key under which the column widths of the tables of an environment are persisted.
+pub fn column_widths_key(env_path: &Path) -> String {
+    format!("column widths {}", env_path.display())
+}
+
+/// Returns a copy of the tree with its panes mapped, the panes mapped to `None` are left out.
+///
+/// The containers keep referring to the tiles left out, the tree forgets about them when shown.
+pub fn map_panes<A, B>(tree: &Tree<A>, mut f: impl FnMut(&A) -> Option<B>) -> Tree<B> {
+    let mut tiles = Tiles::default();
+    for (&tile_id, tile) in &tree.tiles.tiles {
+        let tile = match tile {
+            Tile::Pane(pane) => match f(pane) {
+                Some(pane) => Tile::Pane(pane),
+                None => continue,
+            },
+            Tile::Container(container) => Tile::Container(container.clone()),
+        };
+        tiles.tiles.insert(tile_id, tile);
+    }
+    Tree { root: tree.root, tiles }
+}
@@ -1,357 +1,5990 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
+use std::collections::{HashMap, HashSet};
 use std::mem;
-use std::ops::Deref;
+use std::ops::{Bound, Deref};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::background_clear::BackgroundClear;
+use crate::backup::Backup;
+use crate::compression::Compression;
+use crate::databases::{Entries, SpaceUsage};
+use crate::diff::{DatabaseDiff, Difference, Side};
+use crate::encoding::ValueEncoding;
+use crate::error_report::{is_map_full, OrReport};
 use crate::escaped_entry::EscapedEntry;
-use eframe::egui::{self, InnerResponse};
+use crate::export::ExportFormat;
+use crate::external_edit::{EditState, ExternalEdit};
+use crate::glob::GlobScan;
+use crate::hex_editor::HexEditor;
+use crate::import::{BulkInsert, ImportPreview};
+use crate::key_type::KeyType;
+use crate::layout::{ColumnWidths, SavedDisplay, SavedDisplays, SavedPane};
+use crate::match_count::{CountTool, MatchCount, MatchKind};
+use crate::merge_env::EnvMerge;
+use crate::op_log::{Changes, DirtyKeys, OpLog};
+use crate::open_env::{
+    OpenEnvDialog, OpenedEnv, SyncFlags, DEFAULT_MAP_SIZE, MAP_SIZE_KEY, MAX_DBS, MIB,
+};
+use crate::prefix_tree::PrefixTree;
+use crate::quick_switcher::{QuickSwitcher, SwitcherAction};
+use crate::recent_envs::RecentEnvs;
+use crate::references::ReferenceRule;
+use crate::regex_search::RegexSearch;
+use crate::row_cache::{KeyStart, RowCache};
+use crate::settings::Settings;
+use crate::timestamps::TIMESTAMPS_DATABASE;
+use crate::value_decoder::ValueDecoder;
+use crate::value_layout::ValueLayout;
+use crate::value_sizes::ValueSizes;
+use crate::value_templates::ValueTemplates;
+use crate::write_lock::WriteLockWait;
+use clap::Parser;
+use eframe::egui;
+use eframe::Theme;
 use egui::Color32;
 use egui_extras::{Column, TableBuilder};
 use egui_tiles::Container;
 use egui_tiles::Tile;
 use either::Either;
 use heed::types::ByteSlice;
-use heed::{Database, Env, EnvOpenOptions};
+use heed::{Database, Env};
 use heed::{RoTxn, RwTxn};
-use once_cell::sync::OnceCell;
 use rfd::FileDialog;
 
+mod background_clear;
+mod backup;
+mod compression;
+mod databases;
+mod diff;
+mod encoding;
+mod error_report;
 mod escaped_entry;
+mod export;
+mod external_edit;
+mod glob;
+mod hex_dump;
+mod hex_editor;
+mod import;
+mod key_type;
+mod layout;
+mod match_count;
+mod merge_env;
+mod op_log;
+mod open_env;
+mod prefix_groups;
+mod prefix_tree;
+mod quick_switcher;
+mod recent_envs;
+mod references;
+mod regex_search;
+mod row_cache;
+mod settings;
+#[cfg(test)]
+mod test_env;
+mod timestamps;
+mod value_decoder;
+mod value_layout;
+mod value_sizes;
+mod value_templates;
+mod write_lock;
 
-static ENV: OnceCell<Env> = OnceCell::new();
+/// Views and edits LMDB environments.
+#[derive(Parser)]
+struct Args {
+    /// The folder of the environment to open, it is chosen in the app otherwise.
+    env_path: Option<PathBuf>,
+}
 
 fn main() -> anyhow::Result<()> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
+    let args = Args::parse();
+    // The window opens on the environment chooser when the folder can't be opened.
+    let (env_path, env_error) = match args.env_path {
+        Some(env_path) => {
+            let checked =
+                open_env::check_env_dir(&env_path).and_then(|()| Ok(env_path.canonicalize()?));
+            match checked {
+                Ok(env_path) => (Some(env_path), None),
+                Err(e) => (None, Some(format!("{e:#}"))),
+            }
+        }
+        None => (None, None),
+    };
+
     let options = eframe::NativeOptions {
         initial_window_size: Some(egui::vec2(720.0, 480.0)),
         ..Default::default()
     };
 
-    if let Some(env_path) = FileDialog::new().pick_folder() {
-        let env = EnvOpenOptions::new().max_dbs(1000).open(env_path)?;
-        let _ = ENV.set(env);
-
-        eframe::run_native("LMDB Editor", options, Box::new(|ctx| Box::new(LmdbEditor::new(ctx))))
-            .unwrap();
-    }
+    eframe::run_native(
+        "LMDB Editor",
+        options,
+        Box::new(|cc| Box::new(Editor::new(cc, env_path, env_error))),
+    )
+    .unwrap();
 
     Ok(())
 }
 
-struct LmdbEditor {
-    txn: Either<RoTxn<'static>, RwTxn<'static>>,
-    tree: egui_tiles::Tree<Pane>,
+/// The editor first asks which environment to open and how, and then lets the user edit it.
+/// Other environments can be opened next to it, each one with its own tabs.
+struct Editor {
+    /// The opened environments, the active one is shown.
+    editors: Vec<LmdbEditor>,
+    /// The index of the environment shown in `editors`.
+    active: usize,
+    /// Choosing another environment to open, always when none is opened.
+    chooser: Option<EnvChooser>,
+    /// The theme chosen by the user, the one of the system is followed when there is none.
+    theme: Option<Theme>,
+    /// Why the folder given on the command line or dropped onto the window couldn't be opened.
+    drop_error: Option<String>,
+    /// Whether the user is asked what to do with the pending changes before closing.
+    confirming_close: bool,
+    /// Whether the window closes, once the pending changes were committed or discarded.
+    closing: bool,
+    /// The values saved to be inserted again, in any environment.
+    templates: ValueTemplates,
+    /// The defaults of the new tabs and environments.
+    settings: Settings,
+    /// Whether the settings window is open.
+    editing_settings: bool,
 }
 
-impl LmdbEditor {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        // Customize egui here with cc.egui_ctx.set_fonts and cc.egui_ctx.set_visuals.
-        // Restore app state using cc.storage (requires the "persistence" feature).
+enum EnvChooser {
+    Choosing(RecentEnvs),
+    Opening(OpenEnvDialog),
+}
+
+impl EnvChooser {
+    /// Asks how to open the environment at `env_path`, with the map size it was last opened
+    /// with, the environments opened for the first time use the default map size of the
+    /// settings, or else the last used map size.
+    fn opening(
+        env_path: PathBuf,
+        recent_map_size: Option<usize>,
+        default_map_size: Option<usize>,
+        storage: Option<&dyn eframe::Storage>,
+    ) -> EnvChooser {
+        let map_size = recent_map_size
+            .or(default_map_size)
+            .or_else(|| eframe::get_value(storage?, MAP_SIZE_KEY))
+            .unwrap_or(DEFAULT_MAP_SIZE);
+        EnvChooser::Opening(OpenEnvDialog::new(env_path, map_size))
+    }
+}
+
+impl Editor {
+    /// Starts by asking how to open the environment at `env_path`, if any, or with why
+    /// the one given couldn't be opened.
+    fn new(
+        cc: &eframe::CreationContext<'_>,
+        env_path: Option<PathBuf>,
+        env_error: Option<String>,
+    ) -> Self {
+        // Customize egui here with cc.egui_ctx.set_fonts.
         // Use the cc.gl (a glow::Context) to create graphics shaders and buffers that you can use
         // for e.g. egui::PaintCallback.
 
-        // TODO do not try to create the database here.
-        let env = ENV.wait();
-        let mut wtxn = env.write_txn().unwrap();
-        let main_db = env.create_database(&mut wtxn, None).unwrap();
-        wtxn.commit().unwrap();
+        let recent_envs = RecentEnvs::load(cc.storage);
+        let settings = Settings::load(cc.storage);
+        let chooser = match env_path {
+            Some(env_path) => {
+                let recent_map_size = recent_envs.map_size(&env_path);
+                EnvChooser::opening(env_path, recent_map_size, settings.map_size, cc.storage)
+            }
+            None => EnvChooser::Choosing(recent_envs),
+        };
+        let theme = cc.storage.and_then(|storage| eframe::get_value(storage, THEME_KEY));
+        let templates = ValueTemplates::load(cc.storage);
+        Editor {
+            editors: Vec::new(),
+            active: 0,
+            chooser: Some(chooser),
+            theme,
+            drop_error: env_error,
+            confirming_close: false,
+            closing: false,
+            templates,
+            settings,
+            editing_settings: false,
+        }
+    }
 
-        let mut tiles = egui_tiles::Tiles::default();
-        let mut tabs = vec![];
+    /// Applies the chosen theme, or the one of the system when it changes.
+    fn apply_theme(&self, ctx: &egui::Context, frame: &eframe::Frame) {
+        if let Some(theme) = self.theme.or(frame.info().system_theme) {
+            if ctx.style().visuals.dark_mode != (theme == Theme::Dark) {
+                ctx.set_visuals(theme.egui_visuals());
+            }
+        }
+    }
 
-        tabs.push(tiles.insert_pane(Pane::DatabaseEntries {
-            database_name: None,
-            database: main_db,
-            entry_to_insert: EscapedEntry::default(),
-        }));
-        tabs.push(tiles.insert_pane(Pane::OpenNew { database_to_open: String::new() }));
-        let root = tiles.insert_tab_tile(tabs);
-        let tree = egui_tiles::Tree::new(root, tiles);
+    fn drop_error_ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(error) = &self.drop_error {
+            ui.colored_label(Color32::RED, error);
+            if ui.small_button("✕").clicked() {
+                self.drop_error = None;
+            }
+        }
+    }
 
-        let rtxn = env.read_txn().unwrap();
-        LmdbEditor { txn: Either::Left(rtxn), tree }
+    fn theme_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("theme", |ui| {
+            for (theme, label) in THEME_CHOICES {
+                if ui.radio_value(&mut self.theme, theme, label).clicked() {
+                    ui.close_menu();
+                }
+            }
+        });
     }
-}
 
-impl eframe::App for LmdbEditor {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                let env = ENV.wait();
-                let button = if self.txn.is_right() {
-                    egui::Button::new("currently writing").fill(Color32::GREEN)
-                } else {
-                    egui::Button::new("currently reading").fill(Color32::RED)
-                };
+    /// Shows the defaults of the new tabs and environments, when the window is open.
+    fn settings_ui(&mut self, ctx: &egui::Context) {
+        let Editor { settings, theme, .. } = self;
+        egui::Window::new("Settings").open(&mut self.editing_settings).resizable(false).show(
+            ctx,
+            |ui| {
+                egui::Grid::new("settings").num_columns(2).show(ui, |ui| {
+                    ui.label("theme");
+                    ui.horizontal(|ui| {
+                        for (choice, label) in THEME_CHOICES {
+                            ui.radio_value(theme, choice, label);
+                        }
+                    });
+                    ui.end_row();
 
-                if ui.add(button).clicked() && self.txn.is_left() {
-                    let wtxn = env.write_txn().unwrap();
-                    self.txn = Either::Right(wtxn);
-                }
+                    ui.label("encoding of the new tabs").on_hover_text(
+                        "STFU-8 shows any bytes and round-trips them, UTF-8 (lossy) replaces \
+                        the invalid sequences",
+                    );
+                    egui::ComboBox::from_id_source("settings_encoding")
+                        .selected_text(settings.encoding.label())
+                        .show_ui(ui, |ui| {
+                            for encoding in ValueEncoding::ALL {
+                                ui.selectable_value(
+                                    &mut settings.encoding,
+                                    encoding,
+                                    encoding.label(),
+                                );
+                            }
+                        });
+                    ui.end_row();
 
-                if ui.button("commit changes").clicked() {
-                    if let Some(wtxn) =
-                        replace_right_with(&mut self.txn, || env.read_txn().unwrap())
-                    {
-                        wtxn.commit().unwrap();
-                    }
-                }
+                    ui.label("key type of the new tabs");
+                    egui::ComboBox::from_id_source("settings_key_type")
+                        .selected_text(settings.key_type.label())
+                        .show_ui(ui, |ui| {
+                            for ty in KeyType::ALL {
+                                ui.selectable_value(&mut settings.key_type, ty, ty.label());
+                            }
+                        });
+                    ui.end_row();
 
-                if ui.button("abort changes").clicked() {
-                    if let Some(wtxn) =
-                        replace_right_with(&mut self.txn, || env.read_txn().unwrap())
-                    {
-                        wtxn.abort();
-                    }
+                    ui.label("pages of the new tabs");
+                    let label = |page_size: Option<usize>| match page_size {
+                        Some(page_size) => format!("{page_size} per page"),
+                        None => "continuous scroll".to_owned(),
+                    };
+                    egui::ComboBox::from_id_source("settings_page_size")
+                        .selected_text(label(settings.page_size))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut settings.page_size, None, label(None));
+                            for size in Pagination::PAGE_SIZES {
+                                let page_size = Some(size);
+                                ui.selectable_value(
+                                    &mut settings.page_size,
+                                    page_size,
+                                    label(page_size),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("map size of the new environments");
+                    ui.horizontal(|ui| {
+                        let mut last_used = settings.map_size.is_none();
+                        ui.checkbox(&mut last_used, "the last used one");
+                        match (last_used, settings.map_size.as_mut()) {
+                            (false, Some(map_size)) => {
+                                let mut mib = *map_size / MIB;
+                                let drag = egui::DragValue::new(&mut mib).clamp_range(1..=1 << 20);
+                                ui.add(drag.suffix(" MiB"));
+                                *map_size = mib * MIB;
+                            }
+                            (false, None) => settings.map_size = Some(DEFAULT_MAP_SIZE),
+                            (true, _) => settings.map_size = None,
+                        }
+                    });
+                    ui.end_row();
+                });
+                ui.weak("The databases already shown keep how they were last shown.");
+            },
+        );
+    }
+
+    /// Shows the environment chooser and opens the chosen environment in its own tabs.
+    /// Asks how to open the chosen environment, or shows it when it is already open.
+    fn choose_env(
+        &mut self,
+        env_path: PathBuf,
+        recent_map_size: Option<usize>,
+        storage: Option<&dyn eframe::Storage>,
+    ) {
+        // heed opens an environment only once, show it again instead.
+        let canonical_path = env_path.canonicalize().unwrap_or_else(|_| env_path.clone());
+        if let Some(i) = self.editors.iter().position(|e| e.env.path() == canonical_path) {
+            self.active = i;
+            self.chooser = None;
+            return;
+        }
+        let default_map_size = self.settings.map_size;
+        self.chooser =
+            Some(EnvChooser::opening(env_path, recent_map_size, default_map_size, storage));
+    }
+
+    /// Opens the environment folder dropped onto the window, the same way as a chosen one.
+    fn handle_dropped_env(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("dropped_env"),
+            ));
+            let screen = ctx.screen_rect();
+            painter.rect_filled(screen, 0.0, Color32::from_black_alpha(192));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "drop an environment folder to open it",
+                egui::TextStyle::Heading.resolve(&ctx.style()),
+                Color32::WHITE,
+            );
+        }
+
+        let dropped = ctx.input(|i| i.raw.dropped_files.first().and_then(|file| file.path.clone()));
+        let Some(env_path) = dropped else { return };
+        match open_env::check_env_dir(&env_path) {
+            Ok(()) => {
+                let recent_map_size = RecentEnvs::load(frame.storage()).map_size(&env_path);
+                self.choose_env(env_path, recent_map_size, frame.storage());
+                self.drop_error = None;
+            }
+            Err(e) => self.drop_error = Some(format!("{e:#}")),
+        }
+    }
+
+    /// Asks whether to commit or discard the pending changes of the environments before
+    /// closing the window, or to cancel closing it.
+    fn close_confirmation_ui(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if !self.confirming_close {
+            return;
+        }
+        egui::Window::new("Commit, discard, or cancel?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Closing the window aborts the write txns with pending changes:");
+                for editor in self.editors.iter().filter(|e| e.has_pending_changes()) {
+                    let Changes { insertions, deletions } = editor.op_log.changes();
+                    ui.label(format!(
+                        "{}: {insertions} insertions and {deletions} deletions",
+                        editor.env.path().display()
+                    ));
                 }
+                ui.horizontal(|ui| {
+                    if ui.button("commit").clicked() {
+                        self.confirming_close = false;
+                        self.closing = true;
+                        for (i, editor) in self.editors.iter_mut().enumerate() {
+                            if editor.has_pending_changes() {
+                                // Stay open to show why the changes couldn't be committed.
+                                if !editor.commit() {
+                                    self.closing = false;
+                                    self.active = i;
+                                }
+                            }
+                        }
+                        if self.closing {
+                            frame.close();
+                        }
+                    }
+                    if ui.button("discard").clicked() {
+                        for editor in &mut self.editors {
+                            editor.abort();
+                        }
+                        self.confirming_close = false;
+                        self.closing = true;
+                        frame.close();
+                    }
+                    if ui.button("cancel").clicked() {
+                        self.confirming_close = false;
+                    }
+                });
             });
+    }
 
-            let LmdbEditor { txn, tree } = self;
-
-            let mut behavior = TreeBehavior { txn: txn.as_mut() };
-            tree.ui(&mut behavior, ui);
+    fn chooser_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
+        let Some(chooser) = &mut self.chooser else { return };
+        match chooser {
+            EnvChooser::Choosing(recent_envs) => {
+                let Some((env_path, recent_map_size)) = recent_envs.ui(ui) else { return };
+                if let Some(storage) = frame.storage_mut() {
+                    recent_envs.save(storage);
+                }
 
-            // Automatically insert an OpenNew Tab when one is missing
-            if let Some(root) = self.tree.root() {
-                let must_insert = match self.tree.tiles.get(root).unwrap() {
-                    Tile::Container(Container::Tabs(t)) => t
-                        .children
-                        .iter()
-                        .find(|&&t| {
-                            self.tree
-                                .tiles
-                                .get(t)
-                                .map_or(true, |t| matches!(t, Tile::Pane(p) if p.is_open_new()))
-                        })
-                        .is_none(),
-                    _ => false,
+                self.choose_env(env_path, recent_map_size, frame.storage());
+            }
+            EnvChooser::Opening(dialog) => {
+                let Some(OpenedEnv { env, map_size, read_only, sync }) = dialog.ui(ui) else {
+                    return;
                 };
-
-                if must_insert {
-                    let tid = self
-                        .tree
-                        .tiles
-                        .insert_pane(Pane::OpenNew { database_to_open: String::new() });
-                    if let Tile::Container(Container::Tabs(t)) =
-                        self.tree.tiles.get_mut(root).unwrap()
-                    {
-                        t.children.push(tid);
+                if let Some(storage) = frame.storage_mut() {
+                    let mut recent_envs = RecentEnvs::load(Some(&*storage));
+                    recent_envs.insert(env.path().to_owned(), map_size);
+                    recent_envs.save(storage);
+                }
+                let mut errors = Vec::new();
+                let env = EnvHandle::new(env);
+                let editor = LmdbEditor::new(env, frame.storage(), map_size, read_only, sync);
+                match editor.or_report(&mut errors, "opening the environment") {
+                    Some(editor) => {
+                        self.editors.push(editor);
+                        self.active = self.editors.len() - 1;
+                        self.chooser = None;
                     }
+                    None => dialog.show_error(errors.concat()),
                 }
             }
-        });
+        }
     }
 }
 
-fn replace_right_with<L, R, F: FnMut() -> L>(either: &mut Either<L, R>, mut f: F) -> Option<R> {
-    match either {
-        Either::Left(_) => None,
-        Either::Right(_) => {
-            let obvious_right = mem::replace(either, Either::Left(f()));
-            match obvious_right {
-                Either::Left(_) => unreachable!(),
-                Either::Right(right) => Some(right),
-            }
+impl Editor {
+    /// Closes the active environment and reopens it with another map size, LMDB can only
+    /// grow the map when no txn is open. Its tabs are reopened, and the operations of the
+    /// write txn lost with the full map are done again before the failed put is retried.
+    fn reopen_active(&mut self, mut map_full: MapFull, frame: &mut eframe::Frame) {
+        let map_size = map_full.new_map_size * MIB;
+        let mut editor = self.editors.remove(self.active);
+        let old_map_size = editor.map_size;
+        let (read_only, sync) = (editor.read_only, editor.sync);
+        let env_path = editor.env.path().to_owned();
+        // The tabs are saved to be restored by the new editor.
+        if let Some(storage) = frame.storage_mut() {
+            eframe::App::save(&mut editor, storage);
+        }
+        // The write txn failed to put, it can only be aborted but its operations are logged.
+        let lost_txn = map_full.lost_txn.take().or_else(|| {
+            let logged = (mem::take(&mut editor.op_log), mem::take(&mut editor.dirty_keys));
+            editor.txn.is_right().then_some(logged)
+        });
+        // The editor closes the environment once its txns are dropped. The background threads
+        // own their own handle, closing waits for them to drop it, it isn't reopened while one
+        // runs not to block.
+        drop(editor);
+
+        let mut errors = Vec::new();
+        let reopened = open_env::open_env(&env_path, map_size, read_only, sync)
+            .or_report(&mut errors, "reopening the environment with a larger map")
+            .map(|env| (env, map_size))
+            .or_else(|| {
+                let env = open_env::open_env(&env_path, old_map_size, read_only, sync);
+                env.or_report(&mut errors, "reopening the environment")
+                    .map(|env| (env, old_map_size))
+            });
+        let Some((env, map_size)) = reopened else {
+            self.show_reopen_errors(errors, frame);
+            return;
+        };
+
+        if let Some(storage) = frame.storage_mut() {
+            let mut recent_envs = RecentEnvs::load(Some(&*storage));
+            recent_envs.insert(env.path().to_owned(), map_size);
+            recent_envs.save(storage);
+        }
+        let env = EnvHandle::new(env);
+        let editor = LmdbEditor::new(env, frame.storage(), map_size, read_only, sync);
+        let Some(mut editor) = editor.or_report(&mut errors, "reopening the environment") else {
+            self.show_reopen_errors(errors, frame);
+            return;
+        };
+        editor.errors.extend(errors);
+        editor.redo_lost_txn(lost_txn, map_full.put);
+        self.editors.insert(self.active, editor);
+    }
+
+    /// Shows why the active environment couldn't be reopened in the editor now active,
+    /// or lets the user choose another environment when it was the only one.
+    fn show_reopen_errors(&mut self, errors: Vec<String>, frame: &eframe::Frame) {
+        self.active = self.active.min(self.editors.len().saturating_sub(1));
+        match self.editors.get_mut(self.active) {
+            Some(editor) => editor.errors.extend(errors),
+            None => self.chooser = Some(EnvChooser::Choosing(RecentEnvs::load(frame.storage()))),
         }
     }
 }
 
-enum Pane {
-    DatabaseEntries {
-        database_name: Option<String>,
-        database: Database<ByteSlice, ByteSlice>,
-        entry_to_insert: EscapedEntry,
-    },
-    OpenNew {
-        database_to_open: String,
-    },
+/// An environment leaked for the txns to borrow it for `'static`, it is closed when the
+/// handle is dropped, once its background threads dropped their own handles.
+///
+/// The owner of the handle must declare it after the fields borrowing the environment,
+/// the fields of a struct are dropped in the order they are declared.
+struct EnvHandle(&'static Env);
+
+impl EnvHandle {
+    fn new(env: Env) -> EnvHandle {
+        EnvHandle(Box::leak(Box::new(env)))
+    }
+
+    /// The environment, the txns borrowing it must be dropped before the handle.
+    fn get(&self) -> &'static Env {
+        self.0
+    }
 }
 
-impl Pane {
-    fn is_open_new(&self) -> bool {
-        matches!(self, Pane::OpenNew { .. })
+impl Deref for EnvHandle {
+    type Target = Env;
+
+    fn deref(&self) -> &Env {
+        self.0
     }
 }
 
-struct TreeBehavior<'a> {
-    txn: Either<&'a mut RoTxn<'static>, &'a mut RwTxn<'static>>,
+impl Drop for EnvHandle {
+    fn drop(&mut self) {
+        // Safety: the environment was leaked by `new` and is only freed here, the owner
+        // dropped the txns borrowing it before the handle.
+        let env = unsafe { Box::from_raw(self.0 as *const Env as *mut Env) };
+        env.prepare_for_closing().wait();
+    }
 }
 
-impl egui_tiles::Behavior<Pane> for TreeBehavior<'_> {
-    fn tab_title_for_pane(&mut self, pane: &Pane) -> egui::WidgetText {
-        match pane {
-            Pane::DatabaseEntries { database_name: Some(name), .. } => format!("{name}").into(),
-            Pane::DatabaseEntries { database_name: None, .. } => format!("{{main}}").into(),
-            Pane::OpenNew { .. } => format!("Open new").into(),
+impl eframe::App for Editor {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.apply_theme(ctx, frame);
+        self.handle_dropped_env(ctx, frame);
+        self.settings_ui(ctx);
+
+        if self.editors.is_empty() {
+            egui::TopBottomPanel::top("environments").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    self.theme_menu(ui);
+                    if ui.button("settings…").clicked() {
+                        self.editing_settings = !self.editing_settings;
+                    }
+                    self.drop_error_ui(ui);
+                });
+            });
+            egui::CentralPanel::default().show(ctx, |ui| self.chooser_ui(ui, frame));
+            return;
+        }
+
+        egui::TopBottomPanel::top("environments").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                self.theme_menu(ui);
+                if ui.button("settings…").clicked() {
+                    self.editing_settings = !self.editing_settings;
+                }
+                ui.separator();
+                for (i, editor) in self.editors.iter().enumerate() {
+                    let name = editor.env.path().file_name().unwrap_or_default();
+                    let label = ui.selectable_label(i == self.active, name.to_string_lossy());
+                    if label.on_hover_text(editor.env.path().display().to_string()).clicked() {
+                        self.active = i;
+                    }
+                }
+                if ui.button("open another environment…").clicked() && self.chooser.is_none() {
+                    self.chooser = Some(EnvChooser::Choosing(RecentEnvs::load(frame.storage())));
+                }
+                self.drop_error_ui(ui);
+            });
+        });
+
+        if self.chooser.is_some() {
+            let mut open = true;
+            egui::Window::new("Open another environment")
+                .open(&mut open)
+                .show(ctx, |ui| self.chooser_ui(ui, frame));
+            if !open {
+                self.chooser = None;
+            }
         }
+
+        // The templates are shared by the environments, the active one edits them.
+        let editor = &mut self.editors[self.active];
+        editor.templates = mem::take(&mut self.templates);
+        editor.settings = self.settings;
+        editor.update(ctx, frame);
+        self.templates = mem::take(&mut editor.templates);
+        if let Some(map_full) = self.editors[self.active].grow_map.take() {
+            self.reopen_active(map_full, frame);
+        }
+        self.close_confirmation_ui(ctx, frame);
     }
 
-    fn pane_ui(
-        &mut self,
-        ui: &mut egui::Ui,
-        _tile_id: egui_tiles::TileId,
-        pane: &mut Pane,
-    ) -> egui_tiles::UiResponse {
-        ui.add_space(5.0);
+    /// Asks what to do with the pending changes instead of silently aborting them.
+    fn on_close_event(&mut self) -> bool {
+        if self.closing || !self.editors.iter().any(LmdbEditor::has_pending_changes) {
+            return true;
+        }
+        self.confirming_close = true;
+        false
+    }
 
-        match pane {
-            Pane::DatabaseEntries { database, entry_to_insert, database_name, .. } => {
-                let name = database_name.as_ref().map_or_else(|| "{main}".to_owned(), Clone::clone);
-                egui::Window::new(format!("Put an entry into {name}")).default_pos([720.0, 480.0]).show(ui.ctx(), |ui| {
-                    ui.style_mut().spacing.interact_size.y = 0.0; // hack to make `horizontal_wrapped` work better with text.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, THEME_KEY, &self.theme);
+        self.templates.save(storage);
+        self.settings.save(storage);
+        if let Some(EnvChooser::Choosing(recent_envs)) = &self.chooser {
+            recent_envs.save(storage);
+        }
+        for editor in &mut self.editors {
+            editor.save(storage);
+        }
+    }
+}
 
-                    ui.label("We use STFU-8 as a hacky text encoding/decoding protocol for data that might be not quite UTF-8 but is still mostly UTF-8. \
-                    It is based on the syntax of the repr created when you write (or print) binary text in python, C or other common programming languages.");
+/// The key under which the theme chosen by the user is persisted.
+const THEME_KEY: &str = "theme";
 
-                    ui.add_space(8.0);
+/// The themes the user can choose from, `None` follows the system.
+const THEME_CHOICES: [(Option<Theme>, &str); 3] =
+    [(None, "follow the system"), (Some(Theme::Dark), "dark"), (Some(Theme::Light), "light")];
 
-                    ui.label("Basically STFU-8 is the text format you already write when use escape codes in C, python, rust, etc. \
-                    It permits binary data in UTF-8 by escaping them with \\, for instance \\n and \\x0F.");
+/// The key under which whether the tab titles show the number of entries is persisted.
+const TAB_COUNTS_KEY: &str = "tab_counts";
 
-                    ui.add_space(8.0);
+/// The key under which the interval between two renewals of the read txn is persisted.
+const READ_TXN_REFRESH_KEY: &str = "read_txn_refresh_secs";
 
-                    ui.horizontal_wrapped(|ui| {
-                        ui.spacing_mut().item_spacing.x = 0.0;
-                        ui.label("More about how we interpret encoding/decoding ");
-                        ui.hyperlink_to("on the stfu8 documentation", "https://docs.rs/stfu8");
-                        ui.label(".");
-                    });
+/// The read txn is renewed every 30 seconds by default.
+const DEFAULT_READ_TXN_REFRESH_SECS: u64 = 30;
 
-                    ui.separator();
+/// The age after which a read txn is shown as long-lived, LMDB can't reuse the pages it sees.
+const LONG_READ_TXN: Duration = Duration::from_secs(5 * 60);
 
-                    let EscapedEntry { key, data } = entry_to_insert;
-                    ui.add(egui::TextEdit::singleline(key).hint_text("escaped key"));
-                    ui.add(egui::TextEdit::multiline(data).hint_text("escaped data"));
+/// The number of checked keys above which deleting them must be confirmed.
+const CHECKED_DELETE_CONFIRMATION: usize = 10;
 
-                    if ui.button("insert").clicked() {
-                        if let Either::Right(wtxn) = self.txn.as_mut() {
-                            let key = entry_to_insert.decoded_key().unwrap();
-                            let data = entry_to_insert.decoded_data().unwrap();
-                            database.put(wtxn, &key, &data).unwrap();
-                            entry_to_insert.clear();
-                        }
-                    }
+/// Opens a write txn, like the "currently reading" button.
+const WRITE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::E);
 
-                    if ui.button("delete").clicked() {
-                        if let Either::Right(wtxn) = self.txn.as_mut() {
-                            let key = entry_to_insert.decoded_key().unwrap();
-                            database.delete(wtxn, &key).unwrap();
-                            entry_to_insert.clear();
-                        }
-                    }
-                });
+/// Commits the write txn, like the "commit changes" button.
+const COMMIT_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Enter);
 
-                // If there is a write txn opened, use it, else make the wtxn live longer and deref it.
-                let long_wtxn: &&mut RwTxn;
-                let rtxn: &heed::RoTxn;
-                match self.txn.as_ref() {
-                    Either::Left(txn) => rtxn = txn,
-                    Either::Right(wtxn) => {
-                        long_wtxn = wtxn;
-                        rtxn = long_wtxn.deref();
-                    }
-                };
+/// Aborts the write txn, like the "abort changes" button, when no text field is focused.
+const ABORT_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Escape);
 
-                let num_rows = database.len(&rtxn).unwrap().try_into().unwrap();
-                let mut prev_row_index = None;
-                let mut iter = database.iter(&rtxn).unwrap();
+/// Opens the quick switcher to go to a database by name.
+const QUICK_SWITCHER_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::P);
 
-                TableBuilder::new(ui)
-                    .column(Column::auto().resizable(true))
-                    .column(Column::auto().resizable(true))
-                    .column(Column::remainder())
-                    .header(20.0, |mut header| {
-                        header.col(|ui| {
-                            ui.label("Keys");
-                        });
-                        header.col(|ui| {
-                            ui.label("Values");
-                        });
-                        header.col(|ui| {
-                            ui.label("Operations");
-                        });
-                    })
-                    .body(|body| {
-                        body.rows(30.0, num_rows, |row_index, mut row| {
-                            assert!(prev_row_index.map_or(true, |p| p + 1 == row_index));
-                            if prev_row_index.is_none() {
-                                iter.by_ref().take(row_index).for_each(drop);
-                            }
-                            prev_row_index = Some(row_index);
+/// The number of bytes of a value encoded to be shown in its cell, the selected row
+/// shows the whole value.
+const CELL_PREVIEW_BYTES: usize = 256;
 
-                            if let Some(result) = iter.next() {
-                                let (key, data) = result.unwrap();
-                                let encoded_key = stfu8::encode_u8_pretty(key);
-                                let encoded_data = stfu8::encode_u8_pretty(data);
+/// The number of characters the long keys and values are cut after, when they are truncated.
+const TRUNCATED_CELL_CHARS: usize = 80;
 
-                                row.col(|ui| {
-                                    ui.label(&encoded_key);
-                                });
-                                row.col(|ui| {
-                                    ui.label(&encoded_data);
-                                });
-                                row.col(|ui| {
-                                    // TODO Replace me by a ✏️
-                                    if ui.button("edit").clicked() {
-                                        entry_to_insert.key = encoded_key;
-                                        entry_to_insert.data = encoded_data;
-                                    }
-                                    // // Replace me by a red 🗑️
-                                    // if ui.button("delete").clicked() {
-                                    //     if let Some(wtxn) = self.wtxn.as_mut() {
-                                    //     }
-                                    // }
-                                });
-                            }
-                        });
-                    });
-            }
-            Pane::OpenNew { database_to_open } => {
-                let response = ui.horizontal(|ui| {
-                    // If there is a write txn opened, use it, else make the wtxn live longer and deref it.
-                    let long_wtxn: &&mut RwTxn;
-                    let rtxn: &heed::RoTxn;
-                    match self.txn.as_ref() {
-                        Either::Left(txn) => rtxn = txn,
-                        Either::Right(wtxn) => {
-                            long_wtxn = wtxn;
-                            rtxn = long_wtxn.deref();
-                        }
-                    };
+/// The number of entries copied per frame when merging from another environment.
+const MERGE_CHUNK: u64 = 10_000;
 
-                    ui.add(egui::TextEdit::singleline(database_to_open).hint_text("database name"));
-                    if ui.button("open").clicked() {
-                        let env = ENV.wait();
-                        let database_name = if database_to_open.is_empty() {
-                            None
-                        } else {
-                            Some(mem::take(database_to_open))
-                        };
+/// The number of bytes of a key or a value shown in an expanded row, laid out every frame.
+const EXPANDED_CELL_BYTES: usize = 16 * 1024;
 
-                        let database = env
-                            .open_database(&rtxn, database_name.as_ref().map(AsRef::as_ref))
-                            .unwrap();
+/// The size of the data to insert flagged by default, once the user asks for it.
+const DEFAULT_MAX_DATA_KIB: usize = 1024;
 
-                        match database {
-                            Some(database) => Some(Pane::DatabaseEntries {
-                                database_name,
-                                database,
-                                entry_to_insert: Default::default(),
-                            }),
-                            None => None,
-                        }
-                    } else {
-                        None
-                    }
-                });
+/// The number of entries shown by default when peeking at the first or last entries.
+const DEFAULT_SAMPLE_SIZE: usize = 20;
 
-                if let InnerResponse { inner: Some(p), .. } = response {
-                    *pane = p;
+/// The number of entries scanned per frame when searching for the keys matching a glob.
+const GLOB_SCAN_BUDGET: usize = 20_000;
+
+/// The number of entries deleted per frame when deleting the entries with a prefix.
+const PREFIX_DELETION_CHUNK: u64 = 10_000;
+
+/// The number of database names suggested while typing the name of a database to open.
+const MAX_SUGGESTED_DATABASES: usize = 10;
+
+/// How the created databases store their keys, heed has no way to pass `MDB_DUPSORT`
+/// nor `MDB_REVERSEKEY`.
+const CREATED_DATABASE_FLAGS: &str = "The database is created with one value per key and \
+    its keys compared from the start, heed can't create databases with duplicate keys nor \
+    with keys compared from the end";
+
+/// The room left for the menu of the tabs when they overflow the tab bar.
+const OVERFLOW_BUTTON_WIDTH: f32 = 24.0;
+
+/// Edits one environment in its own tabs.
+struct LmdbEditor {
+    /// The read txn, the write txn or the innermost nested txn.
+    txn: Either<RoTxn<'static>, RwTxn<'static>>,
+    /// The parents of the nested txn being written to, from the outer write txn,
+    /// with the operations done and the keys written in them. The nested txn borrows its
+    /// parent, this field must be declared after `txn` for it to be dropped after it.
+    nested_parents: Vec<(Box<RwTxn<'static>>, OpLog, DirtyKeys)>,
+    tree: egui_tiles::Tree<Pane>,
+    /// The errors to display to the user until they are dismissed.
+    errors: Vec<String>,
+    /// The map size in bytes the environment was opened with.
+    map_size: usize,
+    /// Whether the environment was opened read-only, no write txn can be opened then.
+    read_only: bool,
+    /// The durability the environment was opened with, see `SyncFlags`.
+    sync: SyncFlags,
+    /// The names of the named databases of the environment.
+    database_names: Vec<String>,
+    /// Incremented every time the txn is committed or aborted, what
+    /// was read from the previous txn may no longer be valid then.
+    txn_generation: u64,
+    /// The operations done in the current write txn, to undo and redo them.
+    op_log: OpLog,
+    /// Whether the user is asked to confirm the changes to commit.
+    confirming_commit: bool,
+    /// Whether the user is asked to confirm discarding the changes of the write txn.
+    confirming_abort: bool,
+    /// Whether a database is cleared in its own write txn in the background,
+    /// LMDB would block the UI until it is done when opening another write txn.
+    background_writes: bool,
+    /// The number of seconds after which the read txn is renewed, when it is, to see
+    /// the changes committed by other processes and let LMDB reuse the pages they freed.
+    /// It is also renewed when the window gets the focus back.
+    read_txn_refresh: Option<u64>,
+    /// When the read txn, or the outermost write txn, was opened.
+    txn_opened: Instant,
+    /// When the user pinned the snapshot of the read txn, it is then only renewed on
+    /// commits and aborts, for the other processes' writes not to change what is browsed.
+    pinned_at: Option<SystemTime>,
+    /// Whether the window had the focus during the last frame.
+    focused: bool,
+    /// The map got full, until the user grows it or dismisses it.
+    map_full: Option<MapFull>,
+    /// The write lock being waited for to open a write txn, another process may hold it.
+    write_lock_wait: Option<WriteLockWait>,
+    /// The map the environment is reopened with, once the user chose to grow it.
+    grow_map: Option<MapFull>,
+    /// The copy of the environment running in the background.
+    backup: Option<Backup>,
+    /// Whether the backups omit the free pages, which is slower.
+    compact_backup: bool,
+    /// The outcome of the last backup.
+    backup_message: Option<String>,
+    /// The last txn when the environment was last synced to disk by the user.
+    synced_txn_id: Option<usize>,
+    /// The pages used by the environment, measured again when the user asks. They are
+    /// `None` when measuring them failed, the error was reported once.
+    space_usage: Option<Option<SpaceUsage>>,
+    /// The popup to go to a database by name, when it is open.
+    quick_switcher: Option<QuickSwitcher>,
+    /// The defaults of the new tabs, set by the `Editor` before every update.
+    settings: Settings,
+    /// Whether the time the keys are written is recorded in the timestamps database on
+    /// every commit, the user opts in for every environment.
+    record_timestamps: bool,
+    /// The timestamps database, when it exists.
+    timestamps: Option<Database<ByteSlice, ByteSlice>>,
+    /// The comparison of two open databases, when its window is open.
+    diff_view: Option<DiffView>,
+    /// Whether a database was created in the write txn, the op log doesn't count it.
+    created_database: bool,
+    /// The value templates, lent by the `Editor` while the environment is shown.
+    templates: ValueTemplates,
+    /// The keys written in the write txn.
+    dirty_keys: DirtyKeys,
+    /// The widths the user resized the columns of the tables to.
+    column_widths: ColumnWidths,
+    /// How the entries of the databases were last shown.
+    displays: SavedDisplays,
+    /// Whether the tab titles show the number of entries of their database.
+    show_tab_counts: bool,
+    /// The number of entries shown in the tab titles, see `cached_len`.
+    tab_counts: EntryCounts,
+    /// The environment, declared last to be closed after the txns and the tabs are dropped.
+    env: EnvHandle,
+}
+
+/// The number of entries of the databases and the version they were counted for, by name.
+type EntryCounts = HashMap<Option<String>, ((u64, u64, u64), u64)>;
+
+impl LmdbEditor {
+    fn new(
+        handle: EnvHandle,
+        storage: Option<&dyn eframe::Storage>,
+        map_size: usize,
+        read_only: bool,
+        sync: SyncFlags,
+    ) -> anyhow::Result<Self> {
+        let env = handle.get();
+        let main_db = if read_only {
+            // The unnamed database always exists, it can be opened without writing.
+            let main_db = env.open_database(&env.read_txn()?, None)?;
+            main_db.ok_or_else(|| anyhow::anyhow!("the unnamed database is missing"))?
+        } else {
+            // TODO do not try to create the database here.
+            let mut wtxn = env.write_txn()?;
+            let main_db = env.create_database(&mut wtxn, None)?;
+            wtxn.commit()?;
+            main_db
+        };
+
+        let rtxn = env.read_txn()?;
+        let mut errors = Vec::new();
+
+        // Reopen the tabs of the last run, skipping the databases that no longer exist.
+        let saved: Option<egui_tiles::Tree<SavedPane>> =
+            storage.and_then(|storage| eframe::get_value(storage, &layout::layout_key(env.path())));
+        let restored = saved.map(|saved| {
+            layout::map_panes(&saved, |pane| match pane {
+                SavedPane::DatabaseEntries { database_name: None } => {
+                    Some(Pane::database_entries(env, &rtxn, None, main_db))
                 }
+                SavedPane::DatabaseEntries { database_name: Some(name) } => env
+                    .open_database(&rtxn, Some(name))
+                    .or_report(&mut errors, &format!("reopening the {name} database"))
+                    .flatten()
+                    .map(|database| {
+                        Pane::database_entries(env, &rtxn, Some(name.clone()), database)
+                    }),
+                SavedPane::OpenNew => Some(Pane::open_new()),
+            })
+        });
+
+        let tree = match restored {
+            Some(tree) if tree.root.is_some_and(|root| tree.tiles.get(root).is_some()) => tree,
+            _ => {
+                let mut tiles = egui_tiles::Tiles::default();
+                let mut tabs = vec![];
+
+                tabs.push(tiles.insert_pane(Pane::database_entries(env, &rtxn, None, main_db)));
+                tabs.push(tiles.insert_pane(Pane::open_new()));
+                let root = tiles.insert_tab_tile(tabs);
+                egui_tiles::Tree::new(root, tiles)
             }
-        }
+        };
 
-        egui_tiles::UiResponse::None
+        let mut editor = LmdbEditor {
+            txn: Either::Left(rtxn),
+            nested_parents: Vec::new(),
+            tree,
+            errors,
+            map_size,
+            read_only,
+            sync,
+            database_names: Vec::new(),
+            txn_generation: 0,
+            op_log: OpLog::default(),
+            confirming_commit: false,
+            confirming_abort: false,
+            background_writes: false,
+            read_txn_refresh: storage
+                .and_then(|storage| eframe::get_value(storage, READ_TXN_REFRESH_KEY))
+                .unwrap_or(Some(DEFAULT_READ_TXN_REFRESH_SECS)),
+            txn_opened: Instant::now(),
+            pinned_at: None,
+            focused: true,
+            map_full: None,
+            write_lock_wait: None,
+            grow_map: None,
+            backup: None,
+            compact_backup: false,
+            backup_message: None,
+            synced_txn_id: None,
+            space_usage: None,
+            quick_switcher: None,
+            settings: Settings::default(),
+            record_timestamps: storage
+                .and_then(|storage| {
+                    eframe::get_value(storage, &layout::record_timestamps_key(env.path()))
+                })
+                .unwrap_or(false),
+            timestamps: None,
+            diff_view: None,
+            created_database: false,
+            templates: ValueTemplates::default(),
+            dirty_keys: DirtyKeys::default(),
+            column_widths: storage
+                .and_then(|storage| {
+                    eframe::get_value(storage, &layout::column_widths_key(env.path()))
+                })
+                .unwrap_or_default(),
+            displays: storage
+                .and_then(|storage| eframe::get_value(storage, &layout::displays_key(env.path())))
+                .unwrap_or_default(),
+            show_tab_counts: storage
+                .and_then(|storage| eframe::get_value(storage, TAB_COUNTS_KEY))
+                .unwrap_or(false),
+            tab_counts: HashMap::new(),
+            env: handle,
+        };
+        editor.refresh_database_names();
+        Ok(editor)
+    }
+
+    fn refresh_database_names(&mut self) {
+        let names = databases::named_databases(&self.env, read_txn(&self.txn));
+        if let Some(names) = names.or_report(&mut self.errors, "listing the databases") {
+            self.database_names = names;
+        }
+        self.timestamps = None;
+        if self.database_names.iter().any(|name| name == TIMESTAMPS_DATABASE) {
+            let rtxn = read_txn(&self.txn);
+            let database = self.env.open_database(rtxn, Some(TIMESTAMPS_DATABASE));
+            self.timestamps =
+                database.or_report(&mut self.errors, "opening the timestamps").flatten();
+        }
     }
+
+    /// Commits the write txn, if any, and reads from a new read txn.
+    /// Returns whether there was nothing to commit or it was committed.
+    fn commit(&mut self) -> bool {
+        while !self.nested_parents.is_empty() {
+            if !self.end_nested_txn(true) {
+                return false;
+            }
+        }
+        let env = self.env.get();
+        let mut recorded = false;
+        if let (true, Either::Right(wtxn)) = (self.record_timestamps, &mut self.txn) {
+            let result = timestamps::record(env, wtxn, &self.dirty_keys);
+            let result = result.or_report(&mut self.errors, "recording the modification times");
+            recorded = result.unwrap_or(false);
+        }
+        // The write txn stays open when no read txn can be opened to read the commit with.
+        let wtxn = replace_right_with(&mut self.txn, || env.read_txn());
+        let Some(wtxn) = wtxn.or_report(&mut self.errors, "opening a read txn") else {
+            return false;
+        };
+        if let Some(wtxn) = wtxn {
+            let result = wtxn.commit();
+            if result.as_ref().is_err_and(is_map_full) {
+                // LMDB aborted the txn, its operations are done again once the map is grown.
+                let mut map_full = MapFull::new(self.map_size, None);
+                map_full.lost_txn = Some((self.op_log.clone(), self.dirty_keys.clone()));
+                self.map_full = Some(map_full);
+            }
+            let committed = result.or_report(&mut self.errors, "committing changes").is_some();
+            // The read txn opened before the commit doesn't see the committed changes.
+            self.renew_read_txn();
+            self.op_log.clear();
+            self.dirty_keys.clear();
+            self.created_database = false;
+            self.space_usage = None;
+            // The timestamps database is created by the first commit recording in it.
+            if recorded && self.timestamps.is_none() {
+                self.refresh_database_names();
+            }
+            return committed;
+        }
+        true
+    }
+
+    /// Aborts the write txn, if any, and its nested txns, and reads from a new read txn.
+    /// The write txn stays open when no read txn can be opened.
+    fn abort(&mut self) {
+        while !self.nested_parents.is_empty() {
+            if !self.end_nested_txn(false) {
+                return;
+            }
+        }
+        let env = self.env.get();
+        let wtxn = replace_right_with(&mut self.txn, || env.read_txn());
+        if let Some(wtxn) = wtxn.or_report(&mut self.errors, "opening a read txn").flatten() {
+            wtxn.abort();
+            self.txn_generation += 1;
+            self.txn_opened = Instant::now();
+            self.op_log.clear();
+            self.dirty_keys.clear();
+            self.created_database = false;
+        }
+    }
+
+    /// Whether the write txn has changes that would be lost if it were dropped.
+    fn has_pending_changes(&self) -> bool {
+        let nested_changes =
+            self.nested_parents.iter().any(|(_, op_log, _)| !op_log.changes().is_empty());
+        self.txn.is_right()
+            && (!self.op_log.changes().is_empty() || nested_changes || self.created_database)
+    }
+
+    /// Does the operations of the write txn lost when the map got full again in a new write
+    /// txn, then puts again the entry that didn't fit in the map.
+    fn redo_lost_txn(&mut self, lost_txn: Option<(OpLog, DirtyKeys)>, put: Option<FailedPut>) {
+        let lost_txn = lost_txn.filter(|(op_log, _)| !op_log.changes().is_empty());
+        if lost_txn.is_none() && put.is_none() {
+            return;
+        }
+        let env = self.env.get();
+        let Some(wtxn) = env.write_txn().or_report(&mut self.errors, "opening a write txn") else {
+            return;
+        };
+        self.txn = Either::Right(wtxn);
+        self.txn_opened = Instant::now();
+        let Either::Right(wtxn) = &mut self.txn else { unreachable!() };
+        if let Some((op_log, dirty_keys)) = lost_txn {
+            let reapplied = op_log.reapply(env, wtxn);
+            match reapplied.or_report(&mut self.errors, "doing the changes again") {
+                Some(Some(op_log)) => {
+                    self.op_log = op_log;
+                    self.dirty_keys = dirty_keys;
+                }
+                Some(None) => self.errors.push(
+                    "the changes of the lost write txn can't be done again after a bulk \
+                    operation, they were discarded"
+                        .to_owned(),
+                ),
+                None => {
+                    // Half of the changes would be as confusing as none.
+                    self.abort();
+                    return;
+                }
+            }
+        }
+        let Some(put) = put else { return };
+        let Either::Right(wtxn) = &mut self.txn else { unreachable!() };
+        let database = env
+            .open_database(wtxn, put.database_name.as_deref())
+            .or_report(&mut self.errors, "reopening the database")
+            .flatten();
+        if let Some(database) = database {
+            let result = self.op_log.put(
+                &database,
+                &put.database_name,
+                put.dup_sort,
+                wtxn,
+                &put.key,
+                &put.data,
+            );
+            if result.or_report(&mut self.errors, "putting the entry again").is_some() {
+                self.dirty_keys.insert(&put.database_name, &put.key);
+            }
+        }
+    }
+
+    /// Replaces the read txn by a new one that sees what was committed since it was opened,
+    /// the write txn is never replaced.
+    fn renew_read_txn(&mut self) {
+        if self.txn.is_left() {
+            // The current read txn is kept when no new one can be opened.
+            let rtxn =
+                self.env.get().read_txn().or_report(&mut self.errors, "renewing the read txn");
+            if let Some(rtxn) = rtxn {
+                self.txn = Either::Left(rtxn);
+                self.txn_generation += 1;
+                self.txn_opened = Instant::now();
+            }
+        }
+    }
+
+    /// Polls the databases cleared in the background by all the tabs, egui_tiles only shows
+    /// the visible ones.
+    fn poll_background_clears(&mut self) {
+        self.background_writes = false;
+        let mut cleared = false;
+        for tile in self.tree.tiles.tiles.values_mut() {
+            let Tile::Pane(Pane::DatabaseEntries {
+                background_clear,
+                row_cache,
+                total_value_bytes,
+                ..
+            }) = tile
+            else {
+                continue;
+            };
+            let Some(clear) = background_clear else { continue };
+            let Some(result) = clear.poll() else {
+                self.background_writes = true;
+                continue;
+            };
+            result.or_report(&mut self.errors, "clearing the database");
+            row_cache.clear();
+            *total_value_bytes = None;
+            *background_clear = None;
+            cleared = true;
+        }
+        if cleared {
+            // The read txn doesn't see what was committed in the background.
+            self.renew_read_txn();
+        }
+    }
+
+    /// Opens a txn nested in the current write txn, its changes can then be
+    /// committed into the parent txn or aborted without aborting the parent.
+    fn begin_nested_txn(&mut self) {
+        let env = self.env.get();
+        let parent = replace_right_with(&mut self.txn, || env.read_txn());
+        let Some(parent) = parent.or_report(&mut self.errors, "opening a read txn").flatten()
+        else {
+            return;
+        };
+        let mut parent = Box::new(parent);
+        // Safety: the parent is boxed and doesn't move, it is only used again once the
+        // nested txn is committed or aborted and taken out of `txn`. It outlives the nested
+        // txn that borrows it because `LmdbEditor` declares `txn` before `nested_parents`,
+        // and the fields of a struct are dropped in declaration order: they must stay so.
+        let parent_ref: &'static mut RwTxn<'static> = unsafe { &mut *(&mut *parent as *mut _) };
+        match env.nested_write_txn(parent_ref).or_report(&mut self.errors, "opening a nested txn") {
+            Some(nested) => {
+                self.txn = Either::Right(nested);
+                self.nested_parents.push((parent, self.op_log.clone(), self.dirty_keys.clone()));
+            }
+            None => self.txn = Either::Right(*parent),
+        }
+    }
+
+    /// Commits or aborts the innermost nested txn and writes into its parent again.
+    /// Returns whether it was ended, it stays open when no read txn can be opened.
+    fn end_nested_txn(&mut self, commit: bool) -> bool {
+        let Some((parent, parent_op_log, parent_dirty_keys)) = self.nested_parents.pop() else {
+            return false;
+        };
+        let env = self.env.get();
+        let nested = replace_right_with(&mut self.txn, || env.read_txn());
+        let Some(nested) = nested.or_report(&mut self.errors, "opening a read txn") else {
+            self.nested_parents.push((parent, parent_op_log, parent_dirty_keys));
+            return false;
+        };
+        if let Some(nested) = nested {
+            // LMDB aborts the nested txn when it fails to commit it.
+            let committed = commit
+                && nested
+                    .commit()
+                    .or_report(&mut self.errors, "committing the nested txn")
+                    .is_some();
+            if !committed {
+                self.op_log = parent_op_log;
+                self.dirty_keys = parent_dirty_keys;
+            }
+        }
+        self.txn = Either::Right(*parent);
+        self.txn_generation += 1;
+        true
+    }
+
+    /// The databases opened in the tabs, once each.
+    fn open_databases(&self) -> Vec<OpenDatabase> {
+        let mut open_databases: Vec<_> = self
+            .tree
+            .tiles
+            .tiles
+            .values()
+            .filter_map(|tile| match tile {
+                Tile::Pane(Pane::DatabaseEntries {
+                    database_name,
+                    database,
+                    dup_sort,
+                    reverse_key,
+                    locked,
+                    ..
+                }) => Some(OpenDatabase {
+                    name: database_name.clone(),
+                    database: *database,
+                    dup_sort: *dup_sort,
+                    reverse_key: *reverse_key,
+                    locked: *locked,
+                }),
+                _ => None,
+            })
+            .collect();
+        // A database is locked when any of its tabs is, that tab is kept.
+        open_databases.sort_by(|a, b| a.name.cmp(&b.name).then(b.locked.cmp(&a.locked)));
+        open_databases.dedup_by(|a, b| a.name == b.name);
+        open_databases
+    }
+
+    /// Shows the window comparing two open databases, the differing entries can be copied
+    /// from one side to the other in the write txn.
+    fn diff_ui(&mut self, ctx: &egui::Context, open_databases: &[OpenDatabase]) {
+        let Some(DiffView { a, b, diff }) = &mut self.diff_view else { return };
+        let mut open = true;
+        egui::Window::new("Compare databases").open(&mut open).default_size([600.0, 400.0]).show(
+            ctx,
+            |ui| {
+                ui.horizontal(|ui| {
+                    for (label, selected) in [("A", &mut *a), ("B", &mut *b)] {
+                        let text =
+                            selected.as_ref().map_or("choose a database", OpenDatabase::label);
+                        egui::ComboBox::new(("diff_database", label), label)
+                            .selected_text(text)
+                            .show_ui(ui, |ui| {
+                                for database in open_databases {
+                                    let checked = selected
+                                        .as_ref()
+                                        .is_some_and(|selected| selected.name == database.name);
+                                    if ui.selectable_label(checked, database.label()).clicked() {
+                                        *selected = Some(database.clone());
+                                        *diff = None;
+                                    }
+                                }
+                            });
+                    }
+                    let compare = egui::Button::new("compare");
+                    if ui.add_enabled(a.is_some() && b.is_some(), compare).clicked() {
+                        if let (Some(a), Some(b)) = (&a, &b) {
+                            let pairs = a.dup_sort || b.dup_sort;
+                            let reverse_key = a.reverse_key;
+                            let result = if a.reverse_key == b.reverse_key {
+                                let rtxn = read_txn(&self.txn);
+                                diff::diff(&a.database, &b.database, rtxn, pairs, reverse_key)
+                            } else {
+                                Err(heed::Error::Io(std::io::Error::other(
+                                    "only one of the databases compares its keys from the end",
+                                )))
+                            };
+                            *diff = result.or_report(&mut self.errors, "comparing the databases");
+                        }
+                    }
+                });
+                let (Some(a), Some(b), Some(diff)) = (&a, &b, diff) else { return };
+
+                ui.label(format!(
+                    "{} differences{}, the entries only in A are red, the ones only in B green \
+                    and the changed ones yellow.",
+                    diff.differences.len(),
+                    if diff.truncated { ", only the first ones are listed" } else { "" }
+                ));
+                let is_writing = self.txn.is_right();
+                // The tabs are locked after the databases were chosen.
+                let is_locked = |database: &OpenDatabase| {
+                    open_databases.iter().any(|open| open.name == database.name && open.locked)
+                };
+                let mut copy = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("differences").striped(true).show(ui, |ui| {
+                        ui.strong("key");
+                        ui.strong("A");
+                        ui.label("");
+                        ui.label("");
+                        ui.strong("B");
+                        ui.end_row();
+
+                        let encode = |bytes: &[u8]| ValueEncoding::Stfu8.encode(bytes);
+                        for (i, difference) in diff.differences.iter().enumerate() {
+                            let (color, key, a_data, b_data) = match difference {
+                                Difference::OnlyIn { side: Side::A, key, data } => {
+                                    (Color32::RED, key, Some(data), None)
+                                }
+                                Difference::OnlyIn { side: Side::B, key, data } => {
+                                    (Color32::GREEN, key, None, Some(data))
+                                }
+                                Difference::Changed { key, a, b } => {
+                                    (Color32::YELLOW, key, Some(a), Some(b))
+                                }
+                            };
+                            ui.colored_label(color, encode(key));
+                            ui.colored_label(
+                                color,
+                                a_data.map(|data| encode(data)).unwrap_or_default(),
+                            );
+                            for (data, side, arrow, hint, target) in [
+                                (a_data, Side::A, "→", "copy into B", b),
+                                (b_data, Side::B, "←", "copy into A", a),
+                            ] {
+                                match data {
+                                    Some(_) => {
+                                        let button = egui::Button::new(arrow).small();
+                                        let enabled = is_writing && !is_locked(target);
+                                        let button = ui.add_enabled(enabled, button);
+                                        let button =
+                                            button.on_disabled_hover_text("its tab is read-only");
+                                        if button.on_hover_text(hint).clicked() {
+                                            copy = Some((i, side));
+                                        }
+                                    }
+                                    None => {
+                                        ui.label("");
+                                    }
+                                }
+                            }
+                            ui.colored_label(
+                                color,
+                                b_data.map(|data| encode(data)).unwrap_or_default(),
+                            );
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                let (Some((i, from)), Either::Right(wtxn)) = (copy, &mut self.txn) else { return };
+                let (key, data) = match &diff.differences[i] {
+                    Difference::OnlyIn { key, data, .. } => (key, data),
+                    Difference::Changed { key, a, .. } if from == Side::A => (key, a),
+                    Difference::Changed { key, b, .. } => (key, b),
+                };
+                let target = if from == Side::A { b } else { a };
+                let result = self.op_log.put(
+                    &target.database,
+                    &target.name,
+                    target.dup_sort,
+                    wtxn,
+                    key,
+                    data,
+                );
+                if result.as_ref().is_err_and(is_map_full) {
+                    self.map_full = Some(MapFull::new(self.map_size, None));
+                }
+                if result.or_report(&mut self.errors, "copying the entry").is_some() {
+                    self.dirty_keys.insert(&target.name, key);
+                    diff.differences.remove(i);
+                }
+            },
+        );
+        if !open {
+            self.diff_view = None;
+        }
+    }
+
+    /// Focuses the tab of the given database, opening it in a new tab when there is none.
+    fn open_database_tab(&mut self, database_name: Option<String>) {
+        let is_database_tab = |tile: &Tile<Pane>| {
+            matches!(tile, Tile::Pane(Pane::DatabaseEntries { database_name: name, .. })
+                if *name == database_name)
+        };
+
+        if !self.tree.tiles.tiles.values().any(is_database_tab) {
+            let database = self
+                .env
+                .open_database(read_txn(&self.txn), database_name.as_deref())
+                .or_report(&mut self.errors, "opening the database")
+                .flatten();
+            let Some(database) = database else { return };
+
+            let pane = Pane::database_entries(
+                self.env.get(),
+                read_txn(&self.txn),
+                database_name.clone(),
+                database,
+            );
+            let tile_id = self.tree.tiles.insert_pane(pane);
+            if let Some(Tile::Container(container)) =
+                self.tree.root().and_then(|root| self.tree.tiles.get_mut(root))
+            {
+                container.add_child(tile_id);
+            }
+        }
+
+        self.tree.make_active(is_database_tab);
+    }
+}
+
+impl eframe::App for LmdbEditor {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let focused = ctx.input(|i| i.focused);
+        let gained_focus = focused && !self.focused;
+        self.focused = focused;
+        // A write txn sees its own snapshot, the pin is only for browsing.
+        if self.txn.is_right() {
+            self.pinned_at = None;
+        }
+        // The write txn is never renewed, the repaints would then come back to back.
+        let refresh = self.read_txn_refresh.filter(|_| self.txn.is_left());
+        if let (Some(secs), None) = (refresh, self.pinned_at) {
+            let interval = Duration::from_secs(secs);
+            if gained_focus || self.txn_opened.elapsed() >= interval {
+                self.renew_read_txn();
+            }
+            ctx.request_repaint_after(interval.saturating_sub(self.txn_opened.elapsed()));
+        }
+        self.poll_background_clears();
+        if self.background_writes {
+            ctx.request_repaint();
+        }
+
+        egui::TopBottomPanel::top("env_info").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let path = self.env.path();
+                let errors = &mut self.errors;
+                ui.menu_button(format!("📁 {}", path.display()), |ui| {
+                    if ui.button("copy the path").clicked() {
+                        ui.output_mut(|o| o.copied_text = path.display().to_string());
+                        ui.close_menu();
+                    }
+                    if ui.button("show in the file manager").clicked() {
+                        reveal_in_file_manager(path).or_report(errors, "showing the folder");
+                        ui.close_menu();
+                    }
+                    let terminal = ui.button("open a terminal here").on_hover_text(
+                        "to run the LMDB tools like mdb_stat or mdb_dump on the environment",
+                    );
+                    if terminal.clicked() {
+                        open_terminal(path).or_report(errors, "opening a terminal");
+                        ui.close_menu();
+                    }
+                });
+                let info = self.env.info();
+                ui.separator();
+                ui.label(format!("map size: {} MiB", info.map_size / MIB));
+                if self.space_usage.is_none() {
+                    let usage = databases::space_usage(&self.env);
+                    let usage = usage.or_report(&mut self.errors, "measuring the pages");
+                    self.space_usage = Some(usage);
+                }
+                if let Some(Some(usage)) = self.space_usage {
+                    let mib = |bytes: u64| bytes as f32 / MIB as f32;
+                    let fraction = usage.used_bytes as f32 / info.map_size.max(1) as f32;
+                    let text = format!(
+                        "{:.1} MiB used, {:.1} MiB free pages",
+                        mib(usage.used_bytes),
+                        mib(usage.free_bytes())
+                    );
+                    ui.add(egui::ProgressBar::new(fraction).text(text).desired_width(220.0))
+                        .on_hover_text(
+                            "the free pages are reused by the next writes once no read txn \
+                            can see them, refresh the read txns to release them, \
+                            a compacted backup leaves them out",
+                        );
+                }
+                if ui.small_button("↻").on_hover_text("measure again").clicked() {
+                    self.space_usage = None;
+                }
+                ui.separator();
+                ui.label(format!("max dbs: {MAX_DBS}"));
+                ui.separator();
+                let flags: Vec<_> = self
+                    .read_only
+                    .then_some("MDB_RDONLY")
+                    .into_iter()
+                    .chain(self.sync.names())
+                    .collect();
+                let flags = if flags.is_empty() { "none".to_owned() } else { flags.join(" | ") };
+                ui.label(format!("flags: {flags}"));
+                if !self.read_only {
+                    // A commit or a background clear could be flushing at the same time.
+                    let busy = self.confirming_commit || self.background_writes;
+                    let sync = ui
+                        .add_enabled(!busy, egui::Button::new("sync to disk"))
+                        .on_hover_text(if self.sync.is_reduced() {
+                            "flush the commits to the disk, they can be lost on a crash until then"
+                        } else {
+                            "flush the commits to the disk, they already are on every commit"
+                        })
+                        .on_disabled_hover_text("a commit is in progress");
+                    if sync.clicked() {
+                        let synced = self.env.force_sync();
+                        if synced.or_report(&mut self.errors, "syncing to disk").is_some() {
+                            self.synced_txn_id = Some(info.last_txn_id);
+                        }
+                    }
+                    if let Some(txn_id) = self.synced_txn_id {
+                        if txn_id == info.last_txn_id {
+                            ui.label("✔ synced");
+                        } else {
+                            ui.label(format!("synced up to txn {txn_id}"));
+                        }
+                    }
+                }
+                ui.separator();
+                ui.label(format!(
+                    "readers: {} of {}",
+                    info.number_of_readers, info.maximum_number_of_readers
+                ));
+                ui.separator();
+                ui.label(format!("last txn: {}", info.last_txn_id));
+                ui.separator();
+
+                match &self.backup {
+                    Some(backup) => {
+                        ui.spinner();
+                        ui.label(format!("backing up to {}", backup.path.display()));
+                        if let Some(result) = backup.poll() {
+                            let path = backup.path.display().to_string();
+                            if result.or_report(&mut self.errors, "backing up").is_some() {
+                                self.backup_message = Some(format!("backed up to {path}"));
+                            }
+                            self.backup = None;
+                        }
+                        ctx.request_repaint();
+                    }
+                    None => {
+                        let backup = ui.button("backup environment…").on_hover_text(
+                            "copy a snapshot of the last commit into an empty folder",
+                        );
+                        if backup.clicked() {
+                            if let Some(folder) = FileDialog::new().pick_folder() {
+                                let backup = Backup::spawn(&self.env, folder, self.compact_backup);
+                                self.backup = Some(backup);
+                                self.backup_message = None;
+                            }
+                        }
+                        ui.checkbox(&mut self.compact_backup, "compact")
+                            .on_hover_text("omit the free pages, which is slower");
+                    }
+                }
+                if let Some(message) = &self.backup_message {
+                    ui.label(message);
+                }
+            });
+        });
+
+        egui::TopBottomPanel::bottom("txn_status").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let age = self.txn_opened.elapsed();
+                if self.txn.is_right() {
+                    let Changes { insertions, deletions } = self.op_log.changes();
+                    ui.label(format!(
+                        "write txn open for {}, {insertions} insertions and {deletions} deletions \
+                        pending",
+                        format_age(age)
+                    ));
+                    if !self.nested_parents.is_empty() {
+                        ui.label(format!("in {} nested txns", self.nested_parents.len()));
+                    }
+                } else if age >= LONG_READ_TXN {
+                    let text = match self.pinned_at {
+                        Some(pinned_at) => format!(
+                            "snapshot pinned at {}, {} ago",
+                            humantime::format_rfc3339_seconds(pinned_at),
+                            format_age(age)
+                        ),
+                        None => format!("read txn open for {}", format_age(age)),
+                    };
+                    ui.colored_label(Color32::YELLOW, text).on_hover_text(
+                        "LMDB can't reuse the pages freed since a read txn was opened, \
+                        the file grows while it stays open",
+                    );
+                    if self.pinned_at.is_none() && ui.small_button("renew").clicked() {
+                        self.renew_read_txn();
+                    }
+                } else if let Some(pinned_at) = self.pinned_at {
+                    let pinned_at = humantime::format_rfc3339_seconds(pinned_at);
+                    ui.label(format!("snapshot pinned at {pinned_at}"));
+                } else {
+                    ui.label(format!("read txn open for {}", format_age(age)));
+                }
+            });
+            // The age changes every second.
+            ctx.request_repaint_after(Duration::from_secs(1));
+        });
+
+        let mut refresh_database_names = false;
+        let mut database_to_open = None;
+        egui::SidePanel::left("databases").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Databases");
+                if ui.button("⟳").on_hover_text("refresh the list of databases").clicked() {
+                    refresh_database_names = true;
+                }
+                let switcher = ui.button("🔍").on_hover_text(format!(
+                    "go to a database by name ({})",
+                    ctx.format_shortcut(&QUICK_SWITCHER_SHORTCUT)
+                ));
+                if switcher.clicked() {
+                    self.quick_switcher = Some(QuickSwitcher::default());
+                }
+            });
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if ui.selectable_label(false, "{main}").clicked() {
+                    database_to_open = Some(None);
+                }
+                for name in &self.database_names {
+                    if ui.selectable_label(false, name).clicked() {
+                        database_to_open = Some(Some(name.clone()));
+                    }
+                }
+            });
+        });
+
+        if ctx.input_mut(|i| i.consume_shortcut(&QUICK_SWITCHER_SHORTCUT)) {
+            self.quick_switcher = match self.quick_switcher {
+                Some(_) => None,
+                None => Some(QuickSwitcher::default()),
+            };
+        }
+        if let Some(switcher) = &mut self.quick_switcher {
+            match switcher.ui(ctx, &self.database_names) {
+                SwitcherAction::None => (),
+                SwitcherAction::Close => self.quick_switcher = None,
+                SwitcherAction::Open(database_name) => {
+                    self.quick_switcher = None;
+                    database_to_open = Some(database_name);
+                }
+            }
+        }
+
+        if mem::take(&mut refresh_database_names) {
+            self.refresh_database_names();
+        }
+        if let Some(database_name) = database_to_open {
+            self.open_database_tab(database_name);
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let mut dismissed = None;
+            for (i, error) in self.errors.iter().enumerate() {
+                egui::Frame::none().fill(Color32::DARK_RED).inner_margin(4.0).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("✕").clicked() {
+                            dismissed = Some(i);
+                        }
+                        ui.colored_label(Color32::WHITE, error);
+                    });
+                });
+            }
+            if let Some(i) = dismissed {
+                self.errors.remove(i);
+            }
+
+            ui.horizontal(|ui| {
+                let mut refresh = self.read_txn_refresh.is_some();
+                ui.checkbox(&mut refresh, "renew the read txn every").on_hover_text(
+                    "and when the window gets the focus back, to see what other processes \
+                    committed, the write txn is never renewed",
+                );
+                match (refresh, &mut self.read_txn_refresh) {
+                    (true, Some(secs)) => {
+                        ui.add(egui::DragValue::new(secs).clamp_range(1..=3600).suffix(" s"));
+                    }
+                    (true, None) => self.read_txn_refresh = Some(DEFAULT_READ_TXN_REFRESH_SECS),
+                    (false, _) => self.read_txn_refresh = None,
+                }
+                let can_refresh = self.txn.is_left() && self.pinned_at.is_none();
+                let refresh = ui.add_enabled(can_refresh, egui::Button::new("⟳ refresh"));
+                let refresh = refresh
+                    .on_hover_text("renew the read txn to see what other processes committed")
+                    .on_disabled_hover_text(if self.txn.is_left() {
+                        "unpin the snapshot first"
+                    } else {
+                        "commit or abort the write txn first"
+                    });
+                if refresh.clicked() {
+                    self.renew_read_txn();
+                    refresh_database_names = true;
+                }
+                let mut pinned = self.pinned_at.is_some();
+                let pin = egui::Checkbox::new(&mut pinned, "📌 pin the snapshot");
+                let pin = ui.add_enabled(self.txn.is_left(), pin).on_hover_text(
+                    "keep browsing what the read txn sees, without the commits of the other \
+                    processes, LMDB can't reuse the pages they free meanwhile",
+                );
+                if pin.changed() {
+                    self.pinned_at = pinned.then(SystemTime::now);
+                }
+                ui.checkbox(&mut self.show_tab_counts, "entry counts in the tabs").on_hover_text(
+                    "count the entries of the databases again after every change, \
+                    the duplicates of the dup-sort databases are counted too",
+                );
+                if !self.read_only {
+                    ui.checkbox(&mut self.record_timestamps, "🕓 record modification times")
+                        .on_hover_text(format!(
+                            "record when the keys put or deleted in the editor were committed, \
+                            in the {TIMESTAMPS_DATABASE} database of this environment, to show \
+                            them in the modified column. The bulk writes aren't recorded"
+                        ));
+                }
+                ui.separator();
+
+                if ui.button("compare databases…").clicked() && self.diff_view.is_none() {
+                    self.diff_view = Some(DiffView { a: None, b: None, diff: None });
+                }
+                ui.separator();
+
+                if self.read_only {
+                    ui.label("read-only environment");
+                    return;
+                }
+
+                // The shortcuts do the same as the buttons, Escape gives the focus
+                // up in the text fields and only aborts when none is focused.
+                let (mut write, mut commit, mut abort) = (false, false, false);
+                ctx.input_mut(|i| {
+                    write = i.consume_shortcut(&WRITE_SHORTCUT);
+                    commit = i.consume_shortcut(&COMMIT_SHORTCUT);
+                });
+                if ctx.memory(|m| m.focus().is_none()) {
+                    abort = ctx.input_mut(|i| i.consume_shortcut(&ABORT_SHORTCUT));
+                }
+                // Escape cancels the commit or abort being confirmed instead.
+                if abort && (self.confirming_commit || self.confirming_abort) {
+                    self.confirming_commit = false;
+                    self.confirming_abort = false;
+                    abort = false;
+                }
+
+                let env = self.env.get();
+                let button = if self.txn.is_right() {
+                    egui::Button::new("currently writing").fill(Color32::GREEN)
+                } else if self.write_lock_wait.as_ref().is_some_and(|wait| !wait.cancelled) {
+                    egui::Button::new("waiting for the write lock…").fill(Color32::YELLOW)
+                } else {
+                    egui::Button::new("currently reading").fill(Color32::RED)
+                };
+
+                let button = ui
+                    .add_enabled(!self.background_writes, button)
+                    .on_hover_text(format!(
+                        "open a write txn ({})",
+                        ctx.format_shortcut(&WRITE_SHORTCUT)
+                    ))
+                    .on_disabled_hover_text("a database is being cleared in the background");
+                write = (write && !self.background_writes) || button.clicked();
+                // The write txn is only opened once the lock is free, not to freeze the UI
+                // while another process writes.
+                if write && self.txn.is_left() {
+                    match &mut self.write_lock_wait {
+                        // The thread that was left waiting is waited for again.
+                        Some(wait) => wait.cancelled = false,
+                        None => self.write_lock_wait = Some(WriteLockWait::spawn(env)),
+                    }
+                }
+                if let Some(wait) = &self.write_lock_wait {
+                    match wait.poll() {
+                        Some(_) if wait.cancelled => self.write_lock_wait = None,
+                        Some(result) => {
+                            self.write_lock_wait = None;
+                            let errors = &mut self.errors;
+                            let wtxn = result.and_then(|()| env.write_txn());
+                            if let Some(wtxn) = wtxn.or_report(errors, "opening a write txn") {
+                                self.txn = Either::Right(wtxn);
+                                self.txn_opened = Instant::now();
+                            }
+                        }
+                        None => ctx.request_repaint_after(Duration::from_millis(100)),
+                    }
+                }
+                let contended = |wait: &&mut WriteLockWait| wait.is_contended() && !wait.cancelled;
+                if let Some(wait) = self.write_lock_wait.as_mut().filter(contended) {
+                    let mut cancel = false;
+                    egui::Window::new("Environment locked by another writer")
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ctx, |ui| {
+                            ui.label(
+                                "Another process has a write txn open on this environment, \
+                                LMDB allows a single writer at a time. The write txn is \
+                                opened once the other one is committed or aborted.",
+                            );
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                let waited = Duration::from_secs(wait.waited().as_secs());
+                                ui.label(format!(
+                                    "waiting for {}",
+                                    humantime::format_duration(waited)
+                                ));
+                            });
+                            cancel = ui.button("keep reading").clicked();
+                        });
+                    // The thread still waits for the lock, it releases it right away.
+                    if cancel {
+                        wait.cancelled = true;
+                    }
+                }
+
+                let commit_button = ui.button("commit changes").on_hover_text(
+                    ctx.format_shortcut(&COMMIT_SHORTCUT),
+                );
+                commit |= commit_button.clicked();
+                if commit && self.txn.is_right() {
+                    if self.op_log.changes().is_empty() {
+                        self.commit();
+                        refresh_database_names = true;
+                    } else {
+                        self.confirming_commit = true;
+                    }
+                }
+
+                let abort_button = ui
+                    .button("abort changes")
+                    .on_hover_text(ctx.format_shortcut(&ABORT_SHORTCUT));
+                abort |= abort_button.clicked();
+                // Nothing is lost when nothing was written, it is aborted right away.
+                if abort && self.has_pending_changes() {
+                    self.confirming_abort = true;
+                } else if abort && self.txn.is_right() {
+                    self.abort();
+                    refresh_database_names = true;
+                }
+
+                if self.txn.is_right() && ui.button("begin nested txn").clicked() {
+                    self.begin_nested_txn();
+                }
+                if !self.nested_parents.is_empty() {
+                    ui.label(format!("nested txn level {}", self.nested_parents.len()));
+                    if ui.button("commit nested").on_hover_text("into its parent txn").clicked() {
+                        self.end_nested_txn(true);
+                    }
+                    if ui.button("abort nested").clicked() {
+                        self.end_nested_txn(false);
+                    }
+                }
+
+                // The focused text fields undo and redo their own edits.
+                let (mut undo, mut redo) = (false, false);
+                if ctx.memory(|m| m.focus().is_none()) {
+                    ctx.input_mut(|i| {
+                        undo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+                        redo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+                    });
+                }
+                let undo_button = egui::Button::new("⟲ undo");
+                undo |= ui.add_enabled(self.op_log.can_undo(), undo_button).clicked();
+                let redo_button = egui::Button::new("⟳ redo");
+                redo |= ui.add_enabled(self.op_log.can_redo(), redo_button).clicked();
+
+                if let Either::Right(wtxn) = &mut self.txn {
+                    let errors = &mut self.errors;
+                    if undo && self.op_log.undo(wtxn).or_report(errors, "undoing").is_some() {
+                        self.txn_generation += 1;
+                    }
+                    if redo && self.op_log.redo(wtxn).or_report(errors, "redoing").is_some() {
+                        self.txn_generation += 1;
+                    }
+                }
+            });
+
+            if self.confirming_commit {
+                let Changes { insertions, deletions } = self.op_log.changes();
+                let mut confirmed = false;
+                egui::Window::new("Commit changes?").collapsible(false).resizable(false).show(
+                    ctx,
+                    |ui| {
+                        ui.label(format!(
+                            "You are about to commit {insertions} insertions and {deletions} deletions."
+                        ));
+                        ui.horizontal(|ui| {
+                            confirmed = ui.button("commit").clicked();
+                            if ui.button("cancel").clicked() {
+                                self.confirming_commit = false;
+                            }
+                        });
+                    },
+                );
+                if confirmed {
+                    self.commit();
+                    self.confirming_commit = false;
+                    refresh_database_names = true;
+                }
+            }
+
+            if self.confirming_abort {
+                let Changes { insertions, deletions } = self.op_log.changes();
+                let mut confirmed = false;
+                egui::Window::new("Discard changes?").collapsible(false).resizable(false).show(
+                    ctx,
+                    |ui| {
+                        ui.label(format!(
+                            "Aborting discards {insertions} insertions and {deletions} deletions."
+                        ));
+                        if !self.nested_parents.is_empty() {
+                            ui.label("The changes of the parent txns are discarded too.");
+                        }
+                        if self.created_database {
+                            ui.label("The databases created in the write txn are discarded too.");
+                        }
+                        ui.horizontal(|ui| {
+                            confirmed = ui.button("discard").clicked();
+                            if ui.button("cancel").clicked() {
+                                self.confirming_abort = false;
+                            }
+                        });
+                    },
+                );
+                if confirmed {
+                    self.abort();
+                    self.confirming_abort = false;
+                    refresh_database_names = true;
+                }
+            }
+
+            if let Some(map_full) = &mut self.map_full {
+                let mut open = true;
+                let mut grow = false;
+                egui::Window::new("The environment map is full")
+                    .open(&mut open)
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        ui.label(format!(
+                            "The map size is {} MiB, LMDB needs a larger map to write more.",
+                            self.map_size / MIB
+                        ));
+                        ui.label(
+                            "Growing the map closes and reopens the environment, the changes \
+                            that were not committed are done again in a new write txn, unless \
+                            a bulk operation changed the databases.",
+                        );
+                        if map_full.put.is_some() {
+                            ui.label("The entry that didn't fit is put again in a new write txn.");
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label("new map size");
+                            let max = usize::MAX / MIB;
+                            let size = egui::DragValue::new(&mut map_full.new_map_size);
+                            ui.add(size.clamp_range(1..=max).suffix(" MiB"));
+                        });
+                        // The environment must not be closed under the background threads.
+                        let busy = self.background_writes
+                            || self.backup.is_some()
+                            || self.write_lock_wait.is_some();
+                        grow = ui
+                            .add_enabled(!busy, egui::Button::new("reopen"))
+                            .on_disabled_hover_text(
+                                "a database is being cleared, backed up or waiting for the \
+                                 write lock in the background",
+                            )
+                            .clicked();
+                    });
+                if grow {
+                    self.grow_map = self.map_full.take();
+                } else if !open {
+                    self.map_full = None;
+                }
+            }
+
+            if refresh_database_names {
+                self.refresh_database_names();
+            }
+
+            let open_databases = self.open_databases();
+            self.diff_ui(ctx, &open_databases);
+
+            let LmdbEditor {
+                env,
+                txn,
+                tree,
+                errors,
+                read_only,
+                txn_generation,
+                op_log,
+                templates,
+                dirty_keys,
+                column_widths,
+                displays,
+                show_tab_counts,
+                tab_counts,
+                database_names,
+                timestamps,
+                settings,
+                ..
+            } = self;
+
+            let mut behavior = TreeBehavior {
+                env: env.get(),
+                txn: txn.as_mut(),
+                errors,
+                read_only: *read_only,
+                txn_generation: *txn_generation,
+                op_log,
+                templates,
+                dirty_keys,
+                column_widths,
+                displays,
+                tab_counts: show_tab_counts.then_some(tab_counts),
+                database_names,
+                timestamps: timestamps.as_ref(),
+                settings: *settings,
+                open_databases,
+                background_writes: false,
+                failed_put: None,
+                databases_changed: false,
+                closed_tiles: Vec::new(),
+                database_to_open: None,
+                activated_tab: None,
+            };
+            tree.ui(&mut behavior, ui);
+            let database_to_open = behavior.database_to_open.take();
+            if let Some((tabs_id, child)) = behavior.activated_tab {
+                if let Some(Tile::Container(Container::Tabs(tabs))) =
+                    self.tree.tiles.get_mut(tabs_id)
+                {
+                    tabs.active = Some(child);
+                }
+            }
+            // The tree forgets about the children of its containers that no longer exist.
+            for tile_id in behavior.closed_tiles.drain(..) {
+                self.tree.tiles.tiles.remove(&tile_id);
+            }
+            if behavior.background_writes {
+                self.background_writes = true;
+            }
+            if let Some(put) = behavior.failed_put.take() {
+                self.map_full = Some(MapFull::new(self.map_size, Some(put)));
+            }
+            if behavior.databases_changed {
+                self.created_database = true;
+                self.refresh_database_names();
+            }
+
+            if let Some(database_name) = database_to_open {
+                self.open_database_tab(Some(database_name));
+            }
+
+            // Automatically insert an OpenNew Tab when one is missing
+            ensure_open_new(&mut self.tree);
+        });
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, MAP_SIZE_KEY, &self.map_size);
+        eframe::set_value(storage, READ_TXN_REFRESH_KEY, &self.read_txn_refresh);
+        eframe::set_value(storage, TAB_COUNTS_KEY, &self.show_tab_counts);
+
+        let layout = layout::map_panes(&self.tree, |pane| Some(pane.saved()));
+        eframe::set_value(storage, &layout::layout_key(self.env.path()), &layout);
+        let key = layout::column_widths_key(self.env.path());
+        eframe::set_value(storage, &key, &self.column_widths);
+        let key = layout::displays_key(self.env.path());
+        eframe::set_value(storage, &key, &self.displays);
+        let key = layout::record_timestamps_key(self.env.path());
+        eframe::set_value(storage, &key, &self.record_timestamps);
+    }
+}
+
+/// Whether the tile is the tab of a database that can be closed, the tabs clearing their
+/// database in the background stay open until it is done.
+fn is_closable(tile: &Tile<Pane>) -> bool {
+    matches!(tile, Tile::Pane(Pane::DatabaseEntries { background_clear: None, .. }))
+}
+
+/// Adds an OpenNew tab when there is none anywhere in the tree, the tab can be dragged
+/// into another tab container or a split of the tree. It is added to the root tabs, or the
+/// first tab container, or the root is put in new tabs with it.
+fn ensure_open_new(tree: &mut egui_tiles::Tree<Pane>) {
+    let has_open_new =
+        tree.tiles.tiles.values().any(|tile| matches!(tile, Tile::Pane(p) if p.is_open_new()));
+    if has_open_new {
+        return;
+    }
+    let open_new = tree.tiles.insert_pane(Pane::open_new());
+    let Some(root) = tree.root else {
+        tree.root = Some(tree.tiles.insert_tab_tile(vec![open_new]));
+        return;
+    };
+    let tabs_id = match tree.tiles.get(root) {
+        Some(Tile::Container(Container::Tabs(_))) => Some(root),
+        _ => tree
+            .tiles
+            .tiles
+            .iter()
+            .find(|(_, tile)| matches!(tile, Tile::Container(Container::Tabs(_))))
+            .map(|(&tile_id, _)| tile_id),
+    };
+    match tabs_id.and_then(|tabs_id| tree.tiles.get_mut(tabs_id)) {
+        Some(Tile::Container(Container::Tabs(tabs))) => tabs.children.push(open_new),
+        _ => tree.root = Some(tree.tiles.insert_tab_tile(vec![root, open_new])),
+    }
+}
+
+/// Formats a number of entries in a few characters, e.g. `1.2k`.
+fn format_count(count: u64) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{:.1}k", count as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1}M", count as f64 / 1e6),
+        _ => format!("{:.1}G", count as f64 / 1e9),
+    }
+}
+
+/// Formats the age of a txn, to the second.
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, secs) => format!("{secs}s"),
+        (0, mins, secs) => format!("{mins}m {secs:02}s"),
+        (hours, mins, _) => format!("{hours}h {mins:02}m"),
+    }
+}
+
+/// Opens the folder in the file manager of the platform.
+fn reveal_in_file_manager(path: &std::path::Path) -> std::io::Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program).arg(path).spawn().map(drop)
+}
+
+/// Opens a terminal of the platform in the folder, `$TERMINAL` on Linux when it is set.
+fn open_terminal(path: &std::path::Path) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/c", "start", "cmd"]);
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("open");
+        command.args(["-a", "Terminal"]).arg(path);
+        command
+    } else {
+        let terminal = std::env::var("TERMINAL");
+        std::process::Command::new(terminal.as_deref().unwrap_or("x-terminal-emulator"))
+    };
+    command.current_dir(path).spawn().map(drop)
+}
+
+/// Returns the read txn or the current write txn to read with.
+fn read_txn<'a>(txn: &'a Either<RoTxn<'static>, RwTxn<'static>>) -> &'a RoTxn<'static> {
+    match txn {
+        Either::Left(rtxn) => rtxn,
+        Either::Right(wtxn) => wtxn,
+    }
+}
+
+/// Replaces the right value by the left one `f` returns and returns it, `f` is only called
+/// then. The right value is left in place when `f` fails.
+fn replace_right_with<L, R, E>(
+    either: &mut Either<L, R>,
+    f: impl FnOnce() -> Result<L, E>,
+) -> Result<Option<R>, E> {
+    if either.is_left() {
+        return Ok(None);
+    }
+    match mem::replace(either, Either::Left(f()?)) {
+        Either::Left(_) => unreachable!(),
+        Either::Right(right) => Ok(Some(right)),
+    }
+}
+
+// There are only a few panes, boxing the biggest variant isn't worth it.
+#[allow(clippy::large_enum_variant)]
+enum Pane {
+    DatabaseEntries {
+        database_name: Option<String>,
+        database: Database<ByteSlice, ByteSlice>,
+        /// Whether the database stores multiple values per key, the entries
+        /// are then deleted by key and data pair and never edited in place.
+        dup_sort: bool,
+        /// Whether the keys are compared from their end, with `MDB_REVERSEKEY`, the
+        /// key prefix is then matched at the end of the keys.
+        reverse_key: bool,
+        /// Whether the tab hides its write actions even in a write txn, and no entry
+        /// can be copied into its database from the other tabs.
+        locked: bool,
+        entry_to_insert: EscapedEntry,
+        /// The size in KiB above which the data to insert is flagged, when it is.
+        max_data_kib: Option<usize>,
+        /// The row index and the escaped entry of the value being edited in place.
+        editing_cell: Option<(usize, EscapedEntry)>,
+        /// The row index of the selected entry, moved with the arrow keys,
+        /// its value is shown in a hex dump.
+        selected_row: Option<usize>,
+        /// Only show the entries whose displayed key contains this string.
+        filter: String,
+        /// The number of matching entries to collect when filtering, at least a viewport.
+        filter_limit: usize,
+        /// Whether the filter is a glob the whole displayed key must match.
+        glob_filter: bool,
+        /// The search for the keys matching the glob filter, it goes on over many frames.
+        glob_scan: Option<GlobScan>,
+        /// The search with a regex, it takes over the other filters when it has a pattern.
+        regex_search: RegexSearch,
+        /// The page of entries shown, all the entries are scrolled through when there is none.
+        pagination: Option<Pagination>,
+        /// Where the rows are when scrolling through all the entries.
+        row_cache: RowCache,
+        /// Only show the entries whose key starts with this prefix, in the display encoding.
+        prefix: String,
+        /// The encoding the keys and values are displayed and edited with.
+        display_encoding: ValueEncoding,
+        /// How the keys are interpreted, the integer keys are displayed and edited in decimal.
+        key_type: KeyType,
+        /// Whether the display saved for the database was applied, when the tab is first shown.
+        display_restored: bool,
+        /// Whether clearing the database waits for the user to confirm it.
+        confirming_clear: bool,
+        /// The encoding of the keys and values in the exported CSV files.
+        csv_encoding: ValueEncoding,
+        /// Whether the exports write all the entries or only the ones matching the filters.
+        export_scope: ExportScope,
+        /// The export of the current results waiting for the user to confirm it,
+        /// with the number of entries it writes.
+        pending_export: Option<(ExportFormat, u64)>,
+        /// Whether the imported entries replace the existing ones with the same key.
+        import_overwrite: bool,
+        /// The outcome of the last action, shown under the actions.
+        message: Option<String>,
+        /// The entry to copy into a database that already has its key,
+        /// waiting for the user to overwrite the key or to skip the entry.
+        entry_to_copy: Option<EntryCopy>,
+        /// Whether a column shows the byte lengths of the keys and values.
+        show_lengths: bool,
+        /// Whether the modified column shows when the keys were last written, if recorded.
+        show_modified: bool,
+        /// The sum of the sizes of all the values, only summed when the byte lengths are shown,
+        /// a bounded number of entries per frame. It is cancelled when summing failed, the
+        /// error was reported once.
+        total_value_bytes: Option<MatchCount>,
+        /// The clear running in the background, the other write actions are disabled.
+        background_clear: Option<BackgroundClear>,
+        /// Whether the imported and pasted entries are said to be sorted by key,
+        /// they are then appended with `MDB_APPEND`, which is much faster.
+        append_sorted: bool,
+        /// Whether the imported and pasted entries are compared to the existing ones first,
+        /// for the user to confirm the import.
+        preview_imports: bool,
+        /// The import waiting for the user to confirm it, with what it would do.
+        import_preview: Option<(BulkInsert, ImportPreview)>,
+        /// The text of the dialog to paste many entries, when it is open.
+        paste_many: Option<String>,
+        /// The bulk insert that stopped at an entry out of order, waiting for
+        /// the user to put the remaining entries normally or to stop there.
+        bulk_insert: Option<BulkInsert>,
+        /// The copy of the entries of a database of another environment, when its window is open.
+        env_merge: Option<EnvMerge>,
+        /// The entries being edited in an external editor, when its window is open.
+        external_edit: Option<ExternalEdit>,
+        /// The index of the row to go to, among the rows shown.
+        goto_row: usize,
+        /// What separates the segments of the keys, the rows jump from group to group
+        /// of the first segment and the key tree is split at it.
+        group_delimiter: String,
+        /// The keys as a tree of their prefixes, when its window is open.
+        prefix_tree: Option<PrefixTree>,
+        /// The count of the entries matching a pattern, when its window is open.
+        count_tool: Option<CountTool>,
+        /// The decoder the value of the selected row is also shown with, if any.
+        value_decoder: Option<&'static dyn ValueDecoder>,
+        /// How the values are compressed, they are then decompressed to be shown and the
+        /// data inserted from the form is compressed.
+        value_compression: Option<Compression>,
+        /// How the value the insert form was filled from is compressed, `Some(None)` when it
+        /// isn't: the data is inserted compressed like it was rather than with the selected
+        /// compression. `None` when the form wasn't filled from a value.
+        form_compression: Option<Option<Compression>>,
+        /// The value of the selected row decompressed, for the row, key and entries version.
+        decompressed_value: Option<DecompressedValue>,
+        /// The fields at the start of the values, shown in their own columns.
+        value_layout: ValueLayout,
+        /// Whether the window editing the value layout is open.
+        editing_layout: bool,
+        /// The end of the database whose entries are listed, when their window is open.
+        sample: Option<SampleEnd>,
+        /// The number of entries listed when peeking at an end of the database.
+        sample_size: usize,
+        /// The entries listed when peeking, read again when what they were read for changed.
+        sampled_entries: Option<SampledEntries>,
+        /// The entry whose key is being renamed, when the rename dialog is open.
+        key_rename: Option<KeyRename>,
+        /// The hex grid the data to put is edited with instead of the text field, when shown.
+        hex_editor: Option<HexEditor>,
+        /// The prefix of the keys to delete, in the display encoding.
+        prefix_to_delete: String,
+        /// The deletion of the entries with a prefix, waiting for confirmation or running.
+        prefix_deletion: Option<PrefixDeletion>,
+        /// The sampled sizes of the values, once the user asked for them.
+        value_sizes: Option<ValueSizes>,
+        /// The boundaries of the buckets the sampled sizes are counted in, in bytes.
+        bucket_boundaries: String,
+        /// Whether the rows are in descending order of the keys, the highest ones first.
+        reverse: bool,
+        /// Whether the valid UTF-8 keys and values are shown as they are instead of in the
+        /// display encoding, they are still edited and copied in the display encoding.
+        plain_utf8: bool,
+        /// Whether the long keys and values are cut with an ellipsis, shown in full on hover.
+        truncate_cells: bool,
+        /// The keys of the rows expanded to show their whole key and value.
+        expanded_keys: HashSet<Vec<u8>>,
+        /// The heights of the expanded rows when they were last shown, by row index.
+        expanded_heights: HashMap<usize, f32>,
+        /// Whether the expanded rows also show their key and value in hex.
+        expand_hex: bool,
+        /// The text searched in the value of the selected row.
+        value_query: String,
+        /// How the keys referred to by the value of the selected row are found in it.
+        reference_rule: ReferenceRule,
+        /// The index of the match of the query the value view jumped to.
+        value_match: usize,
+        /// The number of entries and the version it was counted for, see `entries_version`.
+        entry_count: Option<((u64, u64, u64), u64)>,
+        /// The raw keys checked to be deleted together, they stay checked while scrolling.
+        checked_keys: HashSet<Vec<u8>>,
+        /// Whether the user is asked to confirm deleting many checked keys.
+        confirming_checked_delete: bool,
+    },
+    OpenNew {
+        database_to_open: String,
+        /// Whether the name field got the focus, it gets it once when the tab appears.
+        focused: bool,
+        /// The name of the database the user tried to open that doesn't exist.
+        missing: Option<String>,
+    },
+}
+
+impl Pane {
+    fn open_new() -> Pane {
+        Pane::OpenNew { database_to_open: String::new(), focused: false, missing: None }
+    }
+
+    fn database_entries(
+        env: &Env,
+        rtxn: &RoTxn,
+        database_name: Option<String>,
+        database: Database<ByteSlice, ByteSlice>,
+    ) -> Pane {
+        let dup_sort =
+            database_name.as_deref().is_some_and(|name| databases::is_dup_sort(env, rtxn, name));
+        let reverse_key =
+            database_name.as_deref().is_some_and(|name| databases::is_reverse_key(env, rtxn, name));
+        Pane::DatabaseEntries {
+            database_name,
+            database,
+            dup_sort,
+            reverse_key,
+            locked: false,
+            entry_to_insert: EscapedEntry::default(),
+            max_data_kib: None,
+            editing_cell: None,
+            selected_row: None,
+            filter: String::new(),
+            filter_limit: 0,
+            glob_filter: false,
+            glob_scan: None,
+            regex_search: RegexSearch::default(),
+            pagination: None,
+            row_cache: RowCache::default(),
+            prefix: String::new(),
+            display_encoding: ValueEncoding::default(),
+            key_type: KeyType::default(),
+            display_restored: false,
+            confirming_clear: false,
+            csv_encoding: ValueEncoding::default(),
+            export_scope: ExportScope::All,
+            pending_export: None,
+            import_overwrite: false,
+            message: None,
+            entry_to_copy: None,
+            show_lengths: false,
+            show_modified: false,
+            total_value_bytes: None,
+            background_clear: None,
+            append_sorted: false,
+            preview_imports: false,
+            import_preview: None,
+            paste_many: None,
+            bulk_insert: None,
+            env_merge: None,
+            external_edit: None,
+            goto_row: 0,
+            group_delimiter: ":".to_owned(),
+            prefix_tree: None,
+            count_tool: None,
+            value_decoder: None,
+            value_compression: None,
+            form_compression: None,
+            decompressed_value: None,
+            value_layout: ValueLayout::default(),
+            editing_layout: false,
+            sample: None,
+            sampled_entries: None,
+            sample_size: DEFAULT_SAMPLE_SIZE,
+            key_rename: None,
+            hex_editor: None,
+            prefix_to_delete: String::new(),
+            prefix_deletion: None,
+            value_sizes: None,
+            bucket_boundaries: value_sizes::DEFAULT_BOUNDARIES.to_owned(),
+            reverse: false,
+            plain_utf8: false,
+            truncate_cells: false,
+            expanded_keys: HashSet::new(),
+            expanded_heights: HashMap::new(),
+            expand_hex: false,
+            value_query: String::new(),
+            reference_rule: ReferenceRule::default(),
+            value_match: 0,
+            entry_count: None,
+            checked_keys: HashSet::new(),
+            confirming_checked_delete: false,
+        }
+    }
+
+    /// Returns what is remembered of this pane between runs.
+    fn saved(&self) -> SavedPane {
+        match self {
+            Pane::DatabaseEntries { database_name, .. } => {
+                SavedPane::DatabaseEntries { database_name: database_name.clone() }
+            }
+            Pane::OpenNew { .. } => SavedPane::OpenNew,
+        }
+    }
+
+    fn is_open_new(&self) -> bool {
+        matches!(self, Pane::OpenNew { .. })
+    }
+}
+
+/// Pages through the entries by seeking after the last key of the previous page.
+struct Pagination {
+    page_size: usize,
+    /// The last entry of each of the previous pages, the current page starts after the last
+    /// one: where the rows of its key start and its data, a dup-sort key can span pages.
+    last_entries: Vec<(KeyStart, Vec<u8>)>,
+}
+
+impl Pagination {
+    const PAGE_SIZES: [usize; 3] = [100, 500, 1000];
+
+    fn new(page_size: usize) -> Pagination {
+        Pagination { page_size, last_entries: Vec::new() }
+    }
+
+    /// The index of the current page, starting at zero.
+    fn page(&self) -> usize {
+        self.last_entries.len()
+    }
+}
+
+/// The end of a database whose entries are peeked at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SampleEnd {
+    First,
+    Last,
+}
+
+/// A database opened in a tab, that entries can be copied into.
+#[derive(Clone)]
+struct OpenDatabase {
+    name: Option<String>,
+    database: Database<ByteSlice, ByteSlice>,
+    dup_sort: bool,
+    reverse_key: bool,
+    /// See `Pane::DatabaseEntries::locked`.
+    locked: bool,
+}
+
+impl OpenDatabase {
+    fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or("{main}")
+    }
+}
+
+/// Two open databases being compared.
+struct DiffView {
+    a: Option<OpenDatabase>,
+    b: Option<OpenDatabase>,
+    /// Their differences, once compared.
+    diff: Option<DatabaseDiff>,
+}
+
+/// An entry to copy from the database of a tab into another open database.
+struct EntryCopy {
+    key: Vec<u8>,
+    data: Vec<u8>,
+    target: OpenDatabase,
+    /// Whether the entry is deleted from its database once copied.
+    moving: bool,
+}
+
+/// A put that failed because the map of the environment is full, to retry it once grown.
+struct FailedPut {
+    database_name: Option<String>,
+    dup_sort: bool,
+    key: Vec<u8>,
+    data: Vec<u8>,
+}
+
+/// The map of the environment is full, the user is offered to reopen it with a larger one.
+struct MapFull {
+    /// The map size to reopen the environment with, in MiB.
+    new_map_size: usize,
+    /// The put to retry in the reopened environment, if it is a put that failed.
+    put: Option<FailedPut>,
+    /// The operations and keys written by the write txn LMDB aborted when failing to commit
+    /// it, the write txn still open has them otherwise.
+    lost_txn: Option<(OpLog, DirtyKeys)>,
+}
+
+impl MapFull {
+    /// Offers to double the map size, which is in bytes.
+    fn new(map_size: usize, put: Option<FailedPut>) -> MapFull {
+        MapFull { new_map_size: (map_size / MIB).max(1) * 2, put, lost_txn: None }
+    }
+}
+
+/// An entry whose key is being renamed.
+struct KeyRename {
+    old_key: Vec<u8>,
+    data: Vec<u8>,
+    /// The new key, in the display encoding or as an integer.
+    new_key: String,
+    /// Whether the new key already exists, renaming then overwrites its data.
+    collides: bool,
+}
+
+/// The deletion of the entries whose key starts with a prefix, in the write txn.
+struct PrefixDeletion {
+    prefix: Vec<u8>,
+    /// The number of entries with the prefix when the deletion was asked for.
+    total: u64,
+    /// The number of entries deleted so far.
+    deleted: u64,
+    /// Whether the user confirmed the deletion, a chunk of entries is then deleted per frame.
+    confirmed: bool,
+    /// Whether the user confirmed deleting all the entries, with an empty prefix.
+    whole_database: bool,
+}
+
+struct TreeBehavior<'a> {
+    /// The environment of the tree, every environment has its own one.
+    env: &'static Env,
+    txn: Either<&'a mut RoTxn<'static>, &'a mut RwTxn<'static>>,
+    errors: &'a mut Vec<String>,
+    /// Whether the environment is read-only and the write actions must be hidden.
+    read_only: bool,
+    /// See `LmdbEditor::txn_generation`.
+    txn_generation: u64,
+    op_log: &'a mut OpLog,
+    templates: &'a mut ValueTemplates,
+    dirty_keys: &'a mut DirtyKeys,
+    column_widths: &'a mut ColumnWidths,
+    displays: &'a mut SavedDisplays,
+    /// The number of entries to show in the tab titles, when they are shown.
+    tab_counts: Option<&'a mut EntryCounts>,
+    /// The named databases of the environment, suggested when opening a database.
+    database_names: &'a [String],
+    /// The timestamps database, when it exists, see `LmdbEditor::record_timestamps`.
+    timestamps: Option<&'a Database<ByteSlice, ByteSlice>>,
+    /// How the databases never shown before are shown.
+    settings: Settings,
+    /// The databases opened in the tabs of the tree.
+    open_databases: Vec<OpenDatabase>,
+    /// Whether a pane started clearing its database in the background.
+    background_writes: bool,
+    /// The put that failed because the map is full.
+    failed_put: Option<FailedPut>,
+    /// Whether a database was created or dropped and the list of databases must be refreshed.
+    databases_changed: bool,
+    /// The tiles to remove from the tree once it has been rendered.
+    closed_tiles: Vec<egui_tiles::TileId>,
+    /// The named database to open in a tab, or focus the tab of, once the tree has been
+    /// rendered.
+    database_to_open: Option<String>,
+    /// The tab chosen in the overflow menu of a tab container, activated once the tree
+    /// has been rendered.
+    activated_tab: Option<(egui_tiles::TileId, egui_tiles::TileId)>,
+}
+
+impl TreeBehavior<'_> {
+    /// Returns the read txn or the current write txn to read with.
+    fn rtxn(&self) -> &RoTxn<'static> {
+        match &self.txn {
+            Either::Left(rtxn) => rtxn,
+            Either::Right(wtxn) => wtxn,
+        }
+    }
+
+    /// Changes when any tab writes, or when the txn is renewed and could see the commits
+    /// of other processes, the number of entries of the databases must be counted again.
+    fn entries_version(&self) -> (u64, u64, u64) {
+        let Changes { insertions, deletions } = self.op_log.changes();
+        (self.txn_generation, insertions, deletions)
+    }
+}
+
+/// A value decompressed from a row, for the row index, the key and the entries version.
+type DecompressedValue =
+    ((usize, Vec<u8>, (u64, u64, u64)), Result<(Compression, Vec<u8>), String>);
+
+/// The key and value texts of the entries at an end of a database, for the end, the number
+/// of entries, how they are shown and the entries version.
+type SampledEntries =
+    ((SampleEnd, usize, KeyType, ValueEncoding, (u64, u64, u64)), Option<Vec<(String, String)>>);
+
+/// Returns the number of entries of the database, only counted again when `version` changed.
+fn cached_len(
+    cache: &mut Option<((u64, u64, u64), u64)>,
+    database: &Database<ByteSlice, ByteSlice>,
+    rtxn: &RoTxn,
+    version: (u64, u64, u64),
+) -> heed::Result<u64> {
+    match *cache {
+        Some((cached_version, len)) if cached_version == version => Ok(len),
+        _ => {
+            let len = database.len(rtxn)?;
+            *cache = Some((version, len));
+            Ok(len)
+        }
+    }
+}
+
+impl egui_tiles::Behavior<Pane> for TreeBehavior<'_> {
+    fn tab_title_for_pane(&mut self, pane: &Pane) -> egui::WidgetText {
+        let version = self.entries_version();
+        let title = match pane {
+            Pane::DatabaseEntries { database_name: Some(name), .. } => format!("{name}"),
+            Pane::DatabaseEntries { database_name: None, .. } => format!("{{main}}"),
+            Pane::OpenNew { .. } => return format!("Open new").into(),
+        };
+        let (Some(tab_counts), Pane::DatabaseEntries { database_name, database, .. }) =
+            (&mut self.tab_counts, pane)
+        else {
+            return title.into();
+        };
+        let rtxn: &RoTxn = match &self.txn {
+            Either::Left(rtxn) => rtxn,
+            Either::Right(wtxn) => wtxn,
+        };
+        // The counts are only refreshed when the entries changed, not every frame.
+        let mut cache = tab_counts.get(database_name).copied();
+        let count = cached_len(&mut cache, database, rtxn, version);
+        match cache {
+            Some(cache) if tab_counts.get(database_name) != Some(&cache) => {
+                tab_counts.insert(database_name.clone(), cache);
+            }
+            _ => (),
+        }
+        match count {
+            Ok(count) => format!("{title} ({})", format_count(count)).into(),
+            Err(_) => title.into(),
+        }
+    }
+
+    fn on_tab_button(
+        &mut self,
+        tiles: &egui_tiles::Tiles<Pane>,
+        tile_id: egui_tiles::TileId,
+        button_response: &egui::Response,
+    ) {
+        if !tiles.get(tile_id).is_some_and(is_closable) {
+            return;
+        }
+        if button_response.middle_clicked() {
+            self.closed_tiles.push(tile_id);
+        }
+        button_response.clone().context_menu(|ui| {
+            if ui.button("close tab").clicked() {
+                self.closed_tiles.push(tile_id);
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn top_bar_rtl_ui(
+        &mut self,
+        tiles: &egui_tiles::Tiles<Pane>,
+        ui: &mut egui::Ui,
+        tile_id: egui_tiles::TileId,
+        tabs: &egui_tiles::Tabs,
+    ) {
+        let closable_tiles = || tiles.tiles.iter().filter(|(_, tile)| is_closable(tile));
+        let close_all = egui::Button::new("close all").small();
+        let close_all = ui.add_enabled(closable_tiles().next().is_some(), close_all);
+        if close_all.on_hover_text("close the tabs of all the databases").clicked() {
+            self.closed_tiles.extend(closable_tiles().map(|(&tile_id, _)| tile_id));
+        }
+        if let Some(active) =
+            tabs.active.filter(|&active| tiles.get(active).is_some_and(is_closable))
+        {
+            if ui.small_button("✕").on_hover_text("close the tab").clicked() {
+                self.closed_tiles.push(active);
+            }
+        }
+
+        // The tabs are measured like `Behavior::tab_ui` lays them out, those that don't fit
+        // are cut off and can only be reached from the menu.
+        let font_id = egui::TextStyle::Button.resolve(ui.style());
+        let x_margin = self.tab_title_spacing(ui.visuals());
+        let titles: Vec<_> = tabs
+            .children
+            .iter()
+            .map(|&child| (child, self.tab_title_for_tile(tiles, child)))
+            .collect();
+        let tabs_width: f32 = titles
+            .iter()
+            .map(|(_, title)| {
+                let galley =
+                    title.clone().into_galley(ui, Some(false), f32::INFINITY, font_id.clone());
+                galley.size().x + 2.0 * x_margin
+            })
+            .sum();
+        if tabs_width <= ui.available_width() - OVERFLOW_BUTTON_WIDTH {
+            return;
+        }
+        ui.menu_button("⏷", |ui| {
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                for (child, title) in titles {
+                    if ui.selectable_label(tabs.is_active(child), title).clicked() {
+                        self.activated_tab = Some((tile_id, child));
+                        ui.close_menu();
+                    }
+                }
+            });
+        })
+        .response
+        .on_hover_text("all the tabs");
+    }
+
+    fn pane_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        tile_id: egui_tiles::TileId,
+        pane: &mut Pane,
+    ) -> egui_tiles::UiResponse {
+        ui.add_space(5.0);
+
+        match pane {
+            Pane::DatabaseEntries {
+                database,
+                dup_sort,
+                reverse_key,
+                locked,
+                entry_to_insert,
+                max_data_kib,
+                database_name,
+                editing_cell,
+                selected_row,
+                filter,
+                filter_limit,
+                glob_filter,
+                glob_scan,
+                regex_search,
+                pagination,
+                row_cache,
+                prefix,
+                display_encoding,
+                key_type,
+                display_restored,
+                confirming_clear,
+                csv_encoding,
+                export_scope,
+                pending_export,
+                import_overwrite,
+                message,
+                entry_to_copy,
+                show_lengths,
+                show_modified,
+                total_value_bytes,
+                background_clear,
+                append_sorted,
+                preview_imports,
+                import_preview,
+                paste_many,
+                bulk_insert,
+                env_merge,
+                external_edit,
+                goto_row,
+                group_delimiter,
+                prefix_tree,
+                count_tool,
+                value_decoder,
+                value_compression,
+                form_compression,
+                decompressed_value,
+                value_layout,
+                editing_layout,
+                sample,
+                sample_size,
+                sampled_entries,
+                key_rename,
+                hex_editor,
+                prefix_to_delete,
+                prefix_deletion,
+                value_sizes,
+                bucket_boundaries,
+                reverse,
+                plain_utf8,
+                truncate_cells,
+                expanded_keys,
+                expanded_heights,
+                expand_hex,
+                value_query,
+                reference_rule,
+                value_match,
+                entry_count,
+                checked_keys,
+                confirming_checked_delete,
+            } => {
+                let name = database_name.as_ref().map_or_else(|| "{main}".to_owned(), Clone::clone);
+                // A read-only environment or tab has nothing to put entries with.
+                // However the tab was opened, the database is shown like it last was,
+                // or with the defaults of the settings.
+                if !*display_restored {
+                    match self.displays.get(database_name) {
+                        Some(saved) => {
+                            *display_encoding = saved.encoding;
+                            *key_type = saved.key_type;
+                        }
+                        None => {
+                            *display_encoding = self.settings.encoding;
+                            *key_type = self.settings.key_type;
+                        }
+                    }
+                    *pagination = self.settings.page_size.map(Pagination::new);
+                    *display_restored = true;
+                }
+
+                let read_only = self.read_only || *locked;
+                if !read_only {
+                    egui::Window::new(format!("Put an entry into {name}")).default_pos([720.0, 480.0]).show(ui.ctx(), |ui| {
+                        ui.style_mut().spacing.interact_size.y = 0.0; // hack to make `horizontal_wrapped` work better with text.
+
+                        ui.label("We use STFU-8 as a hacky text encoding/decoding protocol for data that might be not quite UTF-8 but is still mostly UTF-8. \
+                        It is based on the syntax of the repr created when you write (or print) binary text in python, C or other common programming languages.");
+
+                        ui.add_space(8.0);
+
+                        ui.label("Basically STFU-8 is the text format you already write when use escape codes in C, python, rust, etc. \
+                        It permits binary data in UTF-8 by escaping them with \\, for instance \\n and \\x0F.");
+
+                        ui.add_space(8.0);
+
+                        ui.horizontal_wrapped(|ui| {
+                            ui.spacing_mut().item_spacing.x = 0.0;
+                            ui.label("More about how we interpret encoding/decoding ");
+                            ui.hyperlink_to("on the stfu8 documentation", "https://docs.rs/stfu8");
+                            ui.label(".");
+                        });
+
+                        ui.separator();
+
+                        let encoding = *display_encoding;
+                        let key_type = *key_type;
+                        ui.label(format!("The key and data are decoded from {}, the display encoding of the table.", encoding.label()));
+                        if key_type != KeyType::Bytes {
+                            ui.label(format!("The key is a decimal {}.", key_type.label()));
+                        }
+
+                        let mut show_grid = hex_editor.is_some();
+                        if ui.checkbox(&mut show_grid, "edit the data as hex bytes").changed() {
+                            if show_grid {
+                                let data = entry_to_insert.decoded_data(encoding);
+                                if let Some(data) = data.or_report(self.errors, "invalid data") {
+                                    *hex_editor = Some(HexEditor::new(&data));
+                                    entry_to_insert.data.clear();
+                                }
+                            } else if let Some(editor) = hex_editor {
+                                let data = editor.bytes();
+                                if let Some(data) = data.or_report(self.errors, "invalid data") {
+                                    entry_to_insert.data = encoding.encode(&data);
+                                    *hex_editor = None;
+                                }
+                            }
+                        }
+
+                        // The key and data are decoded every frame to show why they are invalid
+                        // next to them, a malformed escape sequence can't be put or deleted.
+                        let EscapedEntry { key, data } = entry_to_insert;
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(key)
+                                .hint_text(format!("{} key", match key_type {
+                                    KeyType::Bytes => encoding.label(),
+                                    key_type => key_type.label(),
+                                })));
+                            let decoded_key = key_type.decode(key, encoding);
+                            if let (Err(e), false) = (decoded_key, key.is_empty()) {
+                                ui.colored_label(Color32::RED, format!("invalid key: {e:#}"));
+                            }
+                        });
+                        let key_valid = key_type.decode(key, encoding).is_ok();
+                        match hex_editor.as_mut() {
+                            Some(editor) => {
+                                // The rows loaded into the form while the grid is shown
+                                // fill the text field, their data goes into the grid.
+                                if !data.is_empty() {
+                                    if let Ok(bytes) = encoding.decode(&mem::take(data)) {
+                                        *editor = HexEditor::new(&bytes);
+                                    }
+                                }
+                                editor.ui(ui, (tile_id, "hex_editor"));
+                            }
+                            None => {
+                                ui.add(egui::TextEdit::multiline(data)
+                                    .hint_text(format!("{} data", encoding.label())));
+                                if let Err(e) = encoding.decode(data) {
+                                    ui.colored_label(Color32::RED, format!("invalid data: {e:#}"));
+                                }
+                            }
+                        }
+                        let current = match hex_editor.as_ref() {
+                            Some(editor) => editor.bytes().ok(),
+                            None => encoding.decode(data).ok(),
+                        };
+                        ui.horizontal(|ui| {
+                            let mut warn = max_data_kib.is_some();
+                            ui.checkbox(&mut warn, "warn about data larger than").on_hover_text(
+                                "to catch a large paste by mistake before it fills the map, \
+                                the data can still be inserted",
+                            );
+                            match (warn, max_data_kib.as_mut()) {
+                                (true, Some(kib)) => {
+                                    let kib = egui::DragValue::new(kib).clamp_range(1..=1 << 20);
+                                    ui.add(kib.suffix(" KiB"));
+                                }
+                                (true, None) => *max_data_kib = Some(DEFAULT_MAX_DATA_KIB),
+                                (false, _) => *max_data_kib = None,
+                            }
+                        });
+                        if let (Some(kib), Some(bytes)) = (*max_data_kib, &current) {
+                            if bytes.len() > kib * 1024 {
+                                ui.colored_label(
+                                    Color32::YELLOW,
+                                    format!("the data is {} bytes, more than {kib} KiB", bytes.len()),
+                                );
+                            }
+                        }
+                        let id_source = (tile_id, "value_template");
+                        if let Some(bytes) = self.templates.ui(ui, id_source, current.as_deref()) {
+                            match hex_editor.as_mut() {
+                                Some(editor) => *editor = HexEditor::new(&bytes),
+                                None => *data = encoding.encode(&bytes),
+                            }
+                        }
+                        // The grid bytes are put as they are, without decoding them.
+                        let decoded_data = |entry: &EscapedEntry, grid: &Option<HexEditor>| {
+                            match grid {
+                                Some(editor) => editor.bytes(),
+                                None => entry.decoded_data(encoding),
+                            }
+                        };
+
+                        if ui.button("paste many…").clicked() && paste_many.is_none() {
+                            *paste_many = Some(String::new());
+                        }
+
+                        let data_valid = decoded_data(entry_to_insert, hex_editor).is_ok();
+                        // The value the form was filled from is compressed again the same way.
+                        let inserted_compression = match *form_compression {
+                            Some(detected) if value_compression.is_some() => detected,
+                            _ => *value_compression,
+                        };
+
+                        let insert = egui::Button::new("insert");
+                        if ui.add_enabled(key_valid && data_valid, insert).clicked() {
+                            if let Either::Right(wtxn) = self.txn.as_mut() {
+                                let errors = &mut *self.errors;
+                                let key = key_type.decode(&entry_to_insert.key, encoding);
+                                let data = decoded_data(entry_to_insert, hex_editor);
+                                let data = data.and_then(|data| match inserted_compression {
+                                    Some(compression) => Ok(compression.compress(&data)?),
+                                    None => Ok(data),
+                                });
+                                let key = key.or_report(errors, "invalid key");
+                                let data = data.or_report(errors, "invalid data");
+                                if let (Some(key), Some(data)) = (key, data) {
+                                    let op_log = &mut *self.op_log;
+                                    let result = op_log.put(database, database_name, *dup_sort, wtxn, &key, &data);
+                                    if result.as_ref().is_err_and(is_map_full) {
+                                        self.failed_put = Some(FailedPut {
+                                            database_name: database_name.clone(),
+                                            dup_sort: *dup_sort,
+                                            key: key.clone(),
+                                            data,
+                                        });
+                                    }
+                                    if result.or_report(errors, "putting an entry").is_some() {
+                                        self.dirty_keys.insert(database_name, &key);
+                                        entry_to_insert.clear();
+                                        *form_compression = None;
+                                        if let Some(editor) = hex_editor {
+                                            *editor = HexEditor::new(&[]);
+                                        }
+                                        row_cache.clear();
+                                        *total_value_bytes = None;
+                                    }
+                                }
+                            }
+                        }
+
+                        // Only the pairs of the dup-sort databases are deleted with their data.
+                        let can_delete = key_valid && (data_valid || !*dup_sort);
+                        if ui.add_enabled(can_delete, egui::Button::new("delete")).clicked() {
+                            if let Either::Right(wtxn) = self.txn.as_mut() {
+                                let errors = &mut *self.errors;
+                                let key = key_type.decode(&entry_to_insert.key, encoding);
+                                if let Some(key) = key.or_report(errors, "invalid key") {
+                                    // Only delete the given data of a key, when there is one.
+                                    let has_data = match hex_editor {
+                                        Some(editor) => !editor.is_empty(),
+                                        None => !entry_to_insert.data.is_empty(),
+                                    };
+                                    let pair = *dup_sort && has_data;
+                                    let op_log = &mut *self.op_log;
+                                    let result = if pair {
+                                        let data = decoded_data(entry_to_insert, hex_editor);
+                                        data.and_then(|data| {
+                                            let deleted =
+                                                op_log.delete(database, database_name, true, wtxn, &key, &data);
+                                            Ok(deleted?)
+                                        })
+                                    } else {
+                                        let deleted =
+                                            op_log.delete_key(database, database_name, *dup_sort, wtxn, &key);
+                                        deleted.map_err(Into::into)
+                                    };
+                                    if result.or_report(errors, "deleting an entry").is_some() {
+                                        self.dirty_keys.insert(database_name, &key);
+                                        entry_to_insert.clear();
+                                        *form_compression = None;
+                                        if let Some(editor) = hex_editor {
+                                            *editor = HexEditor::new(&[]);
+                                        }
+                                        row_cache.clear();
+                                        *total_value_bytes = None;
+                                    }
+                                }
+                            }
+                        }
+                    });
+                }
+
+                let num_entries =
+                    cached_len(entry_count, database, self.rtxn(), self.entries_version());
+                let num_entries = num_entries.or_report(self.errors, "counting the entries");
+                ui.heading(format!("{name}: {} entries", num_entries.unwrap_or_default()));
+
+                let is_writing = self.txn.is_right() && !*locked;
+                // The imported or pasted entries to insert once the actions are shown.
+                let mut new_bulk_insert = None;
+                let mut export = None;
+                // Clearing runs in its own write txn, the UI must not hold one, the clears
+                // of several databases wait for each other to commit in the background.
+                let can_clear = !is_writing && background_clear.is_none();
+                ui.horizontal(|ui| {
+                    if !self.read_only {
+                        ui.checkbox(locked, "🔒 read-only tab").on_hover_text(
+                            "hide the write actions of this tab even in a write txn, \
+                            and don't let the other tabs copy entries into its database",
+                        );
+                    }
+                    let read_only = self.read_only || *locked;
+                    if !read_only {
+                        let hint = "Commit or abort the changes first, \
+                            the database is cleared in its own write txn";
+                        let clear = egui::Button::new("clear database");
+                        let clear = ui.add_enabled(can_clear, clear);
+                        // heed can't drop a named database from the environment, only clear it.
+                        if clear.on_disabled_hover_text(hint).clicked() {
+                            *confirming_clear = true;
+                        }
+
+                        let edit = egui::TextEdit::singleline(prefix_to_delete)
+                            .hint_text("key prefix")
+                            .desired_width(100.0);
+                        ui.add(edit);
+                        let delete = egui::Button::new("delete with prefix…");
+                        let delete =
+                            ui.add_enabled(is_writing && prefix_deletion.is_none(), delete);
+                        let hint = "Start writing first, the entries are deleted in the write txn";
+                        if delete.on_disabled_hover_text(hint).clicked() {
+                            let prefix = if prefix_to_delete.is_empty() {
+                                Ok(Vec::new())
+                            } else {
+                                key_type.decode(prefix_to_delete, *display_encoding)
+                            };
+                            if let Some(prefix) =
+                                prefix.or_report(self.errors, "decoding the prefix")
+                            {
+                                let rtxn = self.rtxn();
+                                let total = databases::prefix_entries(
+                                    database,
+                                    rtxn,
+                                    &prefix,
+                                    false,
+                                    *reverse_key,
+                                )
+                                .map(|entries| entries.count() as u64);
+                                if let Some(total) =
+                                    total.or_report(self.errors, "counting the entries")
+                                {
+                                    *prefix_deletion = Some(PrefixDeletion {
+                                        prefix,
+                                        total,
+                                        deleted: 0,
+                                        confirmed: false,
+                                        whole_database: false,
+                                    });
+                                }
+                            }
+                        }
+                    }
+
+                    if ui.button("export JSON").clicked() {
+                        export = Some(ExportFormat::Json);
+                    }
+                    if ui.button("export CSV").clicked() {
+                        export = Some(ExportFormat::Csv);
+                    }
+                    egui::ComboBox::from_id_source((tile_id, "csv_encoding"))
+                        .selected_text(csv_encoding.label())
+                        .show_ui(ui, |ui| {
+                            for encoding in ValueEncoding::ALL {
+                                ui.selectable_value(csv_encoding, encoding, encoding.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text("the encoding of the exported CSV cells");
+                    egui::ComboBox::from_id_source((tile_id, "export_scope"))
+                        .selected_text(export_scope.label())
+                        .show_ui(ui, |ui| {
+                            for scope in [ExportScope::All, ExportScope::Results] {
+                                ui.selectable_value(export_scope, scope, scope.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "the entries exported, the results of the regex search \
+                            or all the entries matching the key prefix and the filter",
+                        );
+
+                    // Read from the record of the database, it decides how the entries are deleted.
+                    let mut is_dup_sort = *dup_sort;
+                    let dup_sort_checkbox = egui::Checkbox::new(&mut is_dup_sort, "duplicate keys");
+                    ui.add_enabled(false, dup_sort_checkbox).on_disabled_hover_text(
+                        "whether the database stores multiple values per key, read from the \
+                        flags stored with a named database",
+                    );
+                    // LMDB orders the keys with its stored flag, the prefix logic must agree.
+                    let mut is_reverse_key = *reverse_key;
+                    let reverse_key_checkbox =
+                        egui::Checkbox::new(&mut is_reverse_key, "keys compared from the end");
+                    ui.add_enabled(false, reverse_key_checkbox).on_disabled_hover_text(
+                        "whether the database was created with MDB_REVERSEKEY, read from the \
+                        flags stored with a named database, the key prefix is then matched at \
+                        the end of the keys",
+                    );
+                    ui.checkbox(show_lengths, "byte lengths");
+                    if self.timestamps.is_some() {
+                        ui.checkbox(show_modified, "modified").on_hover_text(
+                            "when the keys were last written through an editor recording \
+                            the modification times",
+                        );
+                    }
+                    ui.label("decode values as");
+                    let label =
+                        |decoder: Option<&dyn ValueDecoder>| decoder.map_or("raw", |d| d.name());
+                    egui::ComboBox::from_id_source((tile_id, "value_decoder"))
+                        .selected_text(label(*value_decoder))
+                        .show_ui(ui, |ui| {
+                            if ui.selectable_label(value_decoder.is_none(), label(None)).clicked() {
+                                *value_decoder = None;
+                            }
+                            for decoder in value_decoder::DECODERS {
+                                let selected =
+                                    value_decoder.is_some_and(|d| d.name() == decoder.name());
+                                if ui.selectable_label(selected, decoder.name()).clicked() {
+                                    *value_decoder = Some(decoder);
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text("how the value of the selected row is also shown");
+                    let mut compressed = value_compression.is_some();
+                    ui.checkbox(&mut compressed, "compressed values").on_hover_text(
+                        "decompress the gzip or zstd values to show them, the raw bytes are \
+                        shown when they can't be, and compress the data inserted from the form",
+                    );
+                    match (compressed, value_compression.as_mut()) {
+                        (true, Some(compression)) => {
+                            egui::ComboBox::from_id_source((tile_id, "value_compression"))
+                                .selected_text(compression.label())
+                                .show_ui(ui, |ui| {
+                                    for c in Compression::ALL {
+                                        ui.selectable_value(compression, c, c.label());
+                                    }
+                                })
+                                .response
+                                .on_hover_text("how the inserted data is compressed");
+                        }
+                        (true, None) => *value_compression = Some(Compression::Gzip),
+                        (false, _) => *value_compression = None,
+                    }
+
+                    let layout = ui.button("value layout…").on_hover_text(
+                        "describe the fields at the start of the values, like a timestamp \
+                        header, to show them in their own columns",
+                    );
+                    if layout.clicked() {
+                        *editing_layout = !*editing_layout;
+                    }
+                    egui::Window::new(format!("Value layout of {name}"))
+                        .id(egui::Id::new((tile_id, "value_layout")))
+                        .open(editing_layout)
+                        .show(ui.ctx(), |ui| value_layout.ui(ui, tile_id));
+
+                    // The tree splits the raw keys, from their start.
+                    if *key_type == KeyType::Bytes && !*reverse_key {
+                        let tree = ui.button("key tree…").on_hover_text(
+                            "browse the keys as folders of their prefixes, split at the \
+                            delimiter of the key groups",
+                        );
+                        if tree.clicked() {
+                            *prefix_tree = match prefix_tree {
+                                Some(_) => None,
+                                None => Some(PrefixTree::default()),
+                            };
+                        }
+                    }
+                    let encoding = *display_encoding;
+                    let delimiter = encoding.decode(group_delimiter).ok();
+                    let delimiter = delimiter.filter(|delimiter| !delimiter.is_empty());
+                    if let Some(tree) = prefix_tree {
+                        let mut open = true;
+                        let mut clicked = None;
+                        let version = self.entries_version();
+                        egui::Window::new(format!("Key tree of {name}"))
+                            .id(egui::Id::new((tile_id, "prefix_tree")))
+                            .open(&mut open)
+                            .default_size([300.0, 400.0])
+                            .show(ui.ctx(), |ui| {
+                                let Some(delimiter) = &delimiter else {
+                                    ui.colored_label(Color32::RED, "invalid key group delimiter");
+                                    return;
+                                };
+                                egui::ScrollArea::vertical().show(ui, |ui| {
+                                    let encode = |bytes: &[u8]| encoding.encode(bytes);
+                                    let rtxn = self.rtxn();
+                                    clicked = Some(
+                                        tree.ui(ui, database, rtxn, delimiter, version, &encode),
+                                    );
+                                });
+                            });
+                        let clicked = clicked.and_then(|clicked| {
+                            clicked.or_report(self.errors, "listing the key prefixes").flatten()
+                        });
+                        // The table goes to the subtree or to the entry clicked.
+                        if let Some((key_prefix, leaf)) = clicked {
+                            *prefix = encoding.encode(&key_prefix);
+                            *filter_limit = 0;
+                            *selected_row = leaf.then_some(0);
+                            *glob_scan = None;
+                        }
+                        if !open {
+                            *prefix_tree = None;
+                        }
+                    }
+
+                    let count = ui.button("count matching…").on_hover_text(
+                        "count the entries whose key matches and the bytes of their values, \
+                        without listing them",
+                    );
+                    if count.clicked() {
+                        *count_tool = match count_tool {
+                            Some(_) => None,
+                            None => Some(CountTool::default()),
+                        };
+                    }
+                    if let Some(tool) = count_tool {
+                        let mut open = true;
+                        let (mut start, mut stop) = (false, false);
+                        let version = self.entries_version();
+                        let running = tool.count.as_ref().is_some_and(|count| !count.done);
+                        egui::Window::new(format!("Count entries of {name}"))
+                            .id(egui::Id::new((tile_id, "count_tool")))
+                            .open(&mut open)
+                            .show(ui.ctx(), |ui| {
+                                // The pattern can't change under a running count.
+                                ui.add_enabled_ui(!running, |ui| {
+                                    ui.horizontal(|ui| {
+                                        egui::ComboBox::from_id_source((tile_id, "count_kind"))
+                                            .selected_text(tool.kind.label())
+                                            .show_ui(ui, |ui| {
+                                                for kind in MatchKind::ALL {
+                                                    let label = kind.label();
+                                                    ui.selectable_value(
+                                                        &mut tool.kind,
+                                                        kind,
+                                                        label,
+                                                    );
+                                                }
+                                            });
+                                        ui.text_edit_singleline(&mut tool.pattern);
+                                        start = ui.button("count").clicked();
+                                    });
+                                });
+                                let Some(count) = &tool.count else { return };
+                                if running {
+                                    ui.horizontal(|ui| {
+                                        ui.spinner();
+                                        ui.label(format!(
+                                            "{} matching entries in the {} scanned so far",
+                                            count.matches, count.scanned
+                                        ));
+                                        stop = ui.button("stop").clicked();
+                                    });
+                                    return;
+                                }
+                                let stopped = if count.cancelled { ", stopped" } else { "" };
+                                ui.label(format!(
+                                    "{} matching entries, {} bytes of values, \
+                                    in {} entries scanned{stopped}",
+                                    count.matches, count.value_bytes, count.scanned
+                                ));
+                                if count.version != version {
+                                    ui.weak("the entries changed since");
+                                }
+                            });
+
+                        let (key_type, reverse_key) = (*key_type, *reverse_key);
+                        let pattern = tool.pattern.as_str();
+                        let decoded = match pattern.is_empty() {
+                            true => Ok(Vec::new()),
+                            false => key_type.decode(pattern, encoding),
+                        };
+                        if start {
+                            // The keys with a prefix are sought, they end with it in a
+                            // `MDB_REVERSEKEY` database and all the keys are scanned.
+                            let seek = match (tool.kind, reverse_key) {
+                                (MatchKind::Prefix, false) => decoded.as_ref().cloned(),
+                                (MatchKind::Prefix, true) => decoded.as_ref().map(|_| Vec::new()),
+                                _ => Ok(Vec::new()),
+                            };
+                            let seek = seek.map_err(|e| anyhow::anyhow!("{e:#}"));
+                            let seek = seek.or_report(self.errors, "decoding the key prefix");
+                            tool.count = seek.map(|seek| MatchCount::new(version, seek));
+                        }
+                        if let Some(count) = tool.count.as_mut().filter(|count| !count.done) {
+                            let suffix = decoded.ok().filter(|_| reverse_key);
+                            let kind = tool.kind;
+                            let is_match = |key: &[u8]| match kind {
+                                MatchKind::Prefix => {
+                                    suffix.as_ref().is_none_or(|suffix| key.ends_with(suffix))
+                                }
+                                MatchKind::Substring => {
+                                    key_type.encode(key, encoding).contains(pattern)
+                                }
+                                MatchKind::Glob => {
+                                    glob::matches(pattern, &key_type.encode(key, encoding))
+                                }
+                            };
+                            let rtxn = self.rtxn();
+                            let result = count.step(database, rtxn, GLOB_SCAN_BUDGET, is_match);
+                            if stop
+                                || result.or_report(self.errors, "counting the entries").is_none()
+                            {
+                                count.done = true;
+                                count.cancelled = true;
+                            }
+                            ui.ctx().request_repaint();
+                        }
+                        if !open {
+                            *count_tool = None;
+                        }
+                    }
+
+                    ui.label("peek at the");
+                    if ui.button(format!("first {sample_size}")).clicked() {
+                        *sample = Some(SampleEnd::First);
+                    }
+                    if ui.button(format!("last {sample_size}")).clicked() {
+                        *sample = Some(SampleEnd::Last);
+                    }
+                    ui.add(egui::DragValue::new(sample_size).clamp_range(1..=10_000))
+                        .on_hover_text("the number of entries to peek at");
+                    if let Some(end) = *sample {
+                        // Only the few entries at an end are read, not the whole table,
+                        // and only again once they or how they are shown changed.
+                        let (key_type, encoding) = (*key_type, *display_encoding);
+                        let cache_key =
+                            (end, *sample_size, key_type, encoding, self.entries_version());
+                        if sampled_entries.as_ref().is_none_or(|(cached, _)| *cached != cache_key) {
+                            let rtxn = self.rtxn();
+                            let entries = row_cache::entries_from(
+                                database,
+                                rtxn,
+                                Bound::Unbounded,
+                                end == SampleEnd::Last,
+                            )
+                            .and_then(|entries| {
+                                entries
+                                    .take(*sample_size)
+                                    .map(|entry| {
+                                        let (key, data) = entry?;
+                                        Ok((key_type.encode(key, encoding), encoding.encode(data)))
+                                    })
+                                    .collect::<heed::Result<Vec<_>>>()
+                            });
+                            let entries = entries.or_report(self.errors, "peeking at the entries");
+                            *sampled_entries = Some((cache_key, entries));
+                        }
+                        let entries =
+                            sampled_entries.as_ref().and_then(|(_, entries)| entries.as_ref());
+
+                        let title = match end {
+                            SampleEnd::First => format!("The first entries of {name}"),
+                            SampleEnd::Last => format!("The last entries of {name}"),
+                        };
+                        let mut open = true;
+                        egui::Window::new(title)
+                            .id(egui::Id::new((tile_id, "sample")))
+                            .open(&mut open)
+                            .default_size([500.0, 300.0])
+                            .show(ui.ctx(), |ui| {
+                                egui::ScrollArea::vertical().show(ui, |ui| {
+                                    egui::Grid::new((tile_id, "sample_entries"))
+                                        .striped(true)
+                                        .show(ui, |ui| {
+                                            for (key, data) in entries.into_iter().flatten() {
+                                                let (key, full_key) = truncate_cell(key.clone());
+                                                let label = ui.label(key);
+                                                if let Some(full) = full_key {
+                                                    label.on_hover_text(full);
+                                                }
+                                                let (data, full_data) = truncate_cell(data.clone());
+                                                let label = ui.label(data);
+                                                if let Some(full) = full_data {
+                                                    label.on_hover_text(full);
+                                                }
+                                                ui.end_row();
+                                            }
+                                        });
+                                    if entries.is_some_and(|entries| entries.is_empty()) {
+                                        ui.label("the database is empty");
+                                    }
+                                });
+                            });
+                        if !open {
+                            *sample = None;
+                            *sampled_entries = None;
+                        }
+                    }
+
+                    if read_only {
+                        return;
+                    }
+                    let import = ui.add_enabled(is_writing, egui::Button::new("import JSON"));
+                    if import
+                        .on_disabled_hover_text("Switch to writing mode to import entries")
+                        .clicked()
+                    {
+                        if let Some(path) =
+                            FileDialog::new().add_filter("JSON", &["json"]).pick_file()
+                        {
+                            let entries = import::read_json(&path);
+                            let entries = entries.or_report(self.errors, "importing from JSON");
+                            new_bulk_insert =
+                                entries.map(|entries| BulkInsert::new(entries, *import_overwrite));
+                        }
+                    }
+                    let merge = egui::Button::new("merge from environment…");
+                    let merge = ui.add_enabled(is_writing && env_merge.is_none(), merge);
+                    if merge
+                        .on_hover_text("copy the entries of a database of another environment")
+                        .on_disabled_hover_text("Switch to writing mode to import entries")
+                        .clicked()
+                    {
+                        if let Some(folder) = FileDialog::new().pick_folder() {
+                            let merge = EnvMerge::open(&folder);
+                            *env_merge = merge.or_report(self.errors, "opening the environment");
+                        }
+                    }
+                    let edit = egui::Button::new("edit in external editor…");
+                    let edit = ui.add_enabled(is_writing && external_edit.is_none(), edit);
+                    if edit
+                        .on_hover_text(
+                            "edit the entries with the key prefix as JSON with $EDITOR, \
+                            the editor must only exit once the file is closed",
+                        )
+                        .on_disabled_hover_text("Switch to writing mode to edit entries")
+                        .clicked()
+                    {
+                        let edit_prefix = if prefix.is_empty() {
+                            Ok(Vec::new())
+                        } else {
+                            key_type.decode(prefix, *display_encoding)
+                        };
+                        if let Some(edit_prefix) =
+                            edit_prefix.or_report(self.errors, "decoding the key prefix")
+                        {
+                            let rtxn = self.rtxn();
+                            let edit = databases::prefix_entries(
+                                database,
+                                rtxn,
+                                &edit_prefix,
+                                false,
+                                *reverse_key,
+                            )
+                            .map_err(anyhow::Error::from)
+                            .and_then(|entries| ExternalEdit::start(entries, &name));
+                            *external_edit =
+                                edit.or_report(self.errors, "editing in an external editor");
+                        }
+                    }
+                    ui.checkbox(import_overwrite, "overwrite existing keys");
+                    ui.checkbox(append_sorted, "sorted keys").on_hover_text(
+                        "append the imported and pasted entries, which is much faster, \
+                        their keys must be sorted and greater than the existing ones",
+                    );
+                    ui.checkbox(preview_imports, "preview imports").on_hover_text(
+                        "count the new, different and identical entries before importing",
+                    );
+                });
+
+                let table_filter = || {
+                    decode_prefix(prefix, *key_type, *display_encoding).map(|prefix| TableFilter {
+                        prefix,
+                        filter: filter.clone(),
+                        glob: *glob_filter,
+                        reverse_key: *reverse_key,
+                        key_type: *key_type,
+                        encoding: *display_encoding,
+                    })
+                };
+                let version = self.entries_version();
+                match (export, *export_scope) {
+                    (Some(format), ExportScope::All) => {
+                        let file_name = format!("{name}.{}", format.extension());
+                        if let Some(path) = FileDialog::new().set_file_name(&file_name).save_file()
+                        {
+                            let entries = database.iter(self.rtxn()).map_err(anyhow::Error::from);
+                            let result = entries
+                                .and_then(|entries| format.write(entries, &path, *csv_encoding));
+                            let context = format!("exporting to {}", format.label());
+                            result.or_report(self.errors, &context);
+                        }
+                    }
+                    (Some(format), ExportScope::Results) => {
+                        let rtxn = self.rtxn();
+                        let entries = table_filter().and_then(|table_filter| {
+                            table_filter.entries(database, rtxn, regex_search, *reverse, version)
+                        });
+                        let count = entries.and_then(|mut entries| {
+                            Ok(entries.try_fold(0, |count, entry| entry.map(|_| count + 1))?)
+                        });
+                        if let Some(count) = count.or_report(self.errors, "counting the results") {
+                            *pending_export = Some((format, count));
+                        }
+                    }
+                    (None, _) => (),
+                }
+                if let Some((format, count)) = *pending_export {
+                    let (mut save, mut cancel) = (false, false);
+                    egui::Window::new(format!("Export the results of {name}?"))
+                        .id(egui::Id::new((tile_id, "pending_export")))
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!("{count} entries to export as {}", format.label()));
+                            ui.horizontal(|ui| {
+                                save = ui.button("save…").clicked();
+                                cancel = ui.button("cancel").clicked();
+                            });
+                        });
+
+                    let file_name = format!("{name}.{}", format.extension());
+                    let path = match save {
+                        true => FileDialog::new().set_file_name(&file_name).save_file(),
+                        false => None,
+                    };
+                    if let Some(path) = path {
+                        let rtxn = self.rtxn();
+                        let entries = table_filter().and_then(|table_filter| {
+                            table_filter.entries(database, rtxn, regex_search, *reverse, version)
+                        });
+                        let result =
+                            entries.and_then(|entries| format.write(entries, &path, *csv_encoding));
+                        let context = format!("exporting to {}", format.label());
+                        if let Some(written) = result.or_report(self.errors, &context) {
+                            *message =
+                                Some(format!("exported {written} entries to {}", path.display()));
+                        }
+                    }
+                    if save || cancel {
+                        *pending_export = None;
+                    }
+                }
+
+                if let (Some(text), false) = (paste_many.as_mut(), read_only) {
+                    let mut open = true;
+                    let mut insert = false;
+                    egui::Window::new(format!("Paste many entries into {name}"))
+                        .id(egui::Id::new((tile_id, "paste_many")))
+                        .open(&mut open)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!(
+                                "One entry per line, the key and the data separated by a tab, \
+                                in {}.",
+                                display_encoding.label()
+                            ));
+                            ui.add(egui::TextEdit::multiline(text).hint_text("key\tdata"));
+                            insert = ui
+                                .add_enabled(is_writing, egui::Button::new("insert"))
+                                .on_disabled_hover_text("Switch to writing mode to insert entries")
+                                .clicked();
+                        });
+
+                    if insert {
+                        let entries = import::read_pasted(text, *key_type, *display_encoding);
+                        if let Some(entries) =
+                            entries.or_report(self.errors, "reading the pasted entries")
+                        {
+                            new_bulk_insert = Some(BulkInsert::new(entries, *import_overwrite));
+                            open = false;
+                        }
+                    }
+                    if !open {
+                        *paste_many = None;
+                    }
+                }
+
+                // The bulk insert to run and whether it appends the entries.
+                let mut bulk_to_run = None;
+                if let Some(bulk) = new_bulk_insert {
+                    if *preview_imports {
+                        let preview = bulk.preview(database, self.rtxn());
+                        let preview = preview.or_report(self.errors, "previewing the import");
+                        *import_preview = preview.map(|preview| (bulk, preview));
+                    } else {
+                        bulk_to_run = Some((bulk, *append_sorted));
+                    }
+                }
+                if let Some((bulk, preview)) = import_preview {
+                    let (mut proceed, mut cancel) = (false, false);
+                    egui::Window::new(format!("Import into {name}?"))
+                        .id(egui::Id::new((tile_id, "import_preview")))
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!("{} entries", bulk.len()));
+                            ui.label(preview.describe(*import_overwrite));
+                            ui.horizontal(|ui| {
+                                proceed = ui
+                                    .add_enabled(is_writing, egui::Button::new("import"))
+                                    .on_disabled_hover_text(
+                                        "Switch to writing mode to import entries",
+                                    )
+                                    .clicked();
+                                cancel = ui.button("cancel").clicked();
+                            });
+                        });
+
+                    if proceed {
+                        bulk_to_run = import_preview.take().map(|(bulk, _)| (bulk, *append_sorted));
+                    } else if cancel || !is_writing {
+                        *import_preview = None;
+                    }
+                }
+                if let Some(bulk) = bulk_insert {
+                    let (mut fall_back, mut stop) = (false, false);
+                    egui::Window::new("Entry out of order")
+                        .id(egui::Id::new((tile_id, "bulk_insert")))
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!(
+                                "Entry {} of {} can't be appended, LMDB only appends keys \
+                                greater than the last key of the database.",
+                                bulk.next() + 1,
+                                bulk.len()
+                            ));
+                            ui.horizontal(|ui| {
+                                let button = egui::Button::new("put the remaining entries");
+                                fall_back = ui.add_enabled(is_writing, button).clicked();
+                                stop = ui.button("stop here").clicked();
+                            });
+                        });
+
+                    if fall_back {
+                        bulk_to_run = bulk_insert.take().map(|bulk| (bulk, false));
+                    } else if stop || !is_writing {
+                        let summary = bulk.summary;
+                        *message = Some(format!(
+                            "inserted {} entries, skipped {} existing keys, stopped at entry {}",
+                            summary.inserted,
+                            summary.skipped,
+                            bulk.next() + 1
+                        ));
+                        *bulk_insert = None;
+                    }
+                }
+
+                if let (Some((mut bulk, append)), Either::Right(wtxn)) =
+                    (bulk_to_run, self.txn.as_mut())
+                {
+                    let inserted = bulk.summary.inserted;
+                    let result = bulk.put_remaining(database, wtxn, append);
+                    self.op_log.record_bulk(bulk.summary.inserted - inserted, 0);
+                    row_cache.clear();
+                    *total_value_bytes = None;
+                    match result.or_report(self.errors, "inserting the entries") {
+                        Some(true) => {
+                            *message = Some(format!(
+                                "inserted {} entries, skipped {} existing keys",
+                                bulk.summary.inserted, bulk.summary.skipped
+                            ));
+                        }
+                        Some(false) => *bulk_insert = Some(bulk),
+                        None => (),
+                    }
+                }
+
+                if let Some(merge) = env_merge {
+                    let mut open = true;
+                    let (mut start, mut dry_run) = (false, false);
+                    egui::Window::new(format!("Merge into {name}"))
+                        .id(egui::Id::new((tile_id, "env_merge")))
+                        .open(&mut open)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!("from {}", merge.path.display()));
+                            let label = |name: &Option<String>| {
+                                name.clone().unwrap_or_else(|| "{main}".to_owned())
+                            };
+                            ui.add_enabled_ui(merge.progress.is_none(), |ui| {
+                                egui::ComboBox::from_label("source database")
+                                    .selected_text(label(&merge.databases[merge.source]))
+                                    .show_ui(ui, |ui| {
+                                        for (i, name) in merge.databases.iter().enumerate() {
+                                            ui.selectable_value(&mut merge.source, i, label(name));
+                                        }
+                                    });
+                            });
+                            ui.checkbox(import_overwrite, "overwrite existing keys");
+                            match &merge.progress {
+                                None => {
+                                    ui.horizontal(|ui| {
+                                        dry_run = ui
+                                            .button("dry run")
+                                            .on_hover_text(
+                                                "count what the merge would do, without writing",
+                                            )
+                                            .clicked();
+                                        start = ui
+                                            .add_enabled(is_writing, egui::Button::new("merge"))
+                                            .on_disabled_hover_text(
+                                                "Switch to writing mode to import entries",
+                                            )
+                                            .clicked();
+                                    });
+                                    let source = merge.source;
+                                    match merge.dry_run.as_ref().filter(|d| d.source == source) {
+                                        Some(dry_run) if dry_run.done => {
+                                            ui.label(dry_run.preview.describe(*import_overwrite));
+                                        }
+                                        Some(dry_run) => {
+                                            let fraction = dry_run.counted as f32
+                                                / dry_run.total.max(1) as f32;
+                                            ui.add(egui::ProgressBar::new(fraction).text(format!(
+                                                "counted {} of {} entries",
+                                                dry_run.counted, dry_run.total
+                                            )));
+                                        }
+                                        None => (),
+                                    }
+                                }
+                                Some(progress) => {
+                                    let copied =
+                                        progress.summary.inserted + progress.summary.skipped;
+                                    let fraction = copied as f32 / progress.total.max(1) as f32;
+                                    ui.add(egui::ProgressBar::new(fraction).text(format!(
+                                        "{copied} of {} entries, {} skipped",
+                                        progress.total, progress.summary.skipped
+                                    )));
+                                }
+                            }
+                        });
+                    if dry_run {
+                        merge.start_dry_run().or_report(self.errors, "reading the source database");
+                    }
+                    if start {
+                        merge.start().or_report(self.errors, "reading the source database");
+                    }
+                    if merge.dry_run.as_ref().is_some_and(|dry_run| !dry_run.done) {
+                        let result = merge.dry_run_step(database, self.rtxn(), MERGE_CHUNK);
+                        if result.or_report(self.errors, "counting the entries").is_none() {
+                            merge.dry_run = None;
+                        }
+                        ui.ctx().request_repaint();
+                    }
+
+                    if let (Some(progress), Either::Right(wtxn)) =
+                        (&merge.progress, self.txn.as_mut())
+                    {
+                        let inserted = progress.summary.inserted;
+                        let result = merge.step(database, wtxn, *import_overwrite, MERGE_CHUNK);
+                        let progress = merge.progress.as_ref().unwrap();
+                        self.op_log.record_bulk(progress.summary.inserted - inserted, 0);
+                        row_cache.clear();
+                        *total_value_bytes = None;
+                        if result.or_report(self.errors, "merging the entries").is_none() {
+                            open = false;
+                        } else if progress.done {
+                            *message = Some(format!(
+                                "merged {} entries from {}, skipped {} existing keys",
+                                progress.summary.inserted,
+                                merge.path.display(),
+                                progress.summary.skipped
+                            ));
+                            open = false;
+                        } else {
+                            // A chunk is copied per frame.
+                            ui.ctx().request_repaint();
+                        }
+                    } else if let Some(progress) = &merge.progress {
+                        // The write txn ended, what was merged is committed or aborted with it.
+                        *message = Some(format!(
+                            "the merge stopped with the write txn after {} entries",
+                            progress.summary.inserted
+                        ));
+                        open = false;
+                    }
+                    if !open {
+                        *env_merge = None;
+                    }
+                }
+
+                if let Some(edit) = external_edit {
+                    edit.poll();
+                    let mut open = true;
+                    let (mut apply, mut edit_again) = (false, false);
+                    egui::Window::new(format!("Edit {name} in an external editor"))
+                        .id(egui::Id::new((tile_id, "external_edit")))
+                        .open(&mut open)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!("{}", edit.path.display()));
+                            match &edit.state {
+                                EditState::Editing(_) => {
+                                    ui.horizontal(|ui| {
+                                        ui.spinner();
+                                        ui.label("waiting for the editor to exit");
+                                    });
+                                }
+                                EditState::Edited(changes) if changes.is_empty() => {
+                                    ui.label("the file was not changed");
+                                }
+                                EditState::Edited(changes) => {
+                                    ui.label(format!(
+                                        "{} entries to put, {} to delete",
+                                        changes.puts.len(),
+                                        changes.deletions.len()
+                                    ));
+                                    apply = ui
+                                        .add_enabled(is_writing, egui::Button::new("apply"))
+                                        .on_disabled_hover_text(
+                                            "Switch to writing mode to edit entries",
+                                        )
+                                        .clicked();
+                                }
+                                EditState::Failed(e) => {
+                                    ui.colored_label(Color32::RED, e);
+                                    edit_again = ui.button("edit again").clicked();
+                                }
+                            }
+                        });
+                    if let EditState::Editing(_) = edit.state {
+                        // The editor isn't watched otherwise.
+                        ui.ctx().request_repaint_after(Duration::from_millis(250));
+                    }
+                    if edit_again {
+                        edit.edit_again().or_report(self.errors, "editing in an external editor");
+                    }
+
+                    if let (EditState::Edited(changes), true, Either::Right(wtxn)) =
+                        (&edit.state, apply, self.txn.as_mut())
+                    {
+                        let result = changes.apply(database, wtxn, *dup_sort);
+                        row_cache.clear();
+                        *total_value_bytes = None;
+                        if let Some((put, deleted)) =
+                            result.or_report(self.errors, "applying the edited entries")
+                        {
+                            self.op_log.record_bulk(put, deleted);
+                            *message =
+                                Some(format!("put {put} and deleted {deleted} edited entries"));
+                        }
+                        open = false;
+                    }
+                    if !open {
+                        *external_edit = None;
+                    }
+                }
+
+                if let Some(deletion) = prefix_deletion {
+                    let mut done = !is_writing;
+                    if !deletion.confirmed {
+                        egui::Window::new(format!("Delete entries of {name}?"))
+                            .id(egui::Id::new((tile_id, "prefix_deletion")))
+                            .collapsible(false)
+                            .resizable(false)
+                            .show(ui.ctx(), |ui| {
+                                if deletion.prefix.is_empty() {
+                                    ui.label(format!(
+                                        "The prefix is empty, this will delete all the {} \
+                                        entries of {name}.",
+                                        deletion.total
+                                    ));
+                                    ui.checkbox(
+                                        &mut deletion.whole_database,
+                                        "delete the entire database",
+                                    );
+                                } else {
+                                    ui.label(format!(
+                                        "This will delete the {} entries whose key {} \
+                                        with the prefix.",
+                                        deletion.total,
+                                        if *reverse_key { "ends" } else { "starts" }
+                                    ));
+                                }
+                                ui.label(
+                                    "It can't be undone, only aborting the write txn \
+                                    brings them back.",
+                                );
+                                ui.horizontal(|ui| {
+                                    let can_delete =
+                                        !deletion.prefix.is_empty() || deletion.whole_database;
+                                    let delete = egui::Button::new("delete");
+                                    deletion.confirmed =
+                                        ui.add_enabled(can_delete, delete).clicked();
+                                    done |= ui.button("cancel").clicked();
+                                });
+                            });
+                    } else if let Either::Right(wtxn) = self.txn.as_mut() {
+                        let result = databases::delete_prefix(
+                            database,
+                            wtxn,
+                            &deletion.prefix,
+                            PREFIX_DELETION_CHUNK,
+                            *reverse_key,
+                        );
+                        row_cache.clear();
+                        *total_value_bytes = None;
+                        match result.or_report(self.errors, "deleting the entries") {
+                            Some(deleted) => {
+                                self.op_log.record_bulk(0, deleted);
+                                deletion.deleted += deleted;
+                                if deleted < PREFIX_DELETION_CHUNK {
+                                    *message = Some(format!(
+                                        "deleted {} entries with the prefix",
+                                        deletion.deleted
+                                    ));
+                                    done = true;
+                                } else {
+                                    // Deleting a chunk per frame keeps the UI responsive.
+                                    let progress = deletion.deleted as f32
+                                        / deletion.total.max(deletion.deleted) as f32;
+                                    ui.add(egui::ProgressBar::new(progress).text(format!(
+                                        "deleting, {} of {} entries removed",
+                                        deletion.deleted, deletion.total
+                                    )));
+                                    ui.ctx().request_repaint();
+                                }
+                            }
+                            None => done = true,
+                        }
+                    }
+                    if done {
+                        *prefix_deletion = None;
+                    }
+                }
+
+                if let Some(text) = message {
+                    let mut dismissed = false;
+                    ui.horizontal(|ui| {
+                        dismissed = ui.small_button("✕").clicked();
+                        ui.label(text.as_str());
+                    });
+                    if dismissed {
+                        *message = None;
+                    }
+                }
+
+                egui::CollapsingHeader::new("statistics").id_source((tile_id, "statistics")).show(
+                    ui,
+                    |ui| {
+                        let stat = match database_name {
+                            Some(name) => databases::database_stat(self.env, self.rtxn(), name)
+                                .or_report(self.errors, "reading the statistics")
+                                .flatten(),
+                            None => None,
+                        };
+                        let page_size = stat.and_then(|_| {
+                            databases::page_size(self.env)
+                                .or_report(self.errors, "reading the page size")
+                        });
+                        match stat {
+                            Some(stat) => {
+                                egui::Grid::new((tile_id, "statistics_grid")).show(ui, |ui| {
+                                    let rows = [
+                                        ("entries", stat.entries as u64),
+                                        ("depth", stat.depth.into()),
+                                        ("branch pages", stat.branch_pages as u64),
+                                        ("leaf pages", stat.leaf_pages as u64),
+                                        ("overflow pages", stat.overflow_pages as u64),
+                                    ];
+                                    let total_bytes = page_size.map(|page_size| {
+                                        ("total bytes", stat.total_bytes(page_size))
+                                    });
+                                    for (label, value) in rows.into_iter().chain(total_bytes) {
+                                        ui.label(label);
+                                        ui.label(value.to_string());
+                                        ui.end_row();
+                                    }
+                                    ui.label("flags");
+                                    ui.label(format!("{:#06x}", stat.flags));
+                                    ui.end_row();
+                                });
+                                if is_writing {
+                                    ui.label("as of the last commit");
+                                }
+                            }
+                            None => {
+                                ui.label(format!("entries: {}", num_entries.unwrap_or_default()));
+                                ui.label("the page statistics are only known for named databases");
+                            }
+                        }
+                    },
+                );
+
+                egui::CollapsingHeader::new("value sizes")
+                    .id_source((tile_id, "value_sizes"))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.button("sample").clicked() {
+                                let sizes = ValueSizes::sample(database, self.rtxn());
+                                *value_sizes = sizes.or_report(self.errors, "sampling the values");
+                            }
+                            ui.label("buckets");
+                            let edit = egui::TextEdit::singleline(bucket_boundaries)
+                                .hint_text("boundaries in bytes, separated by commas");
+                            ui.add(edit);
+                        });
+                        let Some(sizes) = value_sizes else { return };
+                        let boundaries = match value_sizes::parse_boundaries(bucket_boundaries) {
+                            Ok(boundaries) => boundaries,
+                            Err(e) => {
+                                ui.colored_label(Color32::RED, format!("{e:#}"));
+                                return;
+                            }
+                        };
+                        if sizes.seeked {
+                            ui.label(format!(
+                                "{} values sampled out of {} entries, at keys spread between \
+                                the first and the last one",
+                                sizes.len(),
+                                sizes.entries,
+                            ));
+                        } else {
+                            ui.label(format!("all the {} values", sizes.len()));
+                        }
+                        let labels = value_sizes::bucket_labels(&boundaries);
+                        let bars = sizes
+                            .buckets(&boundaries)
+                            .into_iter()
+                            .zip(&labels)
+                            .enumerate()
+                            .map(|(i, (count, label))| {
+                                egui::plot::Bar::new(i as f64, count as f64).name(label)
+                            })
+                            .collect();
+                        egui::plot::Plot::new((tile_id, "value_sizes_plot"))
+                            .height(150.0)
+                            .allow_drag(false)
+                            .allow_zoom(false)
+                            .allow_scroll(false)
+                            .x_axis_formatter(move |x, _range| {
+                                let i = x.round();
+                                match labels.get(i as usize) {
+                                    Some(label) if i >= 0.0 && x == i => label.clone(),
+                                    _ => String::new(),
+                                }
+                            })
+                            .show(ui, |plot_ui| plot_ui.bar_chart(egui::plot::BarChart::new(bars)));
+                    });
+
+                if *confirming_clear {
+                    let mut confirmed = false;
+                    egui::Window::new(format!("Clear {name}?"))
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!(
+                                "This will remove the {} entries of {name}.",
+                                num_entries.unwrap_or_default()
+                            ));
+                            ui.label("It is committed right away and can't be undone.");
+                            ui.horizontal(|ui| {
+                                confirmed = ui
+                                    .add_enabled(can_clear, egui::Button::new("confirm"))
+                                    .clicked();
+                                if ui.button("cancel").clicked() {
+                                    *confirming_clear = false;
+                                }
+                            });
+                        });
+
+                    if confirmed {
+                        let total = num_entries.unwrap_or_default();
+                        let clear = BackgroundClear::spawn(self.env, *database, total);
+                        *background_clear = Some(clear);
+                        self.background_writes = true;
+                        *confirming_clear = false;
+                    }
+                }
+
+                // It is polled by `LmdbEditor::poll_background_clears`, for the hidden tabs too.
+                if let Some(clear) = background_clear {
+                    let progress = if clear.total == 0 {
+                        1.0
+                    } else {
+                        clear.deleted as f32 / clear.total as f32
+                    };
+                    ui.add(egui::ProgressBar::new(progress).text(format!(
+                        "clearing {name}, {} of {} entries removed",
+                        clear.deleted, clear.total
+                    )));
+                }
+
+                // If there is a write txn opened, use it, else make the wtxn live longer and deref it.
+                let long_wtxn: &&mut RwTxn;
+                let rtxn: &heed::RoTxn;
+                match self.txn.as_ref() {
+                    Either::Left(txn) => rtxn = txn,
+                    Either::Right(wtxn) => {
+                        long_wtxn = wtxn;
+                        rtxn = long_wtxn.deref();
+                    }
+                };
+
+                const ROW_HEIGHT: f32 = 30.0;
+                let viewport_rows = (ui.available_height() / ROW_HEIGHT).ceil() as usize;
+
+                let response = ui.horizontal(|ui| {
+                    ui.label("🔍");
+                    let hint = if *glob_filter { "user:*:profile" } else { "filter keys" };
+                    let filter = ui.add(egui::TextEdit::singleline(filter).hint_text(hint));
+                    let glob = ui.checkbox(glob_filter, "glob").on_hover_text(
+                        "match the whole key against the filter, * matches any characters and \
+                        ? a single one, the search seeks to the text before the first wildcard",
+                    );
+                    ui.label("go to key");
+                    let prefix = ui.add(egui::TextEdit::singleline(prefix).hint_text("key prefix"));
+
+                    let previous_encoding = *display_encoding;
+                    ui.label("display as");
+                    egui::ComboBox::from_id_source((tile_id, "display_encoding"))
+                        .selected_text(display_encoding.label())
+                        .show_ui(ui, |ui| {
+                            for encoding in ValueEncoding::ALL {
+                                ui.selectable_value(display_encoding, encoding, encoding.label());
+                            }
+                        });
+                    ui.checkbox(plain_utf8, "plain UTF-8").on_hover_text(
+                        "show the valid UTF-8 as it is, only escaping the control characters",
+                    );
+                    ui.checkbox(truncate_cells, "truncate long cells")
+                        .on_hover_text("cut the long keys and values, hover them to see them all");
+                    ui.checkbox(expand_hex, "hex in expanded rows");
+                    let encoding_changed = *display_encoding != previous_encoding;
+                    if encoding_changed {
+                        // Keep the entry being written the same bytes in the new encoding,
+                        // the integer keys are written in decimal whatever the encoding.
+                        let key = (*key_type != KeyType::Bytes)
+                            .then(|| mem::take(&mut entry_to_insert.key));
+                        entry_to_insert.reencode(previous_encoding, *display_encoding);
+                        if let Some(key) = key {
+                            entry_to_insert.key = key;
+                        }
+                        *editing_cell = None;
+                    }
+
+                    let previous_key_type = *key_type;
+                    ui.label("keys as");
+                    egui::ComboBox::from_id_source((tile_id, "key_type"))
+                        .selected_text(key_type.label())
+                        .show_ui(ui, |ui| {
+                            for ty in KeyType::ALL {
+                                ui.selectable_value(key_type, ty, ty.label());
+                            }
+                        });
+                    let key_type_changed = *key_type != previous_key_type;
+                    if key_type_changed {
+                        *editing_cell = None;
+                    }
+                    if encoding_changed || key_type_changed {
+                        let saved =
+                            SavedDisplay { encoding: *display_encoding, key_type: *key_type };
+                        self.displays.insert(database_name.clone(), saved);
+                    }
+
+                    let reverse = ui.checkbox(reverse, "descending").on_hover_text(
+                        "show the highest keys first, the rows are counted from them",
+                    );
+                    if reverse.changed() {
+                        if let Some(pagination) = pagination {
+                            pagination.last_entries.clear();
+                        }
+                    }
+
+                    filter.changed()
+                        || glob.changed()
+                        || prefix.changed()
+                        || encoding_changed
+                        || key_type_changed
+                        || reverse.changed()
+                });
+                if response.inner {
+                    *filter_limit = 0;
+                    *selected_row = None;
+                    *glob_scan = None;
+                }
+
+                let regex_changed = ui.horizontal(|ui| {
+                    ui.label("regex");
+                    let edit = egui::TextEdit::singleline(&mut regex_search.pattern)
+                        .hint_text("search the first entries");
+                    let pattern = ui.add(edit);
+                    let keys = ui.checkbox(&mut regex_search.keys, "keys");
+                    let values = ui.checkbox(&mut regex_search.values, "values");
+                    let has_pattern = !regex_search.pattern.is_empty();
+                    if let (Err(e), true) = (regex_search.regex(), has_pattern) {
+                        ui.colored_label(Color32::RED, format!("invalid regex: {e}"));
+                    }
+                    // The search only runs over the first entries, the user expands it.
+                    let scope = regex_search.scope;
+                    if regex_search.is_active()
+                        && num_entries.is_none_or(|num| (scope as u64) < num)
+                    {
+                        let next = scope.saturating_mul(10);
+                        if ui.button(format!("search the first {next}")).clicked() {
+                            regex_search.scope = next;
+                        }
+                    }
+                    pattern.changed() || keys.changed() || values.changed()
+                });
+                if regex_changed.inner {
+                    *filter_limit = 0;
+                    *selected_row = None;
+                }
+
+                let encoding = *display_encoding;
+                let key_type = *key_type;
+                let decoded_prefix = match decode_prefix(prefix, key_type, encoding) {
+                    Ok(decoded) => decoded,
+                    Err(e) => {
+                        ui.colored_label(Color32::RED, format!("invalid key prefix: {e:#}"));
+                        Vec::new()
+                    }
+                };
+                let table_filter = TableFilter {
+                    prefix: decoded_prefix.clone(),
+                    filter: filter.clone(),
+                    glob: *glob_filter,
+                    reverse_key: *reverse_key,
+                    key_type,
+                    encoding,
+                };
+
+                // Counted again when the actions above wrote to the database.
+                let version = self.entries_version();
+                let num_entries = cached_len(entry_count, database, rtxn, version);
+                let num_entries = num_entries.or_report(self.errors, "counting the entries");
+                let num_entries = num_entries.unwrap_or_default();
+
+                // When filtering or going to a key prefix we collect the matching entries,
+                // stopping as soon as there are enough of them to fill the viewport
+                // (or as many as the user asked for).
+                let limit = (*filter_limit).max(viewport_rows);
+                let matches = if regex_search.is_active() && regex_search.regex().is_ok() {
+                    let searched = num_entries.min(regex_search.scope as u64);
+                    let matches = regex_search.matches(database, rtxn, *reverse, version);
+                    let matches = matches.or_report(self.errors, "searching with the regex");
+                    let matches = matches.unwrap_or_default();
+                    ui.label(format!(
+                        "{} matching entries in the first {searched} of {num_entries}",
+                        matches.len()
+                    ));
+                    Some(
+                        matches
+                            .iter()
+                            .map(|(key, data)| (key.as_slice(), data.as_slice()))
+                            .collect(),
+                    )
+                } else if *glob_filter && !filter.is_empty() {
+                    // The keys matching the glob start with the bytes of its literal prefix,
+                    // when it decodes to them exactly, except in a `MDB_REVERSEKEY` database.
+                    let literal = glob::literal_prefix(filter);
+                    let literal_bytes = match key_type {
+                        KeyType::Bytes => encoding.decode(literal).ok(),
+                        _ => None,
+                    };
+                    let seek = match literal_bytes {
+                        _ if *reverse_key => Vec::new(),
+                        _ if !decoded_prefix.is_empty() => decoded_prefix.clone(),
+                        Some(bytes) if encoding.encode(&bytes) == literal => bytes,
+                        _ => Vec::new(),
+                    };
+                    if glob_scan.as_ref().is_none_or(|scan| scan.version != version) {
+                        *glob_scan = Some(GlobScan::new(version, seek));
+                    }
+                    let scan = glob_scan.as_mut().unwrap();
+                    let result =
+                        scan.step(database, rtxn, *reverse, GLOB_SCAN_BUDGET, limit + 1, |key| {
+                            table_filter.matches(key)
+                        });
+                    result.or_report(self.errors, "searching the keys");
+
+                    ui.horizontal(|ui| {
+                        if scan.matches.len() > limit {
+                            ui.label(format!("showing the first {limit} matching entries"));
+                            if ui.button("show more").clicked() {
+                                *filter_limit = limit + viewport_rows;
+                            }
+                        } else if !scan.done {
+                            ui.spinner();
+                            ui.label(format!(
+                                "{} matching entries in the {} entries scanned so far",
+                                scan.matches.len(),
+                                scan.scanned
+                            ));
+                            ui.ctx().request_repaint();
+                        } else {
+                            ui.label(format!(
+                                "{} matching entries in {} scanned",
+                                scan.matches.len(),
+                                scan.scanned
+                            ));
+                        }
+                    });
+
+                    let matches = scan.matches.iter().take(limit);
+                    Some(matches.map(|(key, data)| (key.as_slice(), data.as_slice())).collect())
+                } else if filter.is_empty() && decoded_prefix.is_empty() {
+                    let page = pagination_ui(
+                        ui,
+                        tile_id,
+                        database,
+                        rtxn,
+                        pagination,
+                        *reverse,
+                        num_entries,
+                    );
+                    // The table is empty when the page can't be read.
+                    page.map(|page| {
+                        page.or_report(self.errors, "reading the page").unwrap_or_default()
+                    })
+                } else {
+                    let entries = match decoded_prefix.is_empty() {
+                        true => row_cache::entries_from(database, rtxn, Bound::Unbounded, *reverse)
+                            .map(|entries| Box::new(entries) as Entries),
+                        false => databases::prefix_entries(
+                            database,
+                            rtxn,
+                            &decoded_prefix,
+                            *reverse,
+                            *reverse_key,
+                        ),
+                    };
+                    let matches = entries.and_then(|entries| {
+                        entries
+                            // The first error is kept to report it.
+                            .filter(|entry| match entry {
+                                Ok((key, _)) => table_filter.matches(key),
+                                Err(_) => true,
+                            })
+                            .take(limit + 1)
+                            .collect::<heed::Result<Vec<_>>>()
+                    });
+                    let matches = matches.or_report(self.errors, "reading the entries");
+                    let mut matches = matches.unwrap_or_default();
+
+                    ui.horizontal(|ui| {
+                        if matches.len() > limit {
+                            matches.truncate(limit);
+                            ui.label(format!("showing the first {limit} matching entries"));
+                            if ui.button("show more").clicked() {
+                                *filter_limit = limit + viewport_rows;
+                            }
+                        } else if matches.is_empty() && !decoded_prefix.is_empty() {
+                            ui.label(if *reverse_key {
+                                "no keys end with this prefix"
+                            } else {
+                                "no keys start with this prefix"
+                            });
+                        } else {
+                            ui.label(format!("{} matching entries", matches.len()));
+                        }
+                    });
+
+                    Some(matches)
+                };
+
+                let num_rows = match &matches {
+                    Some(matches) => matches.len(),
+                    None => num_entries.try_into().unwrap(),
+                };
+                // The rows move when entries are inserted or removed, by this pane or
+                // any other one, the cache is also cleared where this pane writes.
+                let version = (num_entries, self.txn_generation);
+                row_cache.check_version(version);
+                row_cache.set_reverse(*reverse);
+                let entries_version = self.entries_version();
+                if total_value_bytes.as_ref().is_some_and(|sum| sum.version != entries_version) {
+                    *total_value_bytes = None;
+                }
+                if *show_lengths {
+                    // Summed like the count tool counts, not to freeze on large databases.
+                    let sum = total_value_bytes
+                        .get_or_insert_with(|| MatchCount::new(entries_version, Vec::new()));
+                    if !sum.done {
+                        let result = sum.step(database, rtxn, GLOB_SCAN_BUDGET, |_| true);
+                        if result.or_report(self.errors, "summing the value sizes").is_none() {
+                            sum.done = true;
+                            sum.cancelled = true;
+                        }
+                        ui.ctx().request_repaint();
+                    }
+                }
+                let mut prev_row_index = None;
+                // Positioned at the first row shown and at every row that doesn't follow
+                // the previous one, the table shows the visible rows in order.
+                let mut iter = None;
+                // Whether the rows were read, the rows after an error are left empty.
+                let mut rows_read = Ok(());
+                // The raw key and data of the entry to delete once the table is
+                // rendered and the iterator no longer borrows the transaction.
+                let mut entry_to_delete = None;
+                let mut key_to_revert = None;
+                // The key of the previous row, the values of a dup-sort key are grouped under it.
+                let mut prev_key = None;
+                // The edited entry to put when the user validated an in place edit.
+                let mut entry_to_put = None;
+                // The entry to copy into another database chosen from the operations.
+                let mut new_entry_copy = None;
+
+                // The group of keys to jump to, with the first segment of the keys.
+                let mut group_jump = None;
+                let goto = ui.horizontal(|ui| {
+                    ui.label("go to row");
+                    let max_row = num_rows.saturating_sub(1);
+                    ui.add(egui::DragValue::new(goto_row).clamp_range(0..=max_row));
+                    let go = ui.add_enabled(num_rows > 0, egui::Button::new("go")).clicked();
+                    ui.separator();
+
+                    ui.label("key groups split at");
+                    let edit = egui::TextEdit::singleline(group_delimiter).desired_width(30.0);
+                    ui.add(edit).on_hover_text(format!(
+                        "the keys before the first delimiter form a group, in {}",
+                        encoding.label()
+                    ));
+                    let delimiter = encoding.decode(group_delimiter).ok();
+                    let delimiter = delimiter.filter(|delimiter| !delimiter.is_empty());
+                    let enabled = delimiter.is_some() && num_rows > 0;
+                    let previous = ui.add_enabled(enabled, egui::Button::new("⏶ previous group"));
+                    let next = ui.add_enabled(enabled, egui::Button::new("⏷ next group"));
+                    if let Some(delimiter) = delimiter {
+                        if previous.clicked() {
+                            group_jump = Some((delimiter, false));
+                        } else if next.clicked() {
+                            group_jump = Some((delimiter, true));
+                        }
+                    }
+                    go
+                });
+                let mut jumped = false;
+                if let Some((delimiter, forward)) = group_jump {
+                    let row = selected_row.unwrap_or(0).min(num_rows - 1);
+                    let keys = |start: usize, len: usize| match &matches {
+                        Some(matches) => Ok(matches
+                            .iter()
+                            .skip(start)
+                            .take(len)
+                            .map(|(key, _)| key.to_vec())
+                            .collect()),
+                        None => row_cache
+                            .iter_from(database, rtxn, start)?
+                            .take(len)
+                            .map(|entry| entry.map(|(key, _)| key.to_vec()))
+                            .collect(),
+                    };
+                    let group_row = if forward {
+                        prefix_groups::next_group_row(row, &delimiter, keys)
+                    } else {
+                        prefix_groups::previous_group_row(row, &delimiter, keys)
+                    };
+                    match group_row.or_report(self.errors, "looking for the group of keys") {
+                        Some(Some(row)) => {
+                            *goto_row = row;
+                            jumped = true;
+                        }
+                        Some(None) if forward => *message = Some("this is the last group".into()),
+                        Some(None) => *message = Some("this is the first group".into()),
+                        None => (),
+                    }
+                    ui.ctx().request_repaint();
+                }
+                // The row jumped to is shown like the one gone to.
+                let goto = goto.inner || jumped;
+                if goto {
+                    let row = (*goto_row).min(num_rows - 1);
+                    // The row cache seeks near the row instead of iterating from the start.
+                    let entry = match &matches {
+                        Some(matches) => Ok(matches.get(row).map(|(key, _)| key.to_vec())),
+                        None => row_cache
+                            .iter_from(database, rtxn, row)
+                            .and_then(|mut entries| entries.next().transpose())
+                            .map(|entry| entry.map(|(key, _)| key.to_vec())),
+                    };
+                    // The selection stays where it was when the row can't be read.
+                    if let Some(entry) = entry.or_report(self.errors, "going to the row") {
+                        *goto_row = row;
+                        *selected_row = Some(row);
+                        if let Some(key) = entry {
+                            let key = key_type.encode(&key, encoding);
+                            *message = Some(format!("row {goto_row} has the key {key}"));
+                        }
+                    }
+                    ui.ctx().request_repaint();
+                }
+
+                // The keys go to the hovered table, unless a text field has the focus.
+                let (mut up, mut down, mut enter, mut delete) = (false, false, false, false);
+                if ui.ui_contains_pointer() && ui.memory(|m| m.focus().is_none()) {
+                    ui.input_mut(|i| {
+                        up = i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp);
+                        down = i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown);
+                        enter = i.consume_key(egui::Modifiers::NONE, egui::Key::Enter);
+                        delete = i.consume_key(egui::Modifiers::NONE, egui::Key::Delete);
+                    });
+                }
+                // The row selected before the arrow keys moved it.
+                let previous_row = *selected_row;
+                if (up || down) && num_rows > 0 {
+                    *selected_row = Some(match *selected_row {
+                        None => 0,
+                        Some(row_index) if up => row_index.saturating_sub(1),
+                        Some(row_index) => (row_index + 1).min(num_rows - 1),
+                    });
+                }
+
+                let mut delete_checked = false;
+                if !read_only {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} keys checked", checked_keys.len()));
+                        let all =
+                            ui.button("check all").on_hover_text("the keys of the rows shown");
+                        if all.clicked() {
+                            match &matches {
+                                Some(matches) => {
+                                    checked_keys.extend(matches.iter().map(|(key, _)| key.to_vec()))
+                                }
+                                None => {
+                                    let keys = database.iter(rtxn).and_then(|entries| {
+                                        entries
+                                            .map(|entry| entry.map(|(key, _)| key.to_vec()))
+                                            .collect()
+                                    });
+                                    let keys: Option<Vec<_>> =
+                                        keys.or_report(self.errors, "checking the keys");
+                                    checked_keys.extend(keys.into_iter().flatten());
+                                }
+                            }
+                        }
+                        let uncheck = egui::Button::new("uncheck all");
+                        if ui.add_enabled(!checked_keys.is_empty(), uncheck).clicked() {
+                            checked_keys.clear();
+                        }
+                        let delete = egui::Button::new("delete checked");
+                        let delete = ui.add_enabled(is_writing && !checked_keys.is_empty(), delete);
+                        if delete
+                            .on_disabled_hover_text("Switch to writing mode to delete entries")
+                            .clicked()
+                        {
+                            if checked_keys.len() > CHECKED_DELETE_CONFIRMATION {
+                                *confirming_checked_delete = true;
+                            } else {
+                                delete_checked = true;
+                            }
+                        }
+                    });
+                }
+
+                let show_lengths = *show_lengths;
+                let timestamps = self.timestamps.filter(|_| *show_modified);
+                // The unnamed database also keeps a record of every named database under its name.
+                let sub_databases: HashSet<&[u8]> = match database_name {
+                    None => self.database_names.iter().map(|name| name.as_bytes()).collect(),
+                    Some(_) => HashSet::new(),
+                };
+                let mut sub_database_to_open = None;
+                let plain_utf8 = *plain_utf8;
+                // The text shown in a cell and whether it isn't valid UTF-8 when it should be.
+                let shown_text = |bytes: &[u8], encoded: &str| match plain_utf8 {
+                    false => (encoded.to_owned(), false),
+                    true => match encoding::plain_utf8(bytes) {
+                        Some(plain) => (plain, false),
+                        None => (encoded.to_owned(), true),
+                    },
+                };
+                let truncate_cells = *truncate_cells;
+                let expand_hex = *expand_hex;
+                let value_compression = *value_compression;
+                // The form is filled with the decompressed data, and how it was compressed to
+                // compress it again when inserted, see `form_compression`.
+                let form_data = |data: &[u8]| match value_compression {
+                    Some(_) => match compression::decompress(data) {
+                        Ok((compression, decompressed)) => {
+                            (encoding.encode(&decompressed), Some(Some(compression)))
+                        }
+                        Err(_) => (encoding.encode(data), Some(None)),
+                    },
+                    None => (encoding.encode(data), None),
+                };
+                // The text to show in a cell and the full text when it was cut.
+                let shorten = |text: String| match truncate_cells {
+                    true => truncate_cell(text),
+                    false => (text, None),
+                };
+                let not_utf8 = |ui: &mut egui::Ui| {
+                    ui.colored_label(Color32::YELLOW, "⚠")
+                        .on_hover_text(format!("not valid UTF-8, shown in {}", encoding.label()));
+                };
+                // The rows written in the write txn are marked in a first column.
+                let dirty_keys = self.dirty_keys.of(database_name).filter(|_| is_writing);
+                // The keys and values columns start at the widths they were resized to.
+                let saved_widths = self.column_widths.get(database_name).copied();
+                let column = |i: usize| match saved_widths {
+                    Some(widths) => Column::initial(widths[i]).resizable(true),
+                    None => Column::auto().resizable(true),
+                };
+                let mut table = TableBuilder::new(ui);
+                if dirty_keys.is_some() {
+                    table = table.column(Column::exact(36.0));
+                }
+                let mut table = table.column(column(0)).column(column(1));
+                for _ in &value_layout.fields {
+                    table = table.column(Column::auto().resizable(true));
+                }
+                if show_lengths {
+                    table = table.column(Column::auto().resizable(true));
+                }
+                if timestamps.is_some() {
+                    table = table.column(Column::auto().resizable(true));
+                }
+                let mut table = table.column(Column::remainder());
+                if let (true, Some(row_index)) = (up || down || goto, *selected_row) {
+                    table = table.scroll_to_row(row_index, None);
+                }
+                table
+                    .header(20.0, |mut header| {
+                        if dirty_keys.is_some() {
+                            header.col(|_| ());
+                        }
+                        header.col(|ui| {
+                            ui.label("Keys");
+                        });
+                        header.col(|ui| {
+                            ui.label("Values");
+                        });
+                        for field in &value_layout.fields {
+                            header.col(|ui| {
+                                ui.label(&field.name);
+                            });
+                        }
+                        if show_lengths {
+                            header.col(|ui| {
+                                match total_value_bytes {
+                                    Some(sum) if !sum.done => {
+                                        ui.label(format!("Bytes, Σ ≥ {}", sum.value_bytes))
+                                            .on_hover_text(format!(
+                                                "the total size of the values, {} entries \
+                                                summed so far",
+                                                sum.scanned
+                                            ));
+                                    }
+                                    Some(sum) if !sum.cancelled => {
+                                        ui.label(format!("Bytes, Σ {}", sum.value_bytes))
+                                            .on_hover_text("the total size of the values");
+                                    }
+                                    _ => {
+                                        ui.label("Bytes");
+                                    }
+                                }
+                                let sum = ui.small_button("⟳");
+                                if sum.on_hover_text("sum again").clicked() {
+                                    *total_value_bytes = None;
+                                }
+                            });
+                        }
+                        if timestamps.is_some() {
+                            header.col(|ui| {
+                                ui.label("Modified").on_hover_text("UTC");
+                            });
+                        }
+                        header.col(|ui| {
+                            ui.label("Operations");
+                        });
+                    })
+                    .body(|body| {
+                        let first = usize::from(dirty_keys.is_some());
+                        if let &[key_width, data_width, ..] = &body.widths()[first..] {
+                            let widths = [key_width, data_width];
+                            self.column_widths.insert(database_name.clone(), widths);
+                        }
+                        // The heights of the expanded rows as they were last shown.
+                        let heights = expanded_heights.clone();
+                        let add_row = |row_index: usize, mut row: egui_extras::TableRow<'_, '_>| {
+                            let entry = match &matches {
+                                Some(matches) => matches.get(row_index).copied(),
+                                None if rows_read.is_err() => None,
+                                None => {
+                                    if prev_row_index.is_none_or(|p| p + 1 != row_index) {
+                                        let entries =
+                                            row_cache.iter_from(database, rtxn, row_index);
+                                        iter = entries.map_err(|e| rows_read = Err(e)).ok();
+                                    }
+                                    prev_row_index = Some(row_index);
+                                    match iter.as_mut().and_then(Iterator::next) {
+                                        Some(Ok(entry)) => Some(entry),
+                                        Some(Err(e)) => {
+                                            rows_read = Err(e);
+                                            None
+                                        }
+                                        None => None,
+                                    }
+                                }
+                            };
+
+                            if let Some((key, data)) = entry {
+                                let encoded_key = key_type.encode(key, encoding);
+                                // Only the start of the long values is encoded every frame,
+                                // they are encoded in full when copied or edited.
+                                let preview = cell_preview(data);
+                                let shown_data = encoding.encode(preview);
+
+                                let is_duplicate = *dup_sort && prev_key == Some(key);
+                                prev_key = Some(key);
+                                let expanded = expanded_keys.contains(key);
+                                let mut expanded_height: f32 = 0.0;
+
+                                if let Some(dirty_keys) = dirty_keys {
+                                    row.col(|ui| {
+                                        if !dirty_keys.contains(key) {
+                                            return;
+                                        }
+                                        ui.horizontal(|ui| {
+                                            ui.spacing_mut().item_spacing.x = 2.0;
+                                            ui.colored_label(Color32::YELLOW, "●").on_hover_text(
+                                                "written in the write txn, not committed yet",
+                                            );
+                                            // The duplicates of a key are reverted with it.
+                                            if !read_only && !is_duplicate {
+                                                let revert = ui.small_button("↺").on_hover_text(
+                                                    "put back what the key has in the last \
+                                                    commit, or delete it when it was inserted",
+                                                );
+                                                if revert.clicked() {
+                                                    key_to_revert = Some(key.to_vec());
+                                                }
+                                            }
+                                        });
+                                    });
+                                }
+
+                                row.col(|ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            let caret = if expanded { "⏷" } else { "⏵" };
+                                            let caret = ui.small_button(caret);
+                                            if caret
+                                                .on_hover_text("show the whole key and value")
+                                                .clicked()
+                                            {
+                                                if expanded {
+                                                    expanded_keys.remove(key);
+                                                } else {
+                                                    expanded_keys.insert(key.to_vec());
+                                                }
+                                            }
+                                            let selected = *selected_row == Some(row_index);
+                                            // The duplicates of a key are deleted with it.
+                                            if !read_only && !is_duplicate {
+                                                let mut checked = checked_keys.contains(key);
+                                                if ui.checkbox(&mut checked, "").changed() {
+                                                    if checked {
+                                                        checked_keys.insert(key.to_vec());
+                                                    } else {
+                                                        checked_keys.remove(key);
+                                                    }
+                                                }
+                                            }
+                                            copy_button(ui, &encoded_key);
+                                            // The integer keys are always shown in decimal.
+                                            let mut full_text = None;
+                                            let text = if is_duplicate {
+                                                egui::RichText::new("   ↳").weak()
+                                            } else if key_type == KeyType::Bytes {
+                                                let (text, invalid) = shown_text(key, &encoded_key);
+                                                if invalid {
+                                                    not_utf8(ui);
+                                                }
+                                                let (text, full) = shorten(text);
+                                                full_text = full;
+                                                egui::RichText::new(text)
+                                            } else {
+                                                egui::RichText::new(&encoded_key)
+                                            };
+                                            let mut response = ui.selectable_label(selected, text);
+                                            if let Some(full_text) = full_text {
+                                                response = response.on_hover_text(full_text);
+                                            }
+                                            if copy_context_menu(response, key).clicked() {
+                                                *selected_row = (!selected).then_some(row_index);
+                                            }
+                                        });
+                                        if expanded {
+                                            let height = expanded_ui(ui, key, expand_hex);
+                                            expanded_height = expanded_height.max(height);
+                                        }
+                                    });
+                                });
+                                row.col(|ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            let edit_id = egui::Id::new((tile_id, "editing_cell"));
+                                            match editing_cell {
+                                                Some((index, entry)) if *index == row_index => {
+                                                    let response = ui.add(
+                                                        egui::TextEdit::singleline(&mut entry.data)
+                                                            .id(edit_id),
+                                                    );
+                                                    if response.lost_focus() {
+                                                        if ui.input(|i| {
+                                                            i.key_pressed(egui::Key::Enter)
+                                                        }) {
+                                                            entry_to_put = editing_cell.take();
+                                                        } else {
+                                                            *editing_cell = None;
+                                                        }
+                                                    }
+                                                }
+                                                _ if sub_databases.contains(key) => {
+                                                    let name = String::from_utf8_lossy(key);
+                                                    ui.label("🗄 sub-database").on_hover_text(
+                                                        format!(
+                                                            "LMDB keeps the record of the {name} \
+                                                            database under its name, the value \
+                                                            is internal to LMDB"
+                                                        ),
+                                                    );
+                                                    if ui.small_button("open").clicked() {
+                                                        sub_database_to_open =
+                                                            Some(name.into_owned());
+                                                    }
+                                                }
+                                                _ => {
+                                                    copy_encoded_button(ui, data, encoding);
+                                                    let (mut text, invalid) =
+                                                        shown_text(preview, &shown_data);
+                                                    if invalid {
+                                                        not_utf8(ui);
+                                                    }
+                                                    if preview.len() < data.len() {
+                                                        text.push_str(&format!(
+                                                            "… ({} bytes)",
+                                                            data.len()
+                                                        ));
+                                                    }
+                                                    let (text, full_text) = shorten(text);
+                                                    let label = egui::Label::new(text)
+                                                        .sense(egui::Sense::click());
+                                                    let mut response = ui.add(label);
+                                                    if let Some(full_text) = full_text {
+                                                        response =
+                                                            response.on_hover_text(full_text);
+                                                    }
+                                                    let response =
+                                                        copy_context_menu(response, data);
+                                                    if response.double_clicked() {
+                                                        // Putting into a dup-sort database adds a value.
+                                                        if is_writing && !*dup_sort {
+                                                            let entry = EscapedEntry {
+                                                                key: encoded_key.clone(),
+                                                                data: encoding.encode(data),
+                                                            };
+                                                            *editing_cell =
+                                                                Some((row_index, entry));
+                                                            ui.memory_mut(|m| {
+                                                                m.request_focus(edit_id)
+                                                            });
+                                                        } else if !read_only {
+                                                            entry_to_insert.key =
+                                                                encoded_key.clone();
+                                                            (
+                                                                entry_to_insert.data,
+                                                                *form_compression,
+                                                            ) = form_data(data);
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        });
+                                        if expanded {
+                                            let height = expanded_ui(ui, data, expand_hex);
+                                            expanded_height = expanded_height.max(height);
+                                        }
+                                    });
+                                });
+                                for field in &value_layout.fields {
+                                    row.col(|ui| match field.parse(data) {
+                                        Some(text) => {
+                                            ui.label(text);
+                                        }
+                                        None => {
+                                            ui.weak("—").on_hover_text("the value is too short");
+                                        }
+                                    });
+                                }
+                                if show_lengths {
+                                    row.col(|ui| {
+                                        ui.label(format!("{} / {}", key.len(), data.len()))
+                                            .on_hover_text("key / value bytes");
+                                    });
+                                }
+                                if let Some(timestamps) = timestamps {
+                                    row.col(|ui| {
+                                        match timestamps::get(timestamps, rtxn, database_name, key)
+                                        {
+                                            Ok(Some(time)) => {
+                                                let time = humantime::format_rfc3339_seconds(time);
+                                                ui.label(time.to_string());
+                                            }
+                                            Ok(None) => {
+                                                ui.weak("—").on_hover_text("not recorded");
+                                            }
+                                            Err(e) => {
+                                                ui.colored_label(Color32::RED, "⚠")
+                                                    .on_hover_text(e.to_string());
+                                            }
+                                        }
+                                    });
+                                }
+                                row.col(|ui| {
+                                    if read_only {
+                                        return;
+                                    }
+                                    // TODO Replace me by a ✏️
+                                    if ui.button("edit").clicked() {
+                                        entry_to_insert.key = encoded_key.clone();
+                                        (entry_to_insert.data, *form_compression) = form_data(data);
+                                    }
+                                    // Only fills the form, nothing is written until inserted.
+                                    let duplicate = ui
+                                        .button("duplicate")
+                                        .on_hover_text("put a copy of the entry under a new key");
+                                    if duplicate.clicked() {
+                                        // The integer keys can't be suffixed, they are edited.
+                                        entry_to_insert.key = match key_type {
+                                            KeyType::Bytes => format!("{encoded_key}-copy"),
+                                            _ => encoded_key.clone(),
+                                        };
+                                        (entry_to_insert.data, *form_compression) = form_data(data);
+                                    }
+                                    let delete = egui::Button::new(
+                                        egui::RichText::new("🗑").color(Color32::RED),
+                                    );
+                                    if ui
+                                        .add_enabled(is_writing, delete)
+                                        .on_disabled_hover_text(
+                                            "Switch to writing mode to delete entries",
+                                        )
+                                        .clicked()
+                                    {
+                                        entry_to_delete = Some((key.to_vec(), data.to_vec()));
+                                    }
+                                    if is_writing && ui.button("rename").clicked() {
+                                        *key_rename = Some(KeyRename {
+                                            old_key: key.to_vec(),
+                                            data: data.to_vec(),
+                                            new_key: encoded_key.clone(),
+                                            collides: false,
+                                        });
+                                    }
+                                    let others: Vec<_> = self
+                                        .open_databases
+                                        .iter()
+                                        .filter(|other| {
+                                            other.name != *database_name && !other.locked
+                                        })
+                                        .collect();
+                                    if is_writing && !others.is_empty() {
+                                        ui.menu_button("copy to…", |ui| {
+                                            for target in others {
+                                                ui.horizontal(|ui| {
+                                                    ui.label(target.label());
+                                                    let copy = ui.button("copy").clicked();
+                                                    let moving = ui.button("move").clicked();
+                                                    if copy || moving {
+                                                        new_entry_copy = Some(EntryCopy {
+                                                            key: key.to_vec(),
+                                                            data: data.to_vec(),
+                                                            target: target.clone(),
+                                                            moving,
+                                                        });
+                                                        ui.close_menu();
+                                                    }
+                                                });
+                                            }
+                                        });
+                                    }
+                                });
+
+                                if expanded {
+                                    expanded_heights
+                                        .insert(row_index, ROW_HEIGHT + expanded_height);
+                                } else {
+                                    expanded_heights.remove(&row_index);
+                                }
+                            }
+                        };
+                        // All the heights are gone through, only when some rows are expanded.
+                        if heights.is_empty() {
+                            body.rows(ROW_HEIGHT, num_rows, add_row);
+                        } else {
+                            let heights = (0..num_rows)
+                                .map(|row| heights.get(&row).copied().unwrap_or(ROW_HEIGHT));
+                            body.heterogeneous_rows(heights, add_row);
+                        }
+                    });
+
+                drop(iter);
+                rows_read.or_report(self.errors, "reading the rows");
+                if let Some(name) = sub_database_to_open {
+                    self.database_to_open = Some(name);
+                }
+                if let Some(row_index) = *selected_row {
+                    let entry = match &matches {
+                        Some(matches) => Ok(matches.get(row_index).copied()),
+                        None => row_cache
+                            .iter_from(database, rtxn, row_index)
+                            .and_then(|mut entries| entries.next().transpose()),
+                    };
+                    match entry.or_report(self.errors, "reading the selected row") {
+                        Some(Some((key, data))) => {
+                            if enter && !read_only {
+                                entry_to_insert.key = key_type.encode(key, encoding);
+                                (entry_to_insert.data, *form_compression) = form_data(data);
+                            }
+                            if delete && is_writing {
+                                entry_to_delete = Some((key.to_vec(), data.to_vec()));
+                            }
+
+                            // Decompressing a large value takes a while, it is done once per row.
+                            let cache_key = (row_index, key.to_vec(), self.entries_version());
+                            let cached = matches!(decompressed_value,
+                                Some((cached_key, _)) if *cached_key == cache_key);
+                            if value_compression.is_none() {
+                                *decompressed_value = None;
+                            } else if !cached {
+                                let decompressed = compression::decompress(data);
+                                *decompressed_value = Some((cache_key, decompressed));
+                            }
+                            let title = format!("Value of {}", key_type.encode(key, encoding));
+                            let mut open = true;
+                            let mut reference = None;
+                            egui::Window::new(title)
+                                .id(egui::Id::new((tile_id, "hex_dump")))
+                                .open(&mut open)
+                                .show(ui.ctx(), |ui| {
+                                    let decompressed =
+                                        decompressed_value.as_ref().map(|(_, value)| value);
+                                    let data = match decompressed {
+                                        Some(Ok((compression, decompressed))) => {
+                                            ui.weak(format!(
+                                                "{} decompressed from {} to {} bytes",
+                                                compression.label(),
+                                                data.len(),
+                                                decompressed.len(),
+                                            ));
+                                            decompressed.as_slice()
+                                        }
+                                        Some(Err(e)) => {
+                                            ui.weak(format!("{e}, the raw bytes are shown"));
+                                            data
+                                        }
+                                        None => data,
+                                    };
+                                    if let Some(decoder) = *value_decoder {
+                                        decoded_ui(ui, tile_id, decoder, data);
+                                    }
+                                    byte_inspector_ui(ui, tile_id, key, data);
+                                    find_in_value_ui(ui, tile_id, data, value_query, value_match);
+                                    reference =
+                                        reference_rule.ui(ui, (tile_id, "references"), data);
+                                    hex_dump_ui(ui, data);
+                                });
+                            if !open {
+                                *selected_row = None;
+                            }
+                            // The table goes to the key referred to, the integer keys
+                            // are referred to in decimal.
+                            if let Some(reference) = reference {
+                                *prefix = match key_type {
+                                    KeyType::Bytes => encoding.encode(reference.as_bytes()),
+                                    _ => reference,
+                                };
+                                *filter_limit = 0;
+                                *selected_row = Some(0);
+                                *glob_scan = None;
+                            }
+                        }
+                        // The entry was removed or the table got shorter.
+                        Some(None) => *selected_row = None,
+                        // The selection doesn't move to a row that can't be read.
+                        None => *selected_row = previous_row,
+                    }
+                }
+
+                if let Some((key, data)) = entry_to_delete {
+                    if let Either::Right(wtxn) = self.txn.as_mut() {
+                        let result = self.op_log.delete(
+                            database,
+                            database_name,
+                            *dup_sort,
+                            wtxn,
+                            &key,
+                            &data,
+                        );
+                        if result.or_report(self.errors, "deleting an entry").is_some() {
+                            self.dirty_keys.insert(database_name, &key);
+                        }
+                        row_cache.clear();
+                        *total_value_bytes = None;
+                        ui.ctx().request_repaint();
+                    }
+                }
+
+                if let Some(key) = key_to_revert {
+                    if let Either::Right(wtxn) = self.txn.as_mut() {
+                        // A new read txn sees the last commit, not what the write txn changed.
+                        let result = self.env.read_txn().and_then(|committed| {
+                            revert_key(
+                                self.op_log,
+                                wtxn,
+                                &committed,
+                                database,
+                                database_name,
+                                *dup_sort,
+                                &key,
+                            )
+                        });
+                        if result.or_report(self.errors, "reverting a key").is_some() {
+                            self.dirty_keys.remove(database_name, &key);
+                        }
+                        row_cache.clear();
+                        *total_value_bytes = None;
+                        ui.ctx().request_repaint();
+                    }
+                }
+
+                if *confirming_checked_delete {
+                    egui::Window::new(format!("Delete the checked keys of {name}?"))
+                        .id(egui::Id::new((tile_id, "checked_delete")))
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!(
+                                "This will delete the {} checked keys{}.",
+                                checked_keys.len(),
+                                if *dup_sort { " and all their values" } else { "" }
+                            ));
+                            ui.horizontal(|ui| {
+                                let delete = egui::Button::new("delete");
+                                delete_checked = ui.add_enabled(is_writing, delete).clicked();
+                                if ui.button("cancel").clicked() {
+                                    *confirming_checked_delete = false;
+                                }
+                            });
+                        });
+                }
+
+                if let (true, Either::Right(wtxn)) = (delete_checked, self.txn.as_mut()) {
+                    let mut deleted = 0;
+                    for key in checked_keys.drain() {
+                        let result =
+                            self.op_log.delete_key(database, database_name, *dup_sort, wtxn, &key);
+                        if result.or_report(self.errors, "deleting an entry") == Some(true) {
+                            self.dirty_keys.insert(database_name, &key);
+                            deleted += 1;
+                        }
+                    }
+                    *message = Some(format!("deleted {deleted} keys"));
+                    *confirming_checked_delete = false;
+                    row_cache.clear();
+                    *total_value_bytes = None;
+                    ui.ctx().request_repaint();
+                }
+
+                if let Some(rename) = key_rename {
+                    let title = format!("Rename {}", key_type.encode(&rename.old_key, encoding));
+                    let mut open = true;
+                    let mut confirmed = false;
+                    egui::Window::new(title)
+                        .id(egui::Id::new((tile_id, "key_rename")))
+                        .open(&mut open)
+                        .collapsible(false)
+                        .show(ui.ctx(), |ui| {
+                            let edit = egui::TextEdit::singleline(&mut rename.new_key);
+                            if ui.add(edit.hint_text("new key")).changed() {
+                                rename.collides = false;
+                            }
+                            if rename.collides {
+                                ui.colored_label(
+                                    Color32::RED,
+                                    "This key already exists, its data would be overwritten.",
+                                );
+                            }
+                            let button = if rename.collides { "overwrite" } else { "rename" };
+                            confirmed = ui.button(button).clicked();
+                        });
+
+                    let mut renamed = false;
+                    if let (true, Either::Right(wtxn)) = (confirmed, self.txn.as_mut()) {
+                        let new_key = key_type.decode(&rename.new_key, encoding);
+                        if let Some(new_key) = new_key.or_report(self.errors, "invalid key") {
+                            // The dup-sort databases add the data to the values of the new key.
+                            let exists = if *dup_sort || new_key == rename.old_key {
+                                Some(false)
+                            } else {
+                                let old = database.get(wtxn, &new_key);
+                                old.map(|old| old.is_some())
+                                    .or_report(self.errors, "looking up the new key")
+                            };
+                            if exists == Some(true) && !rename.collides {
+                                rename.collides = true;
+                            } else if exists.is_some() {
+                                let result = rename_key(
+                                    self.op_log,
+                                    wtxn,
+                                    database,
+                                    database_name,
+                                    *dup_sort,
+                                    rename,
+                                    &new_key,
+                                );
+                                renamed = result.or_report(self.errors, "renaming a key").is_some();
+                                if renamed {
+                                    self.dirty_keys.insert(database_name, &rename.old_key);
+                                    self.dirty_keys.insert(database_name, &new_key);
+                                }
+                                row_cache.clear();
+                            }
+                        }
+                    }
+                    // The write txn may have been aborted in the meantime.
+                    if !open || renamed || self.txn.is_left() {
+                        *key_rename = None;
+                    }
+                }
+
+                if let (Some(copy), Either::Right(wtxn)) = (new_entry_copy, self.txn.as_mut()) {
+                    let target = &copy.target;
+                    // The dup-sort databases add the data to the existing values of the key.
+                    let collides = if target.dup_sort {
+                        Some(false)
+                    } else {
+                        let old = target.database.get(wtxn, &copy.key).map(|old| old.is_some());
+                        old.or_report(self.errors, "copying an entry")
+                    };
+                    match collides {
+                        Some(true) => *entry_to_copy = Some(copy),
+                        Some(false) => {
+                            let result = copy_entry(
+                                self.op_log,
+                                wtxn,
+                                database,
+                                database_name,
+                                *dup_sort,
+                                &copy,
+                            );
+                            if result.or_report(self.errors, "copying an entry").is_some() {
+                                self.dirty_keys.insert(&target.name, &copy.key);
+                                if copy.moving {
+                                    self.dirty_keys.insert(database_name, &copy.key);
+                                }
+                            }
+                            row_cache.clear();
+                            *total_value_bytes = None;
+                        }
+                        None => (),
+                    }
+                }
+
+                if let Some(copy) = entry_to_copy {
+                    let title = format!("Overwrite the key in {}?", copy.target.label());
+                    let (mut overwrite, mut skip) = (false, false);
+                    egui::Window::new(title)
+                        .id(egui::Id::new((tile_id, "entry_to_copy")))
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ui.ctx(), |ui| {
+                            ui.label(format!(
+                                "{} already has the key {}.",
+                                copy.target.label(),
+                                key_type.encode(&copy.key, encoding)
+                            ));
+                            ui.horizontal(|ui| {
+                                overwrite = ui.button("overwrite").clicked();
+                                skip = ui.button("skip").clicked();
+                            });
+                        });
+
+                    if overwrite {
+                        if let Either::Right(wtxn) = self.txn.as_mut() {
+                            let result = copy_entry(
+                                self.op_log,
+                                wtxn,
+                                database,
+                                database_name,
+                                *dup_sort,
+                                copy,
+                            );
+                            if result.or_report(self.errors, "copying an entry").is_some() {
+                                self.dirty_keys.insert(&copy.target.name, &copy.key);
+                                if copy.moving {
+                                    self.dirty_keys.insert(database_name, &copy.key);
+                                }
+                            }
+                            row_cache.clear();
+                            *total_value_bytes = None;
+                        }
+                    }
+                    // The write txn may have been aborted in the meantime.
+                    if overwrite || skip || self.txn.is_left() {
+                        *entry_to_copy = None;
+                    }
+                }
+
+                if let Some((_, entry)) = entry_to_put {
+                    if let Either::Right(wtxn) = self.txn.as_mut() {
+                        let key = key_type.decode(&entry.key, encoding);
+                        let key = key.or_report(self.errors, "invalid key");
+                        let data = entry.decoded_data(encoding);
+                        let data = data.or_report(self.errors, "invalid data");
+                        if let (Some(key), Some(data)) = (key, data) {
+                            let result = self.op_log.put(
+                                database,
+                                database_name,
+                                *dup_sort,
+                                wtxn,
+                                &key,
+                                &data,
+                            );
+                            if result.as_ref().is_err_and(is_map_full) {
+                                self.failed_put = Some(FailedPut {
+                                    database_name: database_name.clone(),
+                                    dup_sort: *dup_sort,
+                                    key: key.clone(),
+                                    data,
+                                });
+                            }
+                            if result.or_report(self.errors, "putting an entry").is_some() {
+                                self.dirty_keys.insert(database_name, &key);
+                            }
+                            *total_value_bytes = None;
+                        }
+                        ui.ctx().request_repaint();
+                    }
+                }
+            }
+            Pane::OpenNew { database_to_open, focused, missing } => {
+                // The name of the database to create, typed or that couldn't be opened.
+                let mut database_to_create = None;
+                let response = ui.horizontal(|ui| {
+                    // If there is a write txn opened, use it, else make the wtxn live longer and deref it.
+                    let long_wtxn: &&mut RwTxn;
+                    let rtxn: &heed::RoTxn;
+                    match self.txn.as_ref() {
+                        Either::Left(txn) => rtxn = txn,
+                        Either::Right(wtxn) => {
+                            long_wtxn = wtxn;
+                            rtxn = long_wtxn.deref();
+                        }
+                    };
+
+                    let name_edit =
+                        egui::TextEdit::singleline(database_to_open).hint_text("database name");
+                    let name_edit = ui.add(name_edit);
+                    if !mem::replace(focused, true) {
+                        name_edit.request_focus();
+                    }
+                    if name_edit.changed() {
+                        *missing = None;
+                    }
+                    let enter =
+                        name_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let exists = database_to_open.is_empty()
+                        || self.database_names.iter().any(|name| name == database_to_open);
+                    if !exists {
+                        ui.weak("no database with this name");
+                    }
+                    let can_create = self.txn.is_right() && !database_to_open.is_empty();
+                    if ui.button("open").clicked() || enter {
+                        let env = self.env;
+                        let database_name = if database_to_open.is_empty() {
+                            None
+                        } else {
+                            Some(mem::take(database_to_open))
+                        };
+
+                        let database = env
+                            .open_database(rtxn, database_name.as_ref().map(AsRef::as_ref))
+                            .or_report(self.errors, "opening the database");
+                        // The unnamed database always exists.
+                        if let (Some(None), Some(name)) = (&database, &database_name) {
+                            *missing = Some(name.clone());
+                        }
+
+                        database.flatten().map(|database| {
+                            Pane::database_entries(env, rtxn, database_name, database)
+                        })
+                    } else {
+                        if !self.read_only
+                            && ui
+                                .add_enabled(can_create, egui::Button::new("create"))
+                                .on_hover_text(CREATED_DATABASE_FLAGS)
+                                .on_disabled_hover_text(if self.txn.is_right() {
+                                    "Type the name of the database to create"
+                                } else {
+                                    "Switch to writing mode to create a database"
+                                })
+                                .clicked()
+                        {
+                            database_to_create = Some(mem::take(database_to_open));
+                        }
+                        None
+                    }
+                });
+
+                if let Some(name) = missing.as_ref() {
+                    ui.horizontal(|ui| {
+                        let text = format!("Database '{name}' does not exist");
+                        ui.colored_label(Color32::YELLOW, text);
+                        if !self.read_only {
+                            let create =
+                                ui.add_enabled(self.txn.is_right(), egui::Button::new("create it"));
+                            let hint = "Switch to writing mode to create a database";
+                            let create = create.on_hover_text(CREATED_DATABASE_FLAGS);
+                            if create.on_disabled_hover_text(hint).clicked() {
+                                database_to_create = Some(name.clone());
+                            }
+                        }
+                    });
+                }
+
+                let mut response = response.inner;
+                if let Some(database_name) = database_to_create {
+                    let env = self.env;
+                    let database = match self.txn.as_mut() {
+                        Either::Left(_) => None,
+                        Either::Right(wtxn) => env
+                            .create_database(wtxn, Some(&database_name))
+                            .or_report(self.errors, "creating the database"),
+                    };
+
+                    response = database.map(|database| {
+                        self.databases_changed = true;
+                        Pane::database_entries(env, self.rtxn(), Some(database_name), database)
+                    });
+                }
+
+                // The names with the typed text, the exact match needs no suggestion.
+                let typed = database_to_open.to_lowercase();
+                let suggestions: Vec<_> = self
+                    .database_names
+                    .iter()
+                    .filter(|name| !typed.is_empty() && name.to_lowercase().contains(&typed))
+                    .filter(|name| *name != database_to_open)
+                    .take(MAX_SUGGESTED_DATABASES)
+                    .collect();
+                for name in suggestions {
+                    if ui.selectable_label(false, name).clicked() {
+                        database_to_open.clone_from(name);
+                        // The field gets the focus back for Enter to open the database.
+                        *focused = false;
+                    }
+                }
+
+                if let Some(p) = response {
+                    *pane = p;
+                }
+            }
+        }
+
+        egui_tiles::UiResponse::None
+    }
+}
+
+/// Which entries the JSON and CSV exports write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportScope {
+    All,
+    /// The entries matching the filters of the table.
+    Results,
+}
+
+impl ExportScope {
+    fn label(self) -> &'static str {
+        match self {
+            ExportScope::All => "all entries",
+            ExportScope::Results => "current results",
+        }
+    }
+}
+
+/// Decodes the key prefix typed in the display encoding.
+fn decode_prefix(
+    prefix: &str,
+    key_type: KeyType,
+    encoding: ValueEncoding,
+) -> anyhow::Result<Vec<u8>> {
+    match prefix.is_empty() {
+        true => Ok(Vec::new()),
+        false => key_type.decode(prefix, encoding),
+    }
+}
+
+/// The filters of a table, the table shows the keys they match and the export of its results
+/// goes through all of them rather than the rows shown.
+#[derive(Clone)]
+struct TableFilter {
+    /// The decoded key prefix, the keys of a `MDB_REVERSEKEY` database end with it.
+    prefix: Vec<u8>,
+    filter: String,
+    /// Whether the `filter` is a glob, the keys contain it otherwise.
+    glob: bool,
+    reverse_key: bool,
+    key_type: KeyType,
+    encoding: ValueEncoding,
+}
+
+impl TableFilter {
+    /// Whether the key has the prefix and its text in the display encoding matches the filter.
+    fn matches(&self, key: &[u8]) -> bool {
+        let has_prefix = match self.reverse_key {
+            true => key.ends_with(&self.prefix),
+            false => key.starts_with(&self.prefix),
+        };
+        if !has_prefix || self.filter.is_empty() {
+            return has_prefix;
+        }
+        let key = self.key_type.encode(key, self.encoding);
+        match self.glob {
+            true => glob::matches(&self.filter, &key),
+            false => key.contains(self.filter.as_str()),
+        }
+    }
+
+    /// Returns the matching entries in ascending order, or the results of the regex search
+    /// when it is active, which stays within its scope, in the order of the table.
+    fn entries<'a>(
+        &self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &'a RoTxn,
+        regex_search: &'a mut RegexSearch,
+        reverse: bool,
+        version: (u64, u64, u64),
+    ) -> anyhow::Result<Entries<'a>> {
+        if regex_search.is_active() && regex_search.regex().is_ok() {
+            let matches = regex_search.matches(database, rtxn, reverse, version)?;
+            let matches = matches.iter().map(|(key, data)| Ok((key.as_slice(), data.as_slice())));
+            return Ok(Box::new(matches));
+        }
+
+        let entries =
+            databases::prefix_entries(database, rtxn, &self.prefix, false, self.reverse_key)?;
+        let table_filter = self.clone();
+        Ok(Box::new(entries.filter(move |entry| match entry {
+            Ok((key, _)) => table_filter.matches(key),
+            Err(_) => true,
+        })))
+    }
+}
+
+/// Shows the whole bytes of a key or a value of an expanded row in STFU-8 and optionally
+/// in hex, wrapped, and returns the height they took.
+fn expanded_ui(ui: &mut egui::Ui, bytes: &[u8], hex: bool) -> f32 {
+    let top = ui.cursor().top();
+    let shown = &bytes[..bytes.len().min(EXPANDED_CELL_BYTES)];
+    let mut text = stfu8::encode_u8_pretty(shown);
+    if shown.len() < bytes.len() {
+        text.push_str(&format!("… ({} bytes)", bytes.len()));
+    }
+    ui.add(egui::Label::new(egui::RichText::new(text).monospace()).wrap(true));
+    if hex {
+        let text = egui::RichText::new(hex::encode(shown)).monospace().weak();
+        ui.add(egui::Label::new(text).wrap(true));
+    }
+    ui.cursor().top() - top
+}
+
+/// Puts the entry into its target database, deleting it from `source` when moving it.
+fn copy_entry(
+    op_log: &mut OpLog,
+    wtxn: &mut RwTxn,
+    source: &Database<ByteSlice, ByteSlice>,
+    source_name: &Option<String>,
+    source_dup_sort: bool,
+    copy: &EntryCopy,
+) -> heed::Result<()> {
+    let EntryCopy { key, data, target, moving } = copy;
+    op_log.put(&target.database, &target.name, target.dup_sort, wtxn, key, data)?;
+    if *moving {
+        op_log.delete(source, source_name, source_dup_sort, wtxn, key, data)?;
+    }
+    Ok(())
+}
+
+/// Moves the data of an entry under a new key, in the same txn.
+fn rename_key(
+    op_log: &mut OpLog,
+    wtxn: &mut RwTxn,
+    database: &Database<ByteSlice, ByteSlice>,
+    database_name: &Option<String>,
+    dup_sort: bool,
+    rename: &KeyRename,
+    new_key: &[u8],
+) -> heed::Result<()> {
+    if new_key != rename.old_key {
+        op_log.delete(database, database_name, dup_sort, wtxn, &rename.old_key, &rename.data)?;
+        op_log.put(database, database_name, dup_sort, wtxn, new_key, &rename.data)?;
+    }
+    Ok(())
+}
+
+/// Puts back the data a key has in the `committed` snapshot, or deletes the key when it has
+/// none, through the operation log to undo it like any edit. Only the values that differ
+/// are deleted or put again for a dup-sort database.
+fn revert_key(
+    op_log: &mut OpLog,
+    wtxn: &mut RwTxn,
+    committed: &RoTxn,
+    database: &Database<ByteSlice, ByteSlice>,
+    database_name: &Option<String>,
+    dup_sort: bool,
+    key: &[u8],
+) -> heed::Result<()> {
+    if !dup_sort {
+        return match database.get(committed, key)? {
+            Some(data) => op_log.put(database, database_name, false, wtxn, key, data),
+            None => op_log.delete_key(database, database_name, false, wtxn, key).map(drop),
+        };
+    }
+
+    let values = |txn: &RoTxn| -> heed::Result<Vec<Vec<u8>>> {
+        let mut values = Vec::new();
+        for result in database.prefix_iter(txn, key)? {
+            let (entry_key, data) = result?;
+            if entry_key == key {
+                values.push(data.to_vec());
+            }
+        }
+        Ok(values)
+    };
+    let committed_values = values(committed)?;
+    let current_values = values(wtxn)?;
+    for data in current_values.iter().filter(|data| !committed_values.contains(data)) {
+        op_log.delete(database, database_name, true, wtxn, key, data)?;
+    }
+    for data in committed_values.iter().filter(|data| !current_values.contains(data)) {
+        op_log.put(database, database_name, true, wtxn, key, data)?;
+    }
+    Ok(())
+}
+
+/// Shows `bytes` decoded in a collapsible view when the decoder understands them.
+fn decoded_ui(
+    ui: &mut egui::Ui,
+    tile_id: egui_tiles::TileId,
+    decoder: &dyn ValueDecoder,
+    bytes: &[u8],
+) {
+    let Some(decoded) = decoder.decode(bytes) else {
+        ui.weak(format!("not {}", decoder.name()));
+        return;
+    };
+    egui::CollapsingHeader::new(decoder.name())
+        .id_source((tile_id, "decoded"))
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical().id_source("decoded").max_height(400.0).show(ui, |ui| {
+                ui.monospace(decoded);
+            });
+        });
+}
+
+/// Shows the key and the value in every encoding and as integers side by side, to find out
+/// what format a database uses, each interpretation can be copied.
+fn byte_inspector_ui(ui: &mut egui::Ui, tile_id: egui_tiles::TileId, key: &[u8], data: &[u8]) {
+    /// The number of characters shown of an interpretation, the copy has all of them.
+    const MAX_SHOWN_CHARS: usize = 64;
+
+    egui::CollapsingHeader::new("inspector").id_source((tile_id, "inspector")).show(ui, |ui| {
+        egui::Grid::new((tile_id, "inspector_grid")).striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.strong("key");
+            ui.strong("value");
+            ui.end_row();
+
+            let (key, data) = (interpretations(key), interpretations(data));
+            for ((label, key), (_, data)) in key.into_iter().zip(data) {
+                ui.label(label);
+                for text in [key, data] {
+                    ui.horizontal(|ui| match text {
+                        Some(text) => {
+                            copy_button(ui, &text);
+                            let mut shown: String = text.chars().take(MAX_SHOWN_CHARS).collect();
+                            if shown.len() < text.len() {
+                                shown.push('…');
+                            }
+                            ui.monospace(shown);
+                        }
+                        None => {
+                            ui.weak("—");
+                        }
+                    });
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// Interprets bytes in every encoding and as the integers of their width.
+fn interpretations(bytes: &[u8]) -> Vec<(&'static str, Option<String>)> {
+    let mut interpretations: Vec<_> = ValueEncoding::ALL
+        .into_iter()
+        .map(|encoding| (encoding.label(), Some(encoding.encode(bytes))))
+        .collect();
+    let u32_bytes = <[u8; 4]>::try_from(bytes).ok();
+    let u64_bytes = <[u8; 8]>::try_from(bytes).ok();
+    interpretations.extend([
+        ("u32 LE", u32_bytes.map(|b| u32::from_le_bytes(b).to_string())),
+        ("u32 BE", u32_bytes.map(|b| u32::from_be_bytes(b).to_string())),
+        ("u64 LE", u64_bytes.map(|b| u64::from_le_bytes(b).to_string())),
+        ("u64 BE", u64_bytes.map(|b| u64::from_be_bytes(b).to_string())),
+    ]);
+    interpretations
+}
+
+/// Shows `bytes` as lossy UTF-8 text in a collapsible view with a search box, the matches
+/// of the query are highlighted and the view jumps from one to the other.
+fn find_in_value_ui(
+    ui: &mut egui::Ui,
+    tile_id: egui_tiles::TileId,
+    bytes: &[u8],
+    query: &mut String,
+    current: &mut usize,
+) {
+    egui::CollapsingHeader::new("text").id_source((tile_id, "value_text")).show(ui, |ui| {
+        let text = String::from_utf8_lossy(bytes);
+        let matches: Vec<_> = match query.is_empty() {
+            true => Vec::new(),
+            false => text.match_indices(query.as_str()).map(|(start, _)| start).collect(),
+        };
+
+        let mut jump = false;
+        ui.horizontal(|ui| {
+            let edit = ui.add(egui::TextEdit::singleline(query).hint_text("find in value"));
+            if edit.changed() {
+                *current = 0;
+                jump = true;
+            }
+            let enter = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let has_matches = !matches.is_empty();
+            if ui.add_enabled(has_matches, egui::Button::new("◀")).clicked() {
+                *current = current.checked_sub(1).unwrap_or(matches.len() - 1);
+                jump = true;
+            }
+            if ui.add_enabled(has_matches, egui::Button::new("▶")).clicked() || enter {
+                *current = (*current + 1) % matches.len().max(1);
+                jump = true;
+                if enter {
+                    edit.request_focus();
+                }
+            }
+            *current = (*current).min(matches.len().saturating_sub(1));
+            match matches.len() {
+                0 if !query.is_empty() => ui.label("no matches"),
+                0 => ui.label(""),
+                len => ui.label(format!("{} of {len} matches", *current + 1)),
+            };
+        });
+
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        let text_format = egui::TextFormat::simple(font_id, ui.visuals().text_color());
+        let mut job = egui::text::LayoutJob::default();
+        let mut end = 0;
+        for (i, &start) in matches.iter().enumerate() {
+            job.append(&text[end..start], 0.0, text_format.clone());
+            end = start + query.len();
+            let background =
+                if i == *current { Color32::from_rgb(255, 140, 0) } else { Color32::YELLOW };
+            let format =
+                egui::TextFormat { color: Color32::BLACK, background, ..text_format.clone() };
+            job.append(&text[start..end], 0.0, format);
+        }
+        job.append(&text[end..], 0.0, text_format);
+        job.wrap.max_width = ui.available_width();
+
+        egui::ScrollArea::vertical().id_source("value_text").max_height(300.0).show(ui, |ui| {
+            let galley = ui.fonts(|fonts| fonts.layout_job(job));
+            let response = ui.label(galley.clone());
+            if let (true, Some(&start)) = (jump, matches.get(*current)) {
+                let char_index = text[..start].chars().count();
+                let cursor = galley.from_ccursor(egui::text::CCursor::new(char_index));
+                let rect = galley.pos_from_cursor(&cursor).translate(response.rect.min.to_vec2());
+                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+            }
+        });
+    });
+}
+
+/// Shows `bytes` as a hex dump in its own scroll area, only laying out the visible lines.
+fn hex_dump_ui(ui: &mut egui::Ui, bytes: &[u8]) {
+    ui.label(format!("{} bytes", bytes.len()));
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::vertical().max_height(400.0).show_rows(
+        ui,
+        row_height,
+        hex_dump::num_lines(bytes.len()),
+        |ui, lines| {
+            for line_index in lines {
+                ui.monospace(hex_dump::line(bytes, line_index));
+            }
+        },
+    );
+}
+
+/// The entries of a page of a table.
+type Page<'t> = Vec<(&'t [u8], &'t [u8])>;
+
+/// Shows the pagination controls and returns the entries of the current page, if paginated,
+/// or the error reading them.
+///
+/// The entries of the next page are found by seeking after the last entry of the current one,
+/// which doesn't depend on the number of pages before it. A page that ends in the middle
+/// of the values of a dup-sort key is followed by the remaining ones.
+fn pagination_ui<'t>(
+    ui: &mut egui::Ui,
+    tile_id: egui_tiles::TileId,
+    database: &Database<ByteSlice, ByteSlice>,
+    rtxn: &'t RoTxn,
+    pagination: &mut Option<Pagination>,
+    reverse: bool,
+    num_entries: u64,
+) -> Option<heed::Result<Page<'t>>> {
+    let mut page_size = pagination.as_ref().map(|p| p.page_size);
+    let label = |page_size: Option<usize>| match page_size {
+        Some(page_size) => format!("{page_size} per page"),
+        None => "continuous scroll".to_owned(),
+    };
+
+    let response = ui.horizontal(|ui| {
+        let previous_page_size = page_size;
+        egui::ComboBox::from_id_source((tile_id, "page_size"))
+            .selected_text(label(page_size))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut page_size, None, label(None));
+                for size in Pagination::PAGE_SIZES {
+                    ui.selectable_value(&mut page_size, Some(size), label(Some(size)));
+                }
+            });
+        if page_size != previous_page_size {
+            *pagination = page_size.map(Pagination::new);
+        }
+
+        let pagination = pagination.as_mut()?;
+        // heed excludes a start key by seeking to the next key in byte order, it isn't
+        // the next one in a `MDB_REVERSEKEY` database, so we seek to the rows of the last
+        // key and skip them ourselves up to the last entry, then the next duplicates follow.
+        let last_entry = pagination.last_entries.last().cloned();
+        let entries = match &last_entry {
+            Some((key_start, _)) => key_start.entries(database, rtxn, reverse),
+            None => row_cache::entries_from(database, rtxn, Bound::Unbounded, reverse),
+        };
+        let mut after_last_entry = false;
+        let entries = entries.and_then(|entries| {
+            entries
+                .skip_while(|entry| match (entry, &last_entry) {
+                    (Ok((key, data)), Some((last, last_data)))
+                        if !after_last_entry && *key == last.key =>
+                    {
+                        after_last_entry = data == last_data;
+                        true
+                    }
+                    _ => false,
+                })
+                .take(pagination.page_size)
+                .collect::<heed::Result<Vec<_>>>()
+        });
+        let shown = entries.as_deref().unwrap_or_default();
+
+        let first_row = (pagination.page() * pagination.page_size) as u64;
+        let last_row = first_row + shown.len() as u64;
+
+        if ui.add_enabled(pagination.page() > 0, egui::Button::new("◀")).clicked() {
+            pagination.last_entries.pop();
+            ui.ctx().request_repaint();
+        }
+        ui.label(format!(
+            "page {}, rows {}–{last_row} of {num_entries}",
+            pagination.page() + 1,
+            (first_row + 1).min(last_row),
+        ));
+        let has_next = shown.len() == pagination.page_size && last_row < num_entries;
+        if ui.add_enabled(has_next, egui::Button::new("▶")).clicked() {
+            if let Some(&(key, data)) = shown.last() {
+                // The rows of the key can start on a previous page.
+                let key_before = shown.iter().rev().map(|(key, _)| *key).find(|k| *k != key);
+                let key_before = key_before.or_else(|| match &last_entry {
+                    Some((last, _)) if last.key == key => last.key_before(),
+                    Some((last, _)) => Some(&last.key),
+                    None => None,
+                });
+                let key_start = KeyStart::new(key, key_before, reverse);
+                pagination.last_entries.push((key_start, data.to_vec()));
+            }
+            ui.ctx().request_repaint();
+        }
+
+        Some(entries)
+    });
+
+    response.inner
+}
+
+/// Cuts the text of a cell after `TRUNCATED_CELL_CHARS` characters with an ellipsis,
+/// returns the full text too when it was cut.
+fn truncate_cell(text: String) -> (String, Option<String>) {
+    match text.char_indices().nth(TRUNCATED_CELL_CHARS) {
+        Some((end, _)) => (format!("{}…", &text[..end]), Some(text)),
+        None => (text, None),
+    }
+}
+
+/// The start of a value shown in its cell, cut at a character boundary when it is UTF-8.
+fn cell_preview(data: &[u8]) -> &[u8] {
+    if data.len() <= CELL_PREVIEW_BYTES {
+        return data;
+    }
+    let preview = &data[..CELL_PREVIEW_BYTES];
+    match std::str::from_utf8(preview) {
+        // The last character was cut, the valid UTF-8 stays valid.
+        Err(e) if e.error_len().is_none() => &preview[..e.valid_up_to()],
+        _ => preview,
+    }
+}
+
+/// Shows a small button that copies the bytes of a cell to the clipboard, they are only
+/// encoded when it is clicked.
+fn copy_encoded_button(ui: &mut egui::Ui, bytes: &[u8], encoding: ValueEncoding) {
+    if ui.small_button("📋").on_hover_text("copy").clicked() {
+        ui.output_mut(|o| o.copied_text = encoding.encode(bytes));
+    }
+}
+
+/// Shows a small button that copies the text of a cell to the clipboard.
+fn copy_button(ui: &mut egui::Ui, text: &str) {
+    if ui.small_button("📋").on_hover_text("copy").clicked() {
+        ui.output_mut(|o| o.copied_text = text.to_owned());
+    }
+}
+
+/// Adds a context menu to a cell to copy its bytes in any of the encodings.
+fn copy_context_menu(response: egui::Response, bytes: &[u8]) -> egui::Response {
+    response.context_menu(|ui| {
+        for encoding in ValueEncoding::ALL {
+            if ui.button(format!("copy as {}", encoding.label())).clicked() {
+                ui.output_mut(|o| o.copied_text = encoding.encode(bytes));
+                ui.close_menu();
+            }
+        }
+    })
 }
@@ -0,0 +1,109 @@
+use std::ops::Bound;
+
+use heed::types::ByteSlice;
+use heed::{Database, RoTxn};
+
+use crate::databases::{self, Entries};
+use crate::row_cache;
+
+/// How the keys are matched, in the display encoding of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchKind {
+    #[default]
+    Prefix,
+    Substring,
+    Glob,
+}
+
+impl MatchKind {
+    pub const ALL: [MatchKind; 3] = [MatchKind::Prefix, MatchKind::Substring, MatchKind::Glob];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchKind::Prefix => "key prefix",
+            MatchKind::Substring => "key contains",
+            MatchKind::Glob => "key glob",
+        }
+    }
+}
+
+/// The pattern of the count tool of a table and its count, when its window is open.
+#[derive(Debug, Default)]
+pub struct CountTool {
+    pub pattern: String,
+    pub kind: MatchKind,
+    pub count: Option<MatchCount>,
+}
+
+/// Counts the entries whose key matches and sums the bytes of their values, a bounded number
+/// of entries per frame like `GlobScan`, without keeping the entries.
+#[derive(Debug)]
+pub struct MatchCount {
+    /// What the entries were counted from, see `TreeBehavior::entries_version`.
+    pub version: (u64, u64, u64),
+    /// The bytes all the matching keys start with, the scan seeks to them and stops after them.
+    seek: Vec<u8>,
+    /// The last scanned key and the number of its entries scanned so far.
+    resume: Option<(Vec<u8>, usize)>,
+    pub scanned: u64,
+    pub matches: u64,
+    pub value_bytes: u64,
+    /// Whether all the entries that could match were scanned, or the user stopped the count.
+    pub done: bool,
+    pub cancelled: bool,
+}
+
+impl MatchCount {
+    pub fn new(version: (u64, u64, u64), seek: Vec<u8>) -> MatchCount {
+        MatchCount {
+            version,
+            seek,
+            resume: None,
+            scanned: 0,
+            matches: 0,
+            value_bytes: 0,
+            done: false,
+            cancelled: false,
+        }
+    }
+
+    /// Scans up to `budget` more entries.
+    pub fn step(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &RoTxn,
+        budget: usize,
+        is_match: impl Fn(&[u8]) -> bool,
+    ) -> heed::Result<()> {
+        let (entries, skip): (Entries, usize) = match &self.resume {
+            Some((key, scanned)) => {
+                let start = Bound::Included(key.as_slice());
+                (Box::new(row_cache::entries_from(database, rtxn, start, false)?), *scanned)
+            }
+            None => (databases::prefix_entries(database, rtxn, &self.seek, false, false)?, 0),
+        };
+
+        let mut budget = budget;
+        for entry in entries.skip(skip) {
+            if budget == 0 {
+                return Ok(());
+            }
+            let (key, data) = entry?;
+            if !key.starts_with(&self.seek) {
+                break;
+            }
+            self.resume = match self.resume.take() {
+                Some((last, scanned)) if last == key => Some((last, scanned + 1)),
+                _ => Some((key.to_vec(), 1)),
+            };
+            if is_match(key) {
+                self.matches += 1;
+                self.value_bytes += data.len() as u64;
+            }
+            self.scanned += 1;
+            budget -= 1;
+        }
+        self.done = true;
+        Ok(())
+    }
+}
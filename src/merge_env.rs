@@ -0,0 +1,174 @@
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+
+use heed::types::ByteSlice;
+use heed::{Database, Env, RoTxn, RwTxn};
+
+use crate::databases;
+use crate::import::{ImportPreview, ImportSummary};
+use crate::open_env::{self, SyncFlags, DEFAULT_MAP_SIZE};
+use crate::row_cache;
+
+/// Copies the entries of a database of another environment into a database of the
+/// edited one, a chunk per call to keep the UI responsive.
+pub struct EnvMerge {
+    /// The other environment, opened read-only until the merge is closed.
+    source_env: Env,
+    pub path: PathBuf,
+    /// The databases of the other environment, the unnamed one first.
+    pub databases: Vec<Option<String>>,
+    /// The index of the database copied from.
+    pub source: usize,
+    /// The copy, once started.
+    pub progress: Option<MergeProgress>,
+    /// The dry run of the copy, once started.
+    pub dry_run: Option<MergeDryRun>,
+}
+
+/// Counts what the copy would do to the keys of the target, a chunk per call like the copy.
+pub struct MergeDryRun {
+    /// The index of the source database counted from.
+    pub source: usize,
+    pub total: u64,
+    /// The last counted key and the number of its entries counted.
+    resume: Option<(Vec<u8>, usize)>,
+    pub counted: u64,
+    pub preview: ImportPreview,
+    pub done: bool,
+}
+
+pub struct MergeProgress {
+    /// The number of entries of the source database when the copy started.
+    pub total: u64,
+    /// The last copied key, the number of its entries copied, like `GlobScan`, and
+    /// whether they are put, all the duplicates of a key are skipped or put together.
+    resume: Option<(Vec<u8>, usize, bool)>,
+    pub summary: ImportSummary,
+    /// Whether all the entries were copied.
+    pub done: bool,
+}
+
+impl EnvMerge {
+    /// Opens the environment at `path` read-only to copy from it.
+    pub fn open(path: &Path) -> anyhow::Result<EnvMerge> {
+        open_env::check_env_dir(path)?;
+        // LMDB maps the whole data file whatever the map size of a read-only environment.
+        let source_env = open_env::open_env(path, DEFAULT_MAP_SIZE, true, SyncFlags::default())?;
+        let rtxn = source_env.read_txn()?;
+        let names = databases::named_databases(&source_env, &rtxn)?;
+        drop(rtxn);
+        let databases = std::iter::once(None).chain(names.into_iter().map(Some)).collect();
+        Ok(EnvMerge {
+            source_env,
+            path: path.to_owned(),
+            databases,
+            source: 0,
+            progress: None,
+            dry_run: None,
+        })
+    }
+
+    fn source_database(&self) -> heed::Result<Database<ByteSlice, ByteSlice>> {
+        let rtxn = self.source_env.read_txn()?;
+        let name = self.databases[self.source].as_deref();
+        let database = self.source_env.open_database(&rtxn, name)?;
+        database.ok_or_else(|| heed::Error::Mdb(heed::MdbError::NotFound))
+    }
+
+    /// Starts copying from the chosen database.
+    pub fn start(&mut self) -> heed::Result<()> {
+        let database = self.source_database()?;
+        let total = database.len(&self.source_env.read_txn()?)?;
+        let summary = ImportSummary::default();
+        self.progress = Some(MergeProgress { total, resume: None, summary, done: false });
+        Ok(())
+    }
+
+    /// Starts counting what copying from the chosen database would do.
+    pub fn start_dry_run(&mut self) -> heed::Result<()> {
+        let database = self.source_database()?;
+        let total = database.len(&self.source_env.read_txn()?)?;
+        self.dry_run = Some(MergeDryRun {
+            source: self.source,
+            total,
+            resume: None,
+            counted: 0,
+            preview: ImportPreview::default(),
+            done: false,
+        });
+        Ok(())
+    }
+
+    /// Counts up to `max` more entries of the dry run against the `target`.
+    pub fn dry_run_step(
+        &mut self,
+        target: &Database<ByteSlice, ByteSlice>,
+        target_rtxn: &RoTxn,
+        max: u64,
+    ) -> heed::Result<()> {
+        let source = self.source_database()?;
+        let rtxn = self.source_env.read_txn()?;
+        let Some(dry_run) = &mut self.dry_run else { return Ok(()) };
+        let (start, skip) = match &dry_run.resume {
+            Some((key, counted)) => (Bound::Included(key.as_slice()), *counted),
+            None => (Bound::Unbounded, 0),
+        };
+
+        let mut budget = max;
+        for entry in row_cache::entries_from(&source, &rtxn, start, false)?.skip(skip) {
+            if budget == 0 {
+                return Ok(());
+            }
+            let (key, data) = entry?;
+            dry_run.preview.count(target.get(target_rtxn, key)?, data);
+            dry_run.resume = match dry_run.resume.take() {
+                Some((last, counted)) if last == key => Some((last, counted + 1)),
+                _ => Some((key.to_vec(), 1)),
+            };
+            dry_run.counted += 1;
+            budget -= 1;
+        }
+        dry_run.done = true;
+        Ok(())
+    }
+
+    /// Copies up to `max` more entries into the `target`, the existing keys are skipped
+    /// unless `overwrite` is set. A duplicate is added to a dup-sort target instead.
+    pub fn step(
+        &mut self,
+        target: &Database<ByteSlice, ByteSlice>,
+        wtxn: &mut RwTxn,
+        overwrite: bool,
+        max: u64,
+    ) -> heed::Result<()> {
+        let source = self.source_database()?;
+        let rtxn = self.source_env.read_txn()?;
+        let Some(progress) = &mut self.progress else { return Ok(()) };
+        let (start, skip) = match &progress.resume {
+            Some((key, copied, _)) => (Bound::Included(key.as_slice()), *copied),
+            None => (Bound::Unbounded, 0),
+        };
+
+        let mut budget = max;
+        for entry in row_cache::entries_from(&source, &rtxn, start, false)?.skip(skip) {
+            if budget == 0 {
+                return Ok(());
+            }
+            let (key, data) = entry?;
+            let (last, copied, put) = match progress.resume.take() {
+                Some((last, copied, put)) if last == key => (last, copied + 1, put),
+                _ => (key.to_vec(), 1, overwrite || target.get(wtxn, key)?.is_none()),
+            };
+            if put {
+                target.put(wtxn, key, data)?;
+                progress.summary.inserted += 1;
+            } else {
+                progress.summary.skipped += 1;
+            }
+            progress.resume = Some((last, copied, put));
+            budget -= 1;
+        }
+        progress.done = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::{HashMap, HashSet};
+
+use heed::types::ByteSlice;
+use heed::{Database, Env, RwTxn};
+
+use crate::databases;
+
+/// A write operation done in the current write txn, with what is needed to revert it.
+#[derive(Clone)]
+pub enum Op {
+    Put {
+        database: Database<ByteSlice, ByteSlice>,
+        /// The name of the database, to open it again in a reopened environment.
+        database_name: Option<String>,
+        dup_sort: bool,
+        key: Vec<u8>,
+        /// The data the key had before, a dup-sort database keeps it along the new one.
+        old: Option<Vec<u8>>,
+        new: Vec<u8>,
+    },
+    Delete {
+        database: Database<ByteSlice, ByteSlice>,
+        database_name: Option<String>,
+        dup_sort: bool,
+        key: Vec<u8>,
+        old: Vec<u8>,
+    },
+}
+
+impl Op {
+    /// Returns the operation that reverts this one.
+    fn inverse(self) -> Op {
+        match self {
+            Op::Put { database, database_name, dup_sort, key, old: None, new } => {
+                Op::Delete { database, database_name, dup_sort, key, old: new }
+            }
+            Op::Put { database, database_name, dup_sort, key, old: Some(old), new } => {
+                Op::Put { database, database_name, dup_sort, key, old: Some(new), new: old }
+            }
+            Op::Delete { database, database_name, dup_sort, key, old } => {
+                Op::Put { database, database_name, dup_sort, key, old: None, new: old }
+            }
+        }
+    }
+
+    fn database_name(&self) -> &Option<String> {
+        match self {
+            Op::Put { database_name, .. } | Op::Delete { database_name, .. } => database_name,
+        }
+    }
+
+    /// The same operation on the database of the same name in another environment.
+    fn with_database(mut self, other: Database<ByteSlice, ByteSlice>) -> Op {
+        match &mut self {
+            Op::Put { database, .. } | Op::Delete { database, .. } => *database = other,
+        }
+        self
+    }
+
+    fn apply(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        match self {
+            Op::Put { database, key, new, .. } => database.put(wtxn, key, new),
+            Op::Delete { database, dup_sort: true, key, old, .. } => {
+                databases::delete_duplicate(database, wtxn, key, old).map(drop)
+            }
+            Op::Delete { database, dup_sort: false, key, .. } => {
+                database.delete(wtxn, key).map(drop)
+            }
+        }
+    }
+}
+
+/// The number of entries inserted and deleted in the current write txn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Changes {
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.insertions == 0 && self.deletions == 0
+    }
+
+    fn count(&mut self, op: &Op) {
+        match op {
+            Op::Put { .. } => self.insertions += 1,
+            Op::Delete { .. } => self.deletions += 1,
+        }
+    }
+}
+
+/// The operations done in the current write txn that can be undone and redone,
+/// the bulk operations like imports and clears are only counted and forget the log.
+#[derive(Default, Clone)]
+pub struct OpLog {
+    undo: Vec<Op>,
+    redo: Vec<Op>,
+    changes: Changes,
+    /// Whether a bulk operation forgot the operations before it, they can't be done again.
+    bulk: bool,
+}
+
+impl OpLog {
+    /// Puts an entry and records it, a dup-sort database adds the data to the key. Nothing is
+    /// recorded when the dup-sort database already has the pair, undoing would delete it.
+    pub fn put(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        database_name: &Option<String>,
+        dup_sort: bool,
+        wtxn: &mut RwTxn,
+        key: &[u8],
+        data: &[u8],
+    ) -> heed::Result<()> {
+        let old = match dup_sort {
+            true if databases::has_duplicate(database, wtxn, key, data)? => return Ok(()),
+            true => None,
+            false => database.get(wtxn, key)?.map(<[u8]>::to_vec),
+        };
+        database.put(wtxn, key, data)?;
+        self.record(Op::Put {
+            database: *database,
+            database_name: database_name.clone(),
+            dup_sort,
+            key: key.to_vec(),
+            old,
+            new: data.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Deletes a key and records it, only the given `data` of the key is deleted from
+    /// a dup-sort database, the `data` is ignored for the other databases.
+    pub fn delete(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        database_name: &Option<String>,
+        dup_sort: bool,
+        wtxn: &mut RwTxn,
+        key: &[u8],
+        data: &[u8],
+    ) -> heed::Result<bool> {
+        let old = if dup_sort {
+            databases::delete_duplicate(database, wtxn, key, data)?.then(|| data.to_vec())
+        } else {
+            let old = database.get(wtxn, key)?.map(<[u8]>::to_vec);
+            database.delete(wtxn, key)?;
+            old
+        };
+
+        let deleted = old.is_some();
+        if let Some(old) = old {
+            let database_name = database_name.clone();
+            let key = key.to_vec();
+            self.record(Op::Delete { database: *database, database_name, dup_sort, key, old });
+        }
+        Ok(deleted)
+    }
+
+    /// Deletes all the data of a key and records it, value by value for a dup-sort database.
+    pub fn delete_key(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        database_name: &Option<String>,
+        dup_sort: bool,
+        wtxn: &mut RwTxn,
+        key: &[u8],
+    ) -> heed::Result<bool> {
+        if !dup_sort {
+            return self.delete(database, database_name, false, wtxn, key, &[]);
+        }
+
+        let mut values = Vec::new();
+        for result in database.prefix_iter(wtxn, key)? {
+            let (entry_key, data) = result?;
+            if entry_key == key {
+                values.push(data.to_vec());
+            }
+        }
+        for data in &values {
+            self.delete(database, database_name, true, wtxn, key, data)?;
+        }
+        Ok(!values.is_empty())
+    }
+
+    fn record(&mut self, op: Op) {
+        self.changes.count(&op);
+        self.undo.push(op);
+        self.redo.clear();
+    }
+
+    /// Counts the entries written by a bulk operation, the operations before it can't be
+    /// undone anymore as the bulk operation could have changed what they wrote.
+    pub fn record_bulk(&mut self, insertions: u64, deletions: u64) {
+        self.undo.clear();
+        self.redo.clear();
+        self.bulk = true;
+        self.changes.insertions += insertions;
+        self.changes.deletions += deletions;
+    }
+
+    pub fn changes(&self) -> Changes {
+        self.changes
+    }
+
+    /// Reverts the last operation, returns whether there was one.
+    pub fn undo(&mut self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.replay(wtxn, true)
+    }
+
+    /// Does the last undone operation again, returns whether there was one.
+    pub fn redo(&mut self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.replay(wtxn, false)
+    }
+
+    /// Applies the inverse of the last operation of a log and moves it to the other one.
+    fn replay(&mut self, wtxn: &mut RwTxn, undoing: bool) -> heed::Result<bool> {
+        let (from, to) = if undoing {
+            (&mut self.undo, &mut self.redo)
+        } else {
+            (&mut self.redo, &mut self.undo)
+        };
+        let Some(op) = from.pop() else { return Ok(false) };
+
+        let inverse = op.inverse();
+        if let Err(e) = inverse.apply(wtxn) {
+            from.push(inverse.inverse());
+            return Err(e);
+        }
+        self.changes.count(&inverse);
+        to.push(inverse);
+        Ok(true)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets the operations and their count, once the write txn is committed or aborted.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.changes = Changes::default();
+        self.bulk = false;
+    }
+
+    /// Does the operations again in a write txn of the environment reopened after the txn
+    /// they were done in was lost, and returns their log in the new txn. The databases are
+    /// opened again by name, or created again when they were created in the lost txn.
+    /// Nothing is done when a bulk operation forgot the operations before it.
+    pub fn reapply(&self, env: &Env, wtxn: &mut RwTxn) -> heed::Result<Option<OpLog>> {
+        if self.bulk {
+            return Ok(None);
+        }
+        let mut databases = HashMap::new();
+        let mut reopen = |wtxn: &mut RwTxn, op: &Op| -> heed::Result<Op> {
+            let name = op.database_name();
+            let database = match databases.get(name) {
+                Some(database) => *database,
+                None => {
+                    let database = match env.open_database(wtxn, name.as_deref())? {
+                        Some(database) => database,
+                        None => env.create_database(wtxn, name.as_deref())?,
+                    };
+                    *databases.entry(name.clone()).or_insert(database)
+                }
+            };
+            Ok(op.clone().with_database(database))
+        };
+
+        let mut undo = Vec::with_capacity(self.undo.len());
+        for op in &self.undo {
+            let op = reopen(wtxn, op)?;
+            op.apply(wtxn)?;
+            undo.push(op);
+        }
+        let redo = self.redo.iter().map(|op| reopen(wtxn, op)).collect::<heed::Result<_>>()?;
+        Ok(Some(OpLog { undo, redo, changes: self.changes, bulk: false }))
+    }
+}
+
+/// The keys put or deleted in the current write txn, by database name, to show which
+/// entries are part of it. The bulk operations don't mark their keys.
+#[derive(Debug, Default, Clone)]
+pub struct DirtyKeys(HashMap<Option<String>, HashSet<Vec<u8>>>);
+
+impl DirtyKeys {
+    pub fn insert(&mut self, database_name: &Option<String>, key: &[u8]) {
+        self.0.entry(database_name.clone()).or_default().insert(key.to_vec());
+    }
+
+    /// Forgets a key that was written back as it was before the write txn.
+    pub fn remove(&mut self, database_name: &Option<String>, key: &[u8]) {
+        if let Some(keys) = self.0.get_mut(database_name) {
+            keys.remove(key);
+        }
+    }
+
+    /// The keys put or deleted in the write txn, with the name of their database.
+    pub fn iter(&self) -> impl Iterator<Item = (&Option<String>, &Vec<u8>)> {
+        self.0.iter().flat_map(|(name, keys)| keys.iter().map(move |key| (name, key)))
+    }
+
+    /// The keys of a database put or deleted in the write txn, if any.
+    pub fn of(&self, database_name: &Option<String>) -> Option<&HashSet<Vec<u8>>> {
+        self.0.get(database_name)
+    }
+
+    /// Forgets the keys, once the write txn is committed or aborted.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heed::types::ByteSlice;
+    use heed::{Database, RoTxn};
+
+    use super::{Changes, OpLog};
+    use crate::test_env::TestEnv;
+
+    /// The values of a key, in order.
+    fn values(database: &Database<ByteSlice, ByteSlice>, txn: &RoTxn, key: &str) -> Vec<String> {
+        let entries = database.prefix_iter(txn, key.as_bytes()).unwrap().map(Result::unwrap);
+        let values = entries.filter(|(entry_key, _)| *entry_key == key.as_bytes());
+        values.map(|(_, data)| String::from_utf8(data.to_vec()).unwrap()).collect()
+    }
+
+    #[test]
+    fn undoes_and_redoes_the_operations_in_order() {
+        let env = TestEnv::new("op-log");
+        let (name, database) = (None, env.database(None, &[("a", "1")]));
+        let mut wtxn = env.write_txn().unwrap();
+        let mut op_log = OpLog::default();
+        op_log.put(&database, &name, false, &mut wtxn, b"a", b"2").unwrap();
+        op_log.put(&database, &name, false, &mut wtxn, b"b", b"3").unwrap();
+        assert!(op_log.delete(&database, &name, false, &mut wtxn, b"a", &[]).unwrap());
+        assert_eq!(op_log.changes(), Changes { insertions: 2, deletions: 1 });
+
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["2"]);
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "b"), [""; 0]);
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["1"]);
+        assert!(!op_log.undo(&mut wtxn).unwrap());
+
+        assert!(op_log.redo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["2"]);
+        assert!(op_log.can_redo());
+        // A new operation forgets the undone ones.
+        op_log.put(&database, &name, false, &mut wtxn, b"c", b"4").unwrap();
+        assert!(!op_log.can_redo());
+    }
+
+    #[test]
+    fn undoing_a_put_of_an_existing_dup_sort_pair_keeps_it() {
+        let env = TestEnv::with_dup_sort("op-log-dups", "dups", &[("a", "1"), ("a", "2")]);
+        let (name, database) = (Some("dups".to_owned()), env.dup_sort_database("dups"));
+        let mut wtxn = env.write_txn().unwrap();
+        let mut op_log = OpLog::default();
+
+        op_log.put(&database, &name, true, &mut wtxn, b"a", b"1").unwrap();
+        assert!(!op_log.can_undo());
+        assert!(!op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["1", "2"]);
+
+        op_log.put(&database, &name, true, &mut wtxn, b"a", b"3").unwrap();
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["1", "2"]);
+
+        assert!(op_log.delete(&database, &name, true, &mut wtxn, b"a", b"1").unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["2"]);
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["1", "2"]);
+
+        assert!(op_log.delete_key(&database, &name, true, &mut wtxn, b"a").unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), [""; 0]);
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["1", "2"]);
+    }
+
+    #[test]
+    fn reapplies_the_operations_of_a_lost_txn_by_database_name() {
+        let lost = TestEnv::new("op-log-lost");
+        let (name, database) = (Some("db".to_owned()), lost.database(Some("db"), &[("a", "1")]));
+        let mut op_log = OpLog::default();
+        let mut wtxn = lost.write_txn().unwrap();
+        op_log.put(&database, &name, false, &mut wtxn, b"b", b"2").unwrap();
+        op_log.delete(&database, &name, false, &mut wtxn, b"a", &[]).unwrap();
+        op_log.put(&database, &name, false, &mut wtxn, b"c", b"3").unwrap();
+        op_log.undo(&mut wtxn).unwrap();
+        wtxn.abort();
+
+        let env = TestEnv::new("op-log-reapplied");
+        env.database(Some("db"), &[("a", "1")]);
+        let mut wtxn = env.write_txn().unwrap();
+        let mut op_log = op_log.reapply(&env, &mut wtxn).unwrap().unwrap();
+        let database = env.open_database(&wtxn, Some("db")).unwrap().unwrap();
+        assert_eq!(values(&database, &wtxn, "a"), [""; 0]);
+        assert_eq!(values(&database, &wtxn, "b"), ["2"]);
+        assert_eq!(values(&database, &wtxn, "c"), [""; 0]);
+        assert_eq!(op_log.changes(), Changes { insertions: 2, deletions: 2 });
+
+        assert!(op_log.redo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "c"), ["3"]);
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert!(op_log.undo(&mut wtxn).unwrap());
+        assert_eq!(values(&database, &wtxn, "a"), ["1"]);
+    }
+
+    #[test]
+    fn reapplies_nothing_after_a_bulk_operation() {
+        let env = TestEnv::new("op-log-bulk");
+        let (name, database) = (Some("db".to_owned()), env.database(Some("db"), &[]));
+        let mut op_log = OpLog::default();
+        let mut wtxn = env.write_txn().unwrap();
+        op_log.put(&database, &name, false, &mut wtxn, b"a", b"1").unwrap();
+        op_log.record_bulk(10, 0);
+        assert!(op_log.reapply(&env, &mut wtxn).unwrap().is_none());
+        op_log.clear();
+        assert!(op_log.reapply(&env, &mut wtxn).unwrap().is_some());
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+use egui::Color32;
+use heed::{Env, EnvOpenOptions, Flags};
+
+/// The key under which the last used map size is persisted.
+pub const MAP_SIZE_KEY: &str = "map_size";
+
+/// The maximum number of named databases the environments are opened with.
+pub const MAX_DBS: u32 = 1000;
+
+/// The map size LMDB uses when none is specified.
+pub const DEFAULT_MAP_SIZE: usize = 10 * MIB;
+
+pub const MIB: usize = 1024 * 1024;
+const GIB: usize = 1024 * MIB;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeUnit {
+    MiB,
+    GiB,
+}
+
+impl SizeUnit {
+    fn bytes(self) -> usize {
+        match self {
+            SizeUnit::MiB => MIB,
+            SizeUnit::GiB => GIB,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SizeUnit::MiB => "MiB",
+            SizeUnit::GiB => "GiB",
+        }
+    }
+}
+
+/// The flags trading the durability of the commits for their speed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncFlags {
+    /// `MDB_NOSYNC`, the commits don't flush the data to the disk.
+    pub no_sync: bool,
+    /// `MDB_NOMETASYNC`, the commits flush the data but not the meta page.
+    pub no_meta_sync: bool,
+}
+
+impl SyncFlags {
+    /// Whether the last commits can be lost on a crash, until the environment is synced.
+    pub fn is_reduced(self) -> bool {
+        self.no_sync || self.no_meta_sync
+    }
+
+    /// The names of the flags set, e.g. to show them with the other flags.
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        [(self.no_sync, "MDB_NOSYNC"), (self.no_meta_sync, "MDB_NOMETASYNC")]
+            .into_iter()
+            .filter_map(|(set, name)| set.then_some(name))
+    }
+}
+
+/// Lets the user choose how to open the environment before editing it.
+pub struct OpenEnvDialog {
+    env_path: PathBuf,
+    map_size: usize,
+    unit: SizeUnit,
+    read_only: bool,
+    sync: SyncFlags,
+    /// Why the environment couldn't be opened, and its likely cause when LMDB tells.
+    error: Option<(String, Option<Diagnosis>)>,
+}
+
+/// The likely cause of an error opening an environment and what to try instead.
+struct Diagnosis {
+    cause: String,
+    /// Whether it could be opened read-only instead.
+    retry_read_only: bool,
+}
+
+/// An environment opened by the dialog with the options the user chose.
+pub struct OpenedEnv {
+    pub env: Env,
+    /// The map size in bytes.
+    pub map_size: usize,
+    /// Whether the environment was opened with `MDB_RDONLY` and can't be written to.
+    pub read_only: bool,
+    pub sync: SyncFlags,
+}
+
+impl OpenEnvDialog {
+    /// Creates a dialog to open the environment at `env_path`, `map_size` is in bytes.
+    pub fn new(env_path: PathBuf, map_size: usize) -> OpenEnvDialog {
+        let unit = if map_size.is_multiple_of(GIB) { SizeUnit::GiB } else { SizeUnit::MiB };
+        let map_size = (map_size / unit.bytes()).max(1);
+        OpenEnvDialog {
+            env_path,
+            map_size,
+            unit,
+            read_only: false,
+            sync: SyncFlags::default(),
+            error: None,
+        }
+    }
+
+    /// Shows an error that happened after the environment was opened, like opening it did.
+    pub fn show_error(&mut self, error: String) {
+        self.error = Some((error, None));
+    }
+
+    /// Shows the dialog and returns the opened environment once the user validated it.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<OpenedEnv> {
+        ui.heading("Open an environment");
+        ui.label(format!("path: {}", self.env_path.display()));
+
+        ui.horizontal(|ui| {
+            ui.label("map size");
+            ui.add(egui::DragValue::new(&mut self.map_size).clamp_range(1..=usize::MAX));
+            egui::ComboBox::from_id_source("map_size_unit")
+                .selected_text(self.unit.label())
+                .show_ui(ui, |ui| {
+                    for unit in [SizeUnit::MiB, SizeUnit::GiB] {
+                        ui.selectable_value(&mut self.unit, unit, unit.label());
+                    }
+                });
+        });
+
+        ui.checkbox(&mut self.read_only, "read-only")
+            .on_hover_text("prevents any write, other processes can keep writing to it");
+
+        ui.add_enabled_ui(!self.read_only, |ui| {
+            ui.checkbox(&mut self.sync.no_sync, "don't sync the commits (MDB_NOSYNC)")
+                .on_hover_text("the commits don't wait for the data to be written to the disk");
+            ui.checkbox(&mut self.sync.no_meta_sync, "don't sync the meta page (MDB_NOMETASYNC)")
+                .on_hover_text("the commits write the data but not the page pointing to it");
+        });
+        if self.sync.is_reduced() && !self.read_only {
+            ui.colored_label(
+                Color32::YELLOW,
+                "⚠ much faster for large imports, but a crash of the system can lose the last \
+                commits, or with MDB_NOSYNC corrupt the environment, until it is synced to disk",
+            );
+        }
+
+        let mut open = ui.button("open").clicked();
+
+        if let Some((error, diagnosis)) = &self.error {
+            ui.colored_label(Color32::RED, error);
+            if let Some(diagnosis) = diagnosis {
+                ui.label(&diagnosis.cause);
+                if diagnosis.retry_read_only && !self.read_only {
+                    let retry = ui.button("retry read-only").on_hover_text(
+                        "nothing can be written, the intact data can still be read and exported",
+                    );
+                    if retry.clicked() {
+                        self.read_only = true;
+                        open = true;
+                    }
+                }
+            }
+        }
+
+        let mut opened = None;
+        if open {
+            match self.open() {
+                Ok(env) => opened = Some(env),
+                Err(e) => self.error = Some(e),
+            }
+        }
+        opened
+    }
+
+    fn open(&self) -> Result<OpenedEnv, (String, Option<Diagnosis>)> {
+        let map_size = self
+            .map_size
+            .checked_mul(self.unit.bytes())
+            .ok_or_else(|| ("this map size is too big for this platform".to_owned(), None))?;
+
+        // LMDB can't shrink the map below what the data file already uses.
+        let used_size = used_size(&self.env_path);
+        if (map_size as u64) < used_size {
+            let error = format!(
+                "the environment already uses {} MiB, the map size can't be lower than that",
+                used_size.div_ceil(MIB as u64)
+            );
+            return Err((error, None));
+        }
+
+        // The sync flags are meaningless without writes.
+        let sync = if self.read_only { SyncFlags::default() } else { self.sync };
+        open_env(&self.env_path, map_size, self.read_only, sync)
+            .map(|env| OpenedEnv { env, map_size, read_only: self.read_only, sync })
+            .map_err(|e| (format!("could not open the environment: {e}"), diagnose(&e)))
+    }
+}
+
+/// Explains the errors opening an environment that are about its files rather than the
+/// options, a corrupted or foreign environment gives cryptic LMDB errors.
+fn diagnose(error: &heed::Error) -> Option<Diagnosis> {
+    let (cause, retry_read_only) = match error {
+        heed::Error::Mdb(heed::MdbError::VersionMismatch) => (
+            "The environment was written by another major version of LMDB than the one of \
+            this editor. Dump it with the mdb_dump of the LMDB that wrote it and load the dump \
+            into a new environment with mdb_load."
+                .to_owned(),
+            false,
+        ),
+        heed::Error::Mdb(heed::MdbError::Invalid) => (
+            format!(
+                "The data.mdb file isn't an LMDB environment or its meta pages are damaged: it \
+                could be truncated, be another kind of file, or have been written on a system \
+                with another page size than {} bytes or another word size.",
+                page_size::get()
+            ),
+            false,
+        ),
+        heed::Error::Mdb(
+            heed::MdbError::Corrupted | heed::MdbError::PageNotFound | heed::MdbError::Panic,
+        ) => (
+            "The environment is corrupted, the data file may have been truncated or a crash \
+            happened while it was written without syncing. Opening it read-only may still \
+            read what is intact, to back it up or export it."
+                .to_owned(),
+            true,
+        ),
+        heed::Error::Mdb(heed::MdbError::ReadersFull) => (
+            "The lock file is full of readers, likely left by processes that were killed. \
+            mdb_stat -rr clears the stale readers."
+                .to_owned(),
+            false,
+        ),
+        heed::Error::Io(e) if e.kind() == std::io::ErrorKind::PermissionDenied => (
+            "The files of the environment can't be written, LMDB writes to lock.mdb even to \
+            read. Opening it read-only doesn't write data.mdb, or copy the environment to a \
+            folder you can write to."
+                .to_owned(),
+            true,
+        ),
+        _ => return None,
+    };
+    Some(Diagnosis { cause, retry_read_only })
+}
+
+/// Opens the environment at `env_path` with a map size in bytes.
+pub fn open_env(
+    env_path: &Path,
+    map_size: usize,
+    read_only: bool,
+    sync: SyncFlags,
+) -> heed::Result<Env> {
+    let mut options = EnvOpenOptions::new();
+    options.max_dbs(MAX_DBS).map_size(map_size);
+    if read_only {
+        // Safety: MDB_RDONLY doesn't change how the memory map is shared.
+        unsafe { options.flag(Flags::MdbRdOnly) };
+    }
+    // Safety: neither flag changes how the memory map is shared, only when it is flushed.
+    if sync.no_sync {
+        unsafe { options.flag(Flags::MdbNoSync) };
+    }
+    if sync.no_meta_sync {
+        unsafe { options.flag(Flags::MdbNoMetaSync) };
+    }
+    options.open(env_path)
+}
+
+/// Checks that a folder given on the command line is an environment, LMDB would
+/// create one in any folder.
+pub fn check_env_dir(env_path: &Path) -> anyhow::Result<()> {
+    if !env_path.is_dir() {
+        anyhow::bail!("{} is not a folder", env_path.display());
+    }
+    if !env_path.join("data.mdb").is_file() {
+        anyhow::bail!("{} is not an LMDB environment, it has no data.mdb file", env_path.display());
+    }
+    Ok(())
+}
+
+/// Returns the size of the data file of the environment, zero if it doesn't exist yet.
+fn used_size(env_path: &Path) -> u64 {
+    fs::metadata(env_path.join("data.mdb")).map_or(0, |metadata| metadata.len())
+}
@@ -0,0 +1,101 @@
+/// The number of rows whose keys are read at once when looking for the bounds of a group.
+const BLOCK_ROWS: usize = 256;
+
+/// The first segment of a key, up to and including the first `delimiter`, the keys
+/// without it are their own group.
+fn segment<'a>(key: &'a [u8], delimiter: &[u8]) -> &'a [u8] {
+    key.windows(delimiter.len())
+        .position(|window| window == delimiter)
+        .map_or(key, |start| &key[..start + delimiter.len()])
+}
+
+/// Returns the first row after the group of the `row`, `None` when it is in the last group.
+///
+/// `keys(start, len)` returns the keys of the `len` rows from `start`, fewer at the end.
+pub fn next_group_row(
+    row: usize,
+    delimiter: &[u8],
+    mut keys: impl FnMut(usize, usize) -> heed::Result<Vec<Vec<u8>>>,
+) -> heed::Result<Option<usize>> {
+    let mut group = None;
+    let mut start = row;
+    loop {
+        let block = keys(start, BLOCK_ROWS)?;
+        for (i, key) in block.iter().enumerate() {
+            let segment = segment(key, delimiter);
+            match &group {
+                None => group = Some(segment.to_vec()),
+                Some(group) if group.as_slice() != segment => return Ok(Some(start + i)),
+                Some(_) => (),
+            }
+        }
+        if block.len() < BLOCK_ROWS {
+            return Ok(None);
+        }
+        start += BLOCK_ROWS;
+    }
+}
+
+/// Returns the first row of the group before the group of the `row`, `None` when it is
+/// in the first group. The rows are read backward a block at a time.
+pub fn previous_group_row(
+    row: usize,
+    delimiter: &[u8],
+    mut keys: impl FnMut(usize, usize) -> heed::Result<Vec<Vec<u8>>>,
+) -> heed::Result<Option<usize>> {
+    let Some(current) = keys(row, 1)?.pop() else { return Ok(None) };
+    let current = segment(&current, delimiter).to_vec();
+    // The segment of the previous group once its last row is found.
+    let mut previous: Option<Vec<u8>> = None;
+    let mut end = row;
+    while end > 0 {
+        let start = end.saturating_sub(BLOCK_ROWS);
+        let block = keys(start, end - start)?;
+        for (i, key) in block.iter().enumerate().rev() {
+            let segment = segment(key, delimiter);
+            match &previous {
+                None if segment != current => previous = Some(segment.to_vec()),
+                Some(previous) if previous.as_slice() != segment => {
+                    return Ok(Some(start + i + 1));
+                }
+                _ => (),
+            }
+        }
+        end = start;
+    }
+    Ok(previous.map(|_| 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_group_row, previous_group_row};
+
+    /// The rows of 300 "a:" keys, a "b:" key, two "c" keys without the delimiter and
+    /// 300 "d:" keys, the groups span the blocks of `BLOCK_ROWS`.
+    fn keys() -> Vec<Vec<u8>> {
+        let a = (0..300).map(|i| format!("a:{i:03}"));
+        let d = (0..300).map(|i| format!("d:{i:03}"));
+        let keys = a.chain(["b:x".to_owned(), "c".to_owned(), "c".to_owned()]).chain(d);
+        keys.map(String::into_bytes).collect()
+    }
+
+    #[test]
+    fn finds_the_bounds_of_the_groups() {
+        let keys = keys();
+        let read = |start: usize, len: usize| {
+            let (start, end) = (start.min(keys.len()), (start + len).min(keys.len()));
+            Ok(keys[start..end].to_vec())
+        };
+        let next = |row| next_group_row(row, b":", read).unwrap();
+        let previous = |row| previous_group_row(row, b":", read).unwrap();
+
+        assert_eq!(
+            [next(0), next(299), next(300), next(301), next(303)],
+            [Some(300), Some(300), Some(301), Some(303), None]
+        );
+        assert_eq!(
+            [previous(0), previous(299), previous(300), previous(302), previous(602)],
+            [None, None, Some(0), Some(300), Some(301)]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::ops::Bound;
+
+use eframe::egui;
+use heed::types::ByteSlice;
+use heed::{Database, RoTxn};
+
+/// The number of entries counted under the children of a node, the next children are
+/// still seeked to and listed, with the entries counted so far.
+const MAX_COUNTED_ENTRIES: u64 = 100_000;
+
+/// The number of children listed under a node.
+const MAX_CHILDREN: usize = 1000;
+
+/// A prefix under a node, up to and including the next delimiter, or a whole key.
+#[derive(Debug, Clone)]
+struct Child {
+    prefix: Vec<u8>,
+    /// The number of entries under it, the duplicates of a key included.
+    count: u64,
+    /// Whether there are more entries under it than were counted.
+    capped: bool,
+    /// Whether it is a whole key without any delimiter after its parent.
+    leaf: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Children {
+    children: Vec<Child>,
+    /// Whether the listing stopped before the last child of the node.
+    truncated: bool,
+}
+
+/// Where the nodes are listed from and how they are shown.
+struct Source<'a, 't> {
+    database: &'a Database<ByteSlice, ByteSlice>,
+    rtxn: &'a RoTxn<'t>,
+    delimiter: &'a [u8],
+    encode: &'a dyn Fn(&[u8]) -> String,
+}
+
+/// The keys of a database as a tree of the prefixes split at a delimiter, like folders.
+/// The children of a node are only listed once it is expanded, from the sorted entries
+/// under its prefix.
+#[derive(Debug, Default)]
+pub struct PrefixTree {
+    /// The delimiter and the version of the entries the nodes were listed with,
+    /// see `TreeBehavior::entries_version`.
+    listed_with: Option<(Vec<u8>, (u64, u64, u64))>,
+    /// The children of the nodes expanded so far, by prefix.
+    nodes: HashMap<Vec<u8>, Children>,
+}
+
+impl PrefixTree {
+    /// Shows the tree from the root, returns the prefix of the node or the key of
+    /// the leaf the user clicked, and whether it is a leaf.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &RoTxn,
+        delimiter: &[u8],
+        version: (u64, u64, u64),
+        encode: &dyn Fn(&[u8]) -> String,
+    ) -> heed::Result<Option<(Vec<u8>, bool)>> {
+        let listed_with = Some((delimiter.to_vec(), version));
+        if self.listed_with != listed_with {
+            self.nodes.clear();
+            self.listed_with = listed_with;
+        }
+        let source = Source { database, rtxn, delimiter, encode };
+        let mut clicked = None;
+        self.node_ui(ui, &source, &[], &mut clicked)?;
+        Ok(clicked)
+    }
+
+    fn node_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        source: &Source,
+        prefix: &[u8],
+        clicked: &mut Option<(Vec<u8>, bool)>,
+    ) -> heed::Result<()> {
+        let Children { children, truncated } = match self.nodes.get(prefix) {
+            Some(children) => children.clone(),
+            None => {
+                let children = list_children(source, prefix)?;
+                self.nodes.insert(prefix.to_vec(), children.clone());
+                children
+            }
+        };
+
+        for child in &children {
+            let at_least = if child.capped { "≥ " } else { "" };
+            // The key of the node itself is a leaf without any segment.
+            let segment = &child.prefix[prefix.len()..];
+            let name =
+                if segment.is_empty() { "(this key)".to_owned() } else { (source.encode)(segment) };
+            let label = format!("{name} ({at_least}{})", child.count);
+            if child.leaf {
+                if ui.selectable_label(false, label).on_hover_text("show this entry").clicked() {
+                    *clicked = Some((child.prefix.clone(), true));
+                }
+                continue;
+            }
+            let id = ui.make_persistent_id(&child.prefix);
+            let header = egui::collapsing_header::CollapsingState::load_with_default_open(
+                ui.ctx(),
+                id,
+                false,
+            );
+            let mut result = Ok(());
+            header
+                .show_header(ui, |ui| {
+                    if ui
+                        .selectable_label(false, label)
+                        .on_hover_text("show this subtree")
+                        .clicked()
+                    {
+                        *clicked = Some((child.prefix.clone(), false));
+                    }
+                })
+                .body(|ui| {
+                    result = self.node_ui(ui, source, &child.prefix, clicked);
+                });
+            result?;
+        }
+        if truncated {
+            ui.weak("the next entries are not listed, filter the table to see them");
+        }
+        Ok(())
+    }
+}
+
+/// Lists the children of the node at `prefix`, the keys under it are grouped by their
+/// next segment, the ones with the same segment follow each other in the sorted keys.
+/// The next child is seeked to after counting the entries of one: a child with many
+/// entries doesn't hide the ones after it.
+fn list_children(source: &Source, prefix: &[u8]) -> heed::Result<Children> {
+    let Source { database, rtxn, delimiter, .. } = *source;
+    let mut children: Vec<Child> = Vec::new();
+    let mut start = prefix.to_vec();
+    let mut counted = 0;
+    loop {
+        let mut entries = database.range(rtxn, &(Bound::Included(&start[..]), Bound::Unbounded))?;
+        let key = match entries.next().transpose()? {
+            Some((key, _)) if key.starts_with(prefix) => key,
+            _ => return Ok(Children { children, truncated: false }),
+        };
+        if children.len() == MAX_CHILDREN {
+            return Ok(Children { children, truncated: true });
+        }
+
+        let rest = &key[prefix.len()..];
+        let mut child = match rest.windows(delimiter.len()).position(|w| w == delimiter) {
+            Some(start) => Child {
+                prefix: key[..prefix.len() + start + delimiter.len()].to_vec(),
+                count: 1,
+                capped: false,
+                leaf: false,
+            },
+            None => Child { prefix: key.to_vec(), count: 1, capped: false, leaf: true },
+        };
+        for entry in entries {
+            let (key, _) = entry?;
+            let under = match child.leaf {
+                true => key == child.prefix,
+                false => key.starts_with(&child.prefix),
+            };
+            if !under {
+                break;
+            }
+            if counted == MAX_COUNTED_ENTRIES {
+                child.capped = true;
+                break;
+            }
+            child.count += 1;
+            counted += 1;
+        }
+
+        // The smallest key after the ones of the child, heed's excluded bounds increment
+        // the last byte and would skip the keys that extend a leaf.
+        let next = match child.leaf {
+            true => Some([child.prefix.as_slice(), &[0]].concat()),
+            false => prefix_end(&child.prefix),
+        };
+        children.push(child);
+        match next {
+            Some(next) => start = next,
+            None => return Ok(Children { children, truncated: false }),
+        }
+    }
+}
+
+/// The smallest key after all the keys starting with `prefix`, `None` when they are the last
+/// possible keys.
+fn prefix_end(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
@@ -0,0 +1,117 @@
+use eframe::egui;
+
+/// The number of databases listed by the switcher, the best matches first.
+const MAX_LISTED_DATABASES: usize = 20;
+
+/// A popup to jump to a database by typing part of its name, the letters typed must appear
+/// in the name in order but not next to each other.
+#[derive(Debug, Default)]
+pub struct QuickSwitcher {
+    query: String,
+    /// The index of the highlighted database in the list of matches.
+    selected: usize,
+    /// Whether the query field requested the focus, once.
+    focused: bool,
+}
+
+/// What the user did with the switcher this frame.
+pub enum SwitcherAction {
+    /// Nothing, it stays open.
+    None,
+    /// The user pressed Escape.
+    Close,
+    /// The user chose a database, `None` is the unnamed one.
+    Open(Option<String>),
+}
+
+impl QuickSwitcher {
+    pub fn ui(&mut self, ctx: &egui::Context, database_names: &[String]) -> SwitcherAction {
+        let candidates = std::iter::once(None).chain(database_names.iter().map(Some));
+        let mut matches: Vec<(i64, Option<&String>)> = candidates
+            .filter_map(|name| {
+                let shown = name.map_or("{main}", |name| name.as_str());
+                fuzzy_score(&self.query, shown).map(|score| (score, name))
+            })
+            .collect();
+        // The best scores first, then the shortest names.
+        matches.sort_by_key(|(score, name)| (-score, name.map_or(0, |name| name.len())));
+        matches.truncate(MAX_LISTED_DATABASES);
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.key_pressed(egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down && self.selected + 1 < matches.len() {
+            self.selected += 1;
+        }
+
+        let mut action = SwitcherAction::None;
+        egui::Window::new("Go to a database")
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("part of the name of a database")
+                        .desired_width(300.0),
+                );
+                if !self.focused {
+                    edit.request_focus();
+                    self.focused = true;
+                }
+                if edit.changed() {
+                    self.selected = 0;
+                }
+                if matches.is_empty() {
+                    ui.weak("no database matches");
+                }
+                for (i, (_, name)) in matches.iter().enumerate() {
+                    let shown = name.map_or("{main}", |name| name.as_str());
+                    if ui.selectable_label(i == self.selected, shown).clicked() {
+                        action = SwitcherAction::Open(name.cloned());
+                    }
+                }
+                ui.weak("↑ ↓ to choose, Enter to open, Escape to close");
+            });
+
+        if let (true, Some((_, name))) = (enter, matches.get(self.selected)) {
+            action = SwitcherAction::Open(name.cloned());
+        }
+        if escape {
+            action = SwitcherAction::Close;
+        }
+        action
+    }
+}
+
+/// Scores how well the `query` matches the `name`, ignoring the case, `None` when the
+/// characters of the query don't all appear in order in the name. The characters matched
+/// next to each other and at the start of the words of the name score more.
+fn fuzzy_score(query: &str, name: &str) -> Option<i64> {
+    let name: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for c in query.chars().flat_map(char::to_lowercase) {
+        let found = position + name[position..].iter().position(|&n| n == c)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !name[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
@@ -0,0 +1,87 @@
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+use rfd::FileDialog;
+use serde::{Deserialize, Serialize};
+
+/// The key under which the recently opened environments are persisted.
+const RECENT_ENVS_KEY: &str = "recent_environments";
+
+/// The number of environments we remember.
+const MAX_RECENT_ENVS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentEnv {
+    /// The absolute path of the environment.
+    pub path: PathBuf,
+    /// The map size in bytes the environment was last opened with.
+    pub map_size: usize,
+}
+
+/// The recently opened environments, the most recent first.
+#[derive(Debug, Default)]
+pub struct RecentEnvs {
+    envs: Vec<RecentEnv>,
+}
+
+impl RecentEnvs {
+    /// Loads the recent environments, forgetting about the ones that no longer exist.
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> RecentEnvs {
+        let mut envs: Vec<RecentEnv> = storage
+            .and_then(|storage| eframe::get_value(storage, RECENT_ENVS_KEY))
+            .unwrap_or_default();
+        envs.retain(|env| env.path.is_dir());
+        RecentEnvs { envs }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, RECENT_ENVS_KEY, &self.envs);
+    }
+
+    /// The map size the environment was last opened with, if it is recent.
+    pub fn map_size(&self, path: &Path) -> Option<usize> {
+        self.envs.iter().find(|env| env.path == path).map(|env| env.map_size)
+    }
+
+    /// Moves the environment at the top of the list, remembering its map size.
+    pub fn insert(&mut self, path: PathBuf, map_size: usize) {
+        self.envs.retain(|env| env.path != path);
+        self.envs.insert(0, RecentEnv { path, map_size });
+        self.envs.truncate(MAX_RECENT_ENVS);
+    }
+
+    /// Shows the recent environments and a button to browse for another one,
+    /// returns the path of the chosen environment and its last map size if it was recent.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<(PathBuf, Option<usize>)> {
+        ui.heading("Open an environment");
+
+        let mut chosen = None;
+        if ui.button("browse…").clicked() {
+            chosen = FileDialog::new().pick_folder().map(|path| (path, None));
+        }
+
+        if !self.envs.is_empty() {
+            ui.separator();
+            ui.label("Recent environments");
+        }
+
+        let mut forgotten = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, env) in self.envs.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.small_button("✕").on_hover_text("forget this environment").clicked() {
+                        forgotten = Some(i);
+                    }
+                    if ui.link(env.path.display().to_string()).clicked() {
+                        chosen = Some((env.path.clone(), Some(env.map_size)));
+                    }
+                });
+            }
+        });
+        if let Some(i) = forgotten {
+            self.envs.remove(i);
+        }
+
+        chosen
+    }
+}
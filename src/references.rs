@@ -0,0 +1,74 @@
+use eframe::egui;
+use egui::Color32;
+use regex::Regex;
+
+use crate::regex_search;
+
+/// The number of references listed for a value.
+const MAX_REFERENCES: usize = 100;
+
+/// Finds the keys a value refers to with a regex over its UTF-8 lossy rendering,
+/// its first capture group is the key, or the whole match when it has none.
+#[derive(Debug, Default)]
+pub struct ReferenceRule {
+    pub pattern: String,
+    /// The pattern last compiled and the regex or why it is invalid.
+    compiled: Option<(String, Result<Regex, String>)>,
+}
+
+impl ReferenceRule {
+    /// Lists the references found in the `data`, returns the one the user ctrl-clicked.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        id_source: impl std::hash::Hash,
+        data: &[u8],
+    ) -> Option<String> {
+        let mut clicked = None;
+        egui::CollapsingHeader::new("references").id_source(id_source).show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.pattern)
+                    .hint_text(r"user:(\d+)")
+                    .desired_width(200.0),
+            )
+            .on_hover_text("a regex, its first group is the key referred to");
+            if self.pattern.is_empty() {
+                return;
+            }
+            if self.compiled.as_ref().is_none_or(|(pattern, _)| *pattern != self.pattern) {
+                let regex = regex_search::compile(&self.pattern);
+                self.compiled = Some((self.pattern.clone(), regex));
+            }
+            let regex = match &self.compiled {
+                Some((_, Ok(regex))) => regex,
+                Some((_, Err(e))) => {
+                    ui.colored_label(Color32::RED, format!("invalid regex: {e}"));
+                    return;
+                }
+                None => unreachable!(),
+            };
+
+            let text = String::from_utf8_lossy(data);
+            let mut references: Vec<&str> = regex
+                .captures_iter(&text)
+                .filter_map(|captures| captures.get(1).or_else(|| captures.get(0)))
+                .map(|key| key.as_str())
+                .filter(|key| !key.is_empty())
+                .take(MAX_REFERENCES)
+                .collect();
+            references.dedup();
+            if references.is_empty() {
+                ui.weak("no references in this value");
+            }
+            ui.horizontal_wrapped(|ui| {
+                for key in references {
+                    let link = ui.link(key).on_hover_text("ctrl-click to go to this key");
+                    if link.clicked() && ui.input(|i| i.modifiers.command) {
+                        clicked = Some(key.to_owned());
+                    }
+                }
+            });
+        });
+        clicked
+    }
+}
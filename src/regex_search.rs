@@ -0,0 +1,133 @@
+use std::ops::Bound;
+
+use heed::types::ByteSlice;
+use heed::{Database, RoTxn};
+use regex::{Regex, RegexBuilder};
+
+use crate::row_cache;
+
+/// The bytes a compiled regex can take, the larger patterns are refused.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// The number of bytes of a key or value the regex is run over, the rest is ignored.
+/// The regex crate matches in linear time, this bounds the time spent per entry.
+const MAX_MATCHED_BYTES: usize = 64 * 1024;
+
+/// The number of entries searched first, the user expands it explicitly.
+const DEFAULT_SCOPE: usize = 1000;
+
+/// Compiles a pattern typed by the user, the patterns too large to run quickly are refused.
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// What a search ran with, it runs again when any of it changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Query {
+    pattern: String,
+    keys: bool,
+    values: bool,
+    scope: usize,
+    reverse: bool,
+    /// See `TreeBehavior::entries_version`.
+    version: (u64, u64, u64),
+}
+
+/// Searches the first entries of a database for the keys or values matching a regex,
+/// in their UTF-8 lossy rendering. It is heavier than the other filters so it only runs
+/// over the `scope` first entries and once per change of the query.
+#[derive(Debug)]
+pub struct RegexSearch {
+    pub pattern: String,
+    /// Whether the regex is matched against the keys.
+    pub keys: bool,
+    /// Whether the regex is matched against the values.
+    pub values: bool,
+    /// The number of entries searched, from the first row.
+    pub scope: usize,
+    /// The pattern last compiled and the regex or why it is invalid.
+    compiled: Option<(String, Result<Regex, String>)>,
+    /// The query of the last search, if any.
+    last_query: Option<Query>,
+    /// The matching entries of the last search.
+    results: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Default for RegexSearch {
+    fn default() -> RegexSearch {
+        RegexSearch {
+            pattern: String::new(),
+            keys: true,
+            values: false,
+            scope: DEFAULT_SCOPE,
+            compiled: None,
+            last_query: None,
+            results: Vec::new(),
+        }
+    }
+}
+
+impl RegexSearch {
+    /// Whether there is a pattern to search with on any field.
+    pub fn is_active(&self) -> bool {
+        !self.pattern.is_empty() && (self.keys || self.values)
+    }
+
+    /// Compiles the pattern when it changed and returns the regex or why it is invalid.
+    pub fn regex(&mut self) -> Result<&Regex, &str> {
+        if self.compiled.as_ref().is_none_or(|(pattern, _)| *pattern != self.pattern) {
+            self.compiled = Some((self.pattern.clone(), compile(&self.pattern)));
+        }
+        match &self.compiled {
+            Some((_, Ok(regex))) => Ok(regex),
+            Some((_, Err(e))) => Err(e),
+            None => unreachable!(),
+        }
+    }
+
+    /// Returns the matching entries among the `scope` first ones, searching them again
+    /// only when the query or the entries changed.
+    pub fn matches(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &RoTxn,
+        reverse: bool,
+        version: (u64, u64, u64),
+    ) -> heed::Result<&[(Vec<u8>, Vec<u8>)]> {
+        let query = Query {
+            pattern: self.pattern.clone(),
+            keys: self.keys,
+            values: self.values,
+            scope: self.scope,
+            reverse,
+            version,
+        };
+        if self.last_query.as_ref() != Some(&query) {
+            let (keys, values) = (self.keys, self.values);
+            let regex = match self.regex() {
+                Ok(regex) => regex.clone(),
+                Err(_) => return Ok(&[]),
+            };
+            let is_match = |bytes: &[u8]| {
+                let bytes = &bytes[..bytes.len().min(MAX_MATCHED_BYTES)];
+                regex.is_match(&String::from_utf8_lossy(bytes))
+            };
+
+            let entries = row_cache::entries_from(database, rtxn, Bound::Unbounded, reverse)?;
+            let mut matches = Vec::new();
+            for entry in entries.take(self.scope) {
+                let (key, data) = entry?;
+                if (keys && is_match(key)) || (values && is_match(data)) {
+                    matches.push((key.to_vec(), data.to_vec()));
+                }
+            }
+            self.results = matches;
+            self.last_query = Some(query);
+        }
+        Ok(&self.results)
+    }
+}
@@ -0,0 +1,255 @@
+use std::ops::Bound;
+
+use either::Either;
+use heed::types::ByteSlice;
+use heed::{Database, RoRange, RoRevRange, RoTxn};
+
+/// The number of rows between two remembered keys.
+const STRIDE: usize = 256;
+
+/// Remembers the key of every `STRIDE`th row of a database so that a table can be
+/// positioned at any row by seeking to a key and skipping at most `STRIDE` entries,
+/// instead of iterating over all the rows before it.
+#[derive(Debug, Default)]
+pub struct RowCache {
+    /// The key at each `STRIDE`th row along with the number of rows before it with the
+    /// same key, a dup-sort database can store a key on many consecutive rows.
+    keys: Vec<(KeyStart, usize)>,
+    /// What the keys were computed against, they are forgotten when it changes.
+    version: (u64, u64),
+    /// Whether the rows are in descending order of the keys.
+    reverse: bool,
+}
+
+impl RowCache {
+    /// Forgets the remembered keys, the rows must be forgotten when entries are inserted or removed.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// Forgets the remembered keys when the `version` is not the one they were computed with.
+    pub fn check_version(&mut self, version: (u64, u64)) {
+        if self.version != version {
+            self.keys.clear();
+            self.version = version;
+        }
+    }
+
+    /// Sets the order of the rows, the remembered keys are forgotten when it changes.
+    pub fn set_reverse(&mut self, reverse: bool) {
+        if self.reverse != reverse {
+            self.keys.clear();
+            self.reverse = reverse;
+        }
+    }
+
+    /// Returns an iterator over the entries of the database, starting at `row`.
+    pub fn iter_from<'t>(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &'t RoTxn,
+        row: usize,
+    ) -> heed::Result<impl Iterator<Item = heed::Result<(&'t [u8], &'t [u8])>> + 't> {
+        let reverse = self.reverse;
+        let checkpoint = row / STRIDE;
+        if self.keys.len() <= checkpoint {
+            self.remember_keys_until(database, rtxn, checkpoint)?;
+        }
+
+        // The rows past the end of the database start from the last remembered key.
+        match self.keys.len().checked_sub(1).map(|last| checkpoint.min(last)) {
+            Some(index) => {
+                let (key_start, same_key_rows) = &self.keys[index];
+                let start = index * STRIDE - same_key_rows;
+                Ok(key_start.entries(database, rtxn, reverse)?.skip(row - start))
+            }
+            None => Ok(entries_from(database, rtxn, Bound::Unbounded, reverse)?.skip(row)),
+        }
+    }
+
+    /// Iterates from the last remembered key to remember the keys up to the `checkpoint`.
+    fn remember_keys_until(
+        &mut self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &RoTxn,
+        checkpoint: usize,
+    ) -> heed::Result<()> {
+        let reverse = self.reverse;
+        let (entries, mut row, resumed) = match self.keys.last() {
+            Some((key_start, same_key_rows)) => {
+                let start = (self.keys.len() - 1) * STRIDE - same_key_rows;
+                (key_start.entries(database, rtxn, reverse)?, start, Some(key_start.clone()))
+            }
+            None => (entries_from(database, rtxn, Bound::Unbounded, reverse)?, 0, None),
+        };
+
+        let mut prev_key: Option<&[u8]> = None;
+        // The key of the rows right before the rows of the current key, `None` for the first
+        // key, the one resumed from when there is a remembered key.
+        let mut key_before: Option<&[u8]> = None;
+        let mut same_key_rows = 0;
+        for result in entries {
+            let (key, _) = result?;
+            if prev_key == Some(key) {
+                same_key_rows += 1;
+            } else {
+                same_key_rows = 0;
+                key_before = prev_key;
+            }
+            prev_key = Some(key);
+
+            if row % STRIDE == 0 && row / STRIDE == self.keys.len() {
+                let key_start = match (&resumed, key_before) {
+                    (Some(resumed), None) => resumed.clone(),
+                    _ => KeyStart::new(key, key_before, reverse),
+                };
+                self.keys.push((key_start, same_key_rows));
+                if self.keys.len() > checkpoint {
+                    break;
+                }
+            }
+            row += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Where the rows of a key start in ascending or descending order, to iterate from them again.
+///
+/// LMDB seeks to the first duplicate of a key, but in descending order the rows of a key of a
+/// dup-sort database start at its last duplicate, right before the first row of the next key.
+#[derive(Debug, Clone)]
+pub struct KeyStart {
+    pub key: Vec<u8>,
+    /// In descending order, the key of the rows before the rows of `key`, `None` when `key`
+    /// is the last key of the database.
+    next_key: Option<Vec<u8>>,
+}
+
+impl KeyStart {
+    /// The start of the rows of `key`, `key_before` is the key of the rows right before them.
+    pub fn new(key: &[u8], key_before: Option<&[u8]>, reverse: bool) -> KeyStart {
+        let next_key = key_before.filter(|_| reverse).map(<[u8]>::to_vec);
+        KeyStart { key: key.to_vec(), next_key }
+    }
+
+    /// In descending order, the key of the rows right before the rows of the key.
+    pub fn key_before(&self) -> Option<&[u8]> {
+        self.next_key.as_deref()
+    }
+
+    /// Whether the rows of the key are the first ones in descending order, they are then
+    /// iterated from the end of what is iterated rather than from the next key.
+    pub fn starts_at_end(&self, reverse: bool) -> bool {
+        reverse && self.next_key.is_none()
+    }
+
+    /// The entries from the first row of the key, in the order it was created for.
+    pub fn entries<'t>(
+        &self,
+        database: &Database<ByteSlice, ByteSlice>,
+        rtxn: &'t RoTxn,
+        reverse: bool,
+    ) -> heed::Result<Either<RoRange<'t, ByteSlice, ByteSlice>, RoRevRange<'t, ByteSlice, ByteSlice>>>
+    {
+        let start = match (&self.next_key, reverse) {
+            (_, false) => Bound::Included(self.key.as_slice()),
+            // heed seeks to the first duplicate of the next key and steps back from it.
+            (Some(next_key), true) => Bound::Excluded(next_key.as_slice()),
+            (None, true) => Bound::Unbounded,
+        };
+        entries_from(database, rtxn, start, reverse)
+    }
+}
+
+/// The entries of a database in ascending or descending order of the keys,
+/// from a bound on the key of the first entry.
+pub fn entries_from<'t>(
+    database: &Database<ByteSlice, ByteSlice>,
+    rtxn: &'t RoTxn,
+    start: Bound<&[u8]>,
+    reverse: bool,
+) -> heed::Result<Either<RoRange<'t, ByteSlice, ByteSlice>, RoRevRange<'t, ByteSlice, ByteSlice>>> {
+    if reverse {
+        Ok(Either::Right(database.rev_range(rtxn, &(Bound::Unbounded, start))?))
+    } else {
+        Ok(Either::Left(database.range(rtxn, &(start, Bound::Unbounded))?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::{fs, ptr};
+
+    use heed::types::ByteSlice;
+    use heed::{Database, EnvOpenOptions};
+    use lmdb_master_sys as ffi;
+
+    use super::{RowCache, STRIDE};
+
+    /// The number of keys and of duplicates of each key, the keys span the remembered rows.
+    const KEYS: u8 = 7;
+    const DUPLICATES: u16 = 100;
+
+    /// Creates a dup-sort database with LMDB itself, heed can't create one.
+    fn create_dup_sort_database(path: &std::path::Path) {
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        let name = CString::new("dups").unwrap();
+        let val =
+            |bytes: &[u8]| ffi::MDB_val { mv_size: bytes.len(), mv_data: bytes.as_ptr() as *mut _ };
+        // Safety: the handles are used and closed in order, the values outlive the puts.
+        unsafe {
+            let mut env = ptr::null_mut();
+            assert_eq!(ffi::mdb_env_create(&mut env), 0);
+            assert_eq!(ffi::mdb_env_set_maxdbs(env, 1), 0);
+            assert_eq!(ffi::mdb_env_open(env, path.as_ptr(), 0, 0o600), 0);
+            let mut txn = ptr::null_mut();
+            assert_eq!(ffi::mdb_txn_begin(env, ptr::null_mut(), 0, &mut txn), 0);
+            let mut dbi = 0;
+            let flags = ffi::MDB_CREATE | ffi::MDB_DUPSORT;
+            assert_eq!(ffi::mdb_dbi_open(txn, name.as_ptr(), flags, &mut dbi), 0);
+            for key in 0..KEYS {
+                for data in 0..DUPLICATES {
+                    let (key, data) = ([key], data.to_be_bytes());
+                    assert_eq!(ffi::mdb_put(txn, dbi, &mut val(&key), &mut val(&data), 0), 0);
+                }
+            }
+            assert_eq!(ffi::mdb_txn_commit(txn), 0);
+            ffi::mdb_env_close(env);
+        }
+    }
+
+    #[test]
+    fn positions_dup_sort_rows_in_both_orders() {
+        let path = std::env::temp_dir().join(format!("lmdb-editor-dups-{}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        create_dup_sort_database(&path);
+        let env = EnvOpenOptions::new().max_dbs(1).open(&path).unwrap();
+        let rtxn = env.read_txn().unwrap();
+        let database: Database<ByteSlice, ByteSlice> =
+            env.open_database(&rtxn, Some("dups")).unwrap().unwrap();
+
+        let ascending: Vec<_> = database.iter(&rtxn).unwrap().map(Result::unwrap).collect();
+        assert_eq!(ascending.len(), KEYS as usize * DUPLICATES as usize);
+        assert!(ascending.len() > 2 * STRIDE);
+        let descending: Vec<_> = ascending.iter().rev().copied().collect();
+
+        for (reverse, expected) in [(false, &ascending), (true, &descending)] {
+            // The last rows first, the keys are then remembered in one go, the others after.
+            let rows = (0..expected.len()).rev().step_by(37).chain(0..expected.len());
+            let mut cache = RowCache::default();
+            cache.set_reverse(reverse);
+            for row in rows {
+                let entries = cache.iter_from(&database, &rtxn, row).unwrap();
+                let entries: Vec<_> = entries.take(3).map(Result::unwrap).collect();
+                assert_eq!(entries, expected[row..].iter().take(3).copied().collect::<Vec<_>>());
+            }
+        }
+
+        drop(rtxn);
+        env.prepare_for_closing().wait();
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
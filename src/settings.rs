@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::ValueEncoding;
+use crate::key_type::KeyType;
+
+/// The key under which the settings are persisted.
+const SETTINGS_KEY: &str = "settings";
+
+/// The defaults chosen by the user, for all the environments. The tabs opened on a database
+/// are shown like it last was, the defaults are for the databases never shown before.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The encoding the keys and values of the new tabs are shown in.
+    pub encoding: ValueEncoding,
+    pub key_type: KeyType,
+    /// The number of entries per page of the new tabs, they scroll continuously when `None`.
+    pub page_size: Option<usize>,
+    /// The map size the environments opened for the first time are proposed, the last
+    /// used one when `None`.
+    pub map_size: Option<usize>,
+}
+
+impl Settings {
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Settings {
+        storage.and_then(|storage| eframe::get_value(storage, SETTINGS_KEY)).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, self);
+    }
+}
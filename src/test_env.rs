@@ -0,0 +1,108 @@
+use std::ffi::CString;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::{fs, ptr};
+
+use heed::types::ByteSlice;
+use heed::{Database, Env, EnvOpenOptions};
+use lmdb_master_sys as ffi;
+
+/// The number of named databases the test environments can have.
+const MAX_DBS: u32 = 4;
+
+/// An environment in its own temporary folder for a test, removed when it is dropped.
+pub struct TestEnv {
+    env: Option<Env>,
+    path: PathBuf,
+}
+
+impl TestEnv {
+    /// Opens an empty environment in a folder named after the test, `name` must be unique.
+    pub fn new(name: &str) -> TestEnv {
+        let path = folder(name);
+        let env = EnvOpenOptions::new().max_dbs(MAX_DBS).open(&path).unwrap();
+        TestEnv { env: Some(env), path }
+    }
+
+    /// Opens an environment with a dup-sort database created with LMDB itself, heed can't.
+    pub fn with_dup_sort(name: &str, database: &str, entries: &[(&str, &str)]) -> TestEnv {
+        let path = folder(name);
+        create_dup_sort_database(&path, database, entries);
+        let env = EnvOpenOptions::new().max_dbs(MAX_DBS).open(&path).unwrap();
+        TestEnv { env: Some(env), path }
+    }
+
+    /// Creates a database with the entries, committed.
+    pub fn database(
+        &self,
+        name: Option<&str>,
+        entries: &[(&str, &str)],
+    ) -> Database<ByteSlice, ByteSlice> {
+        let mut wtxn = self.write_txn().unwrap();
+        let database = self.create_database(&mut wtxn, name).unwrap();
+        for (key, data) in entries {
+            database.put(&mut wtxn, key.as_bytes(), data.as_bytes()).unwrap();
+        }
+        wtxn.commit().unwrap();
+        database
+    }
+
+    /// Opens the dup-sort database created with the environment.
+    pub fn dup_sort_database(&self, name: &str) -> Database<ByteSlice, ByteSlice> {
+        // LMDB closes the databases first opened by a txn that is aborted, as a read txn is.
+        let wtxn = self.write_txn().unwrap();
+        let database = self.open_database(&wtxn, Some(name)).unwrap().unwrap();
+        wtxn.commit().unwrap();
+        database
+    }
+}
+
+impl Deref for TestEnv {
+    type Target = Env;
+
+    fn deref(&self) -> &Env {
+        self.env.as_ref().unwrap()
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        if let Some(env) = self.env.take() {
+            env.prepare_for_closing().wait();
+        }
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// An empty temporary folder for the environment of a test.
+fn folder(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("lmdb-editor-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+fn create_dup_sort_database(path: &std::path::Path, name: &str, entries: &[(&str, &str)]) {
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let name = CString::new(name).unwrap();
+    let val =
+        |bytes: &[u8]| ffi::MDB_val { mv_size: bytes.len(), mv_data: bytes.as_ptr() as *mut _ };
+    // Safety: the handles are used and closed in order, the values outlive the puts.
+    unsafe {
+        let mut env = ptr::null_mut();
+        assert_eq!(ffi::mdb_env_create(&mut env), 0);
+        assert_eq!(ffi::mdb_env_set_maxdbs(env, MAX_DBS), 0);
+        assert_eq!(ffi::mdb_env_open(env, path.as_ptr(), 0, 0o600), 0);
+        let mut txn = ptr::null_mut();
+        assert_eq!(ffi::mdb_txn_begin(env, ptr::null_mut(), 0, &mut txn), 0);
+        let mut dbi = 0;
+        let flags = ffi::MDB_CREATE | ffi::MDB_DUPSORT;
+        assert_eq!(ffi::mdb_dbi_open(txn, name.as_ptr(), flags, &mut dbi), 0);
+        for (key, data) in entries {
+            let (mut key, mut data) = (val(key.as_bytes()), val(data.as_bytes()));
+            assert_eq!(ffi::mdb_put(txn, dbi, &mut key, &mut data, 0), 0);
+        }
+        assert_eq!(ffi::mdb_txn_commit(txn), 0);
+        ffi::mdb_env_close(env);
+    }
+}
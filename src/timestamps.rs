@@ -0,0 +1,53 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use heed::types::ByteSlice;
+use heed::{Database, Env, RoTxn, RwTxn};
+
+use crate::op_log::DirtyKeys;
+
+/// The named database the times the keys were last written through the editor are kept in.
+pub const TIMESTAMPS_DATABASE: &str = "__meta_timestamps";
+
+/// The key of a key of a database in the timestamps database: the name of its database, the
+/// unnamed one has an empty name, then a nul byte, LMDB names can't have one, then the key.
+fn meta_key(database_name: &Option<String>, key: &[u8]) -> Vec<u8> {
+    let name = database_name.as_deref().unwrap_or_default();
+    let mut meta_key = Vec::with_capacity(name.len() + 1 + key.len());
+    meta_key.extend_from_slice(name.as_bytes());
+    meta_key.push(0);
+    meta_key.extend_from_slice(key);
+    meta_key
+}
+
+/// Records the time the keys put or deleted in the write txn were written, in the same write txn
+/// right before it is committed. The timestamps are milliseconds since the Unix epoch, as big
+/// endian `u64`s. Returns whether any was recorded.
+pub fn record(env: &Env, wtxn: &mut RwTxn, dirty_keys: &DirtyKeys) -> heed::Result<bool> {
+    let mut keys = dirty_keys
+        .iter()
+        .filter(|(name, _)| name.as_deref() != Some(TIMESTAMPS_DATABASE))
+        .peekable();
+    if keys.peek().is_none() {
+        return Ok(false);
+    }
+    let database: Database<ByteSlice, ByteSlice> =
+        env.create_database(wtxn, Some(TIMESTAMPS_DATABASE))?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let timestamp = (now.as_millis() as u64).to_be_bytes();
+    for (name, key) in keys {
+        database.put(wtxn, &meta_key(name, key), &timestamp)?;
+    }
+    Ok(true)
+}
+
+/// Returns when the key of the database was last written, if it was recorded.
+pub fn get(
+    timestamps: &Database<ByteSlice, ByteSlice>,
+    rtxn: &RoTxn,
+    database_name: &Option<String>,
+    key: &[u8],
+) -> heed::Result<Option<SystemTime>> {
+    let timestamp = timestamps.get(rtxn, &meta_key(database_name, key))?;
+    let millis = timestamp.and_then(|timestamp| timestamp.try_into().ok()).map(u64::from_be_bytes);
+    Ok(millis.map(|millis| UNIX_EPOCH + Duration::from_millis(millis)))
+}
@@ -0,0 +1,261 @@
+use serde_json::{Map, Number, Value};
+
+use crate::encoding::ValueEncoding;
+
+/// Turns the values of a serialization format into text for the detail view of a row.
+pub trait ValueDecoder {
+    fn name(&self) -> &'static str;
+
+    /// Returns `None` when the bytes aren't in the format, only the raw bytes are then shown.
+    fn decode(&self, bytes: &[u8]) -> Option<String>;
+}
+
+/// The decoders the user can choose from.
+pub const DECODERS: [&dyn ValueDecoder; 2] = [&PrettyJson, &MessagePack];
+
+/// Pretty-prints UTF-8 JSON.
+pub struct PrettyJson;
+
+impl ValueDecoder for PrettyJson {
+    fn name(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<String> {
+        let value: Value = serde_json::from_slice(bytes).ok()?;
+        serde_json::to_string_pretty(&value).ok()
+    }
+}
+
+/// Shows MessagePack as pretty-printed JSON, the binary strings and extensions
+/// are written in STFU-8 and the keys of the maps that aren't strings as JSON.
+pub struct MessagePack;
+
+impl ValueDecoder for MessagePack {
+    fn name(&self) -> &'static str {
+        "MessagePack"
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<String> {
+        let mut reader = Reader { bytes, depth: 0 };
+        let value = reader.value()?;
+        // A single byte is often a valid MessagePack integer, the trailing bytes tell apart
+        // the values that merely start like MessagePack.
+        if !reader.bytes.is_empty() {
+            return None;
+        }
+        serde_json::to_string_pretty(&value).ok()
+    }
+}
+
+/// The maximum nesting of the MessagePack arrays and maps, to not overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        let marker = self.u8()?;
+        let value = match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => self.map((marker & 0x0f).into())?,
+            0x90..=0x9f => self.seq((marker & 0x0f).into())?,
+            0xa0..=0xbf => self.str((marker & 0x1f).into())?,
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4 => {
+                let len = self.u8()?.into();
+                self.bin(len)?
+            }
+            0xc5 => {
+                let len = self.u16()?.into();
+                self.bin(len)?
+            }
+            0xc6 => {
+                let len = self.u32()? as usize;
+                self.bin(len)?
+            }
+            0xc7 => {
+                let len = self.u8()?.into();
+                self.ext(len)?
+            }
+            0xc8 => {
+                let len = self.u16()?.into();
+                self.ext(len)?
+            }
+            0xc9 => {
+                let len = self.u32()? as usize;
+                self.ext(len)?
+            }
+            0xca => Value::Number(Number::from_f64(f32::from_be_bytes(self.array()?).into())?),
+            0xcb => Value::Number(Number::from_f64(f64::from_be_bytes(self.array()?))?),
+            0xcc => Value::from(self.u8()?),
+            0xcd => Value::from(self.u16()?),
+            0xce => Value::from(self.u32()?),
+            0xcf => Value::from(u64::from_be_bytes(self.array()?)),
+            0xd0 => Value::from(i8::from_be_bytes(self.array()?)),
+            0xd1 => Value::from(i16::from_be_bytes(self.array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.array()?)),
+            0xd4 => self.ext(1)?,
+            0xd5 => self.ext(2)?,
+            0xd6 => self.ext(4)?,
+            0xd7 => self.ext(8)?,
+            0xd8 => self.ext(16)?,
+            0xd9 => {
+                let len = self.u8()?.into();
+                self.str(len)?
+            }
+            0xda => {
+                let len = self.u16()?.into();
+                self.str(len)?
+            }
+            0xdb => {
+                let len = self.u32()? as usize;
+                self.str(len)?
+            }
+            0xdc => {
+                let len = self.u16()?.into();
+                self.seq(len)?
+            }
+            0xdd => {
+                let len = self.u32()? as usize;
+                self.seq(len)?
+            }
+            0xde => {
+                let len = self.u16()?.into();
+                self.map(len)?
+            }
+            0xdf => {
+                let len = self.u32()? as usize;
+                self.map(len)?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            // 0xc1 is never used.
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn str(&mut self, len: usize) -> Option<Value> {
+        let text = std::str::from_utf8(self.take(len)?).ok()?;
+        Some(Value::String(text.to_owned()))
+    }
+
+    fn bin(&mut self, len: usize) -> Option<Value> {
+        Some(Value::String(ValueEncoding::Stfu8.encode(self.take(len)?)))
+    }
+
+    fn ext(&mut self, len: usize) -> Option<Value> {
+        let ty = i8::from_be_bytes(self.array()?);
+        let data = ValueEncoding::Stfu8.encode(self.take(len)?);
+        Some(serde_json::json!({ "ext": ty, "data": data }))
+    }
+
+    /// The lengths are not trusted to allocate, a malformed value could claim any length.
+    fn seq(&mut self, len: usize) -> Option<Value> {
+        self.nested(|reader| {
+            (0..len).map(|_| reader.value()).collect::<Option<_>>().map(Value::Array)
+        })
+    }
+
+    fn map(&mut self, len: usize) -> Option<Value> {
+        self.nested(|reader| {
+            let mut map = Map::new();
+            for _ in 0..len {
+                let key = match reader.value()? {
+                    Value::String(key) => key,
+                    key => key.to_string(),
+                };
+                map.insert(key, reader.value()?);
+            }
+            Some(Value::Object(map))
+        })
+    }
+
+    fn nested(&mut self, f: impl FnOnce(&mut Self) -> Option<Value>) -> Option<Value> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let value = f(self);
+        self.depth -= 1;
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{MessagePack, PrettyJson, ValueDecoder, MAX_DEPTH};
+
+    fn decoded(bytes: &[u8]) -> Option<Value> {
+        MessagePack.decode(bytes).map(|text| serde_json::from_str(&text).unwrap())
+    }
+
+    #[test]
+    fn decodes_message_pack_as_json() {
+        // {"a": [1, -1, true, nil, 1.5], 1: bin [0, 65], "e": ext 3 [66]}
+        let bytes = [
+            &[0x83, 0xa1, b'a', 0x95, 0x01, 0xff, 0xc3, 0xc0, 0xcb][..],
+            &1.5f64.to_be_bytes(),
+            &[0x01, 0xc4, 0x02, 0x00, 0x41, 0xa1, b'e', 0xd4, 0x03, 0x42],
+        ]
+        .concat();
+        let expected = json!({
+            "a": [1, -1, true, null, 1.5],
+            "1": "\\x00A",
+            "e": { "ext": 3, "data": "B" },
+        });
+        assert_eq!(decoded(&bytes), Some(expected));
+        assert_eq!(decoded(&[0xcd, 0x01, 0x00]), Some(json!(256)));
+        assert_eq!(decoded(&[0xd9, 0x02, b'h', b'i']), Some(json!("hi")));
+    }
+
+    #[test]
+    fn rejects_what_merely_starts_like_message_pack() {
+        // Trailing bytes, a truncated array, a truncated string and the unused marker.
+        for bytes in [&[0x01, 0x02][..], &[0x92, 0x01], &[0xa3, b'a'], &[0xc1]] {
+            assert_eq!(decoded(bytes), None);
+        }
+        let nested = |depth: usize| [vec![0x91; depth], vec![0x01]].concat();
+        assert!(MessagePack.decode(&nested(MAX_DEPTH)).is_some());
+        assert_eq!(MessagePack.decode(&nested(MAX_DEPTH + 1)), None);
+    }
+
+    #[test]
+    fn pretty_prints_json() {
+        assert_eq!(PrettyJson.decode(br#"{"a":1}"#).as_deref(), Some("{\n  \"a\": 1\n}"));
+        assert_eq!(PrettyJson.decode(b"{"), None);
+    }
+}
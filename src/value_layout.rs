@@ -0,0 +1,185 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use eframe::egui;
+
+/// The seconds since the epoch of the last second `humantime` can format, in year 9999.
+const MAX_TIMESTAMP_SECS: u64 = 253_402_300_799;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I64,
+    F64,
+    /// A `u64` of seconds since the Unix epoch.
+    UnixSeconds,
+    /// A `u64` of milliseconds since the Unix epoch.
+    UnixMillis,
+    /// Bytes shown in hex.
+    Hex,
+    /// Bytes shown as UTF-8, lossily.
+    Utf8,
+}
+
+impl FieldType {
+    pub const ALL: [FieldType; 10] = [
+        FieldType::U8,
+        FieldType::U16,
+        FieldType::U32,
+        FieldType::U64,
+        FieldType::I64,
+        FieldType::F64,
+        FieldType::UnixSeconds,
+        FieldType::UnixMillis,
+        FieldType::Hex,
+        FieldType::Utf8,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FieldType::U8 => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+            FieldType::U64 => "u64",
+            FieldType::I64 => "i64",
+            FieldType::F64 => "f64",
+            FieldType::UnixSeconds => "unix seconds",
+            FieldType::UnixMillis => "unix millis",
+            FieldType::Hex => "hex bytes",
+            FieldType::Utf8 => "UTF-8 bytes",
+        }
+    }
+
+    /// The number of bytes of the numbers, the bytes fields have their own length.
+    fn width(self) -> Option<usize> {
+        match self {
+            FieldType::U8 => Some(1),
+            FieldType::U16 => Some(2),
+            FieldType::U32 => Some(4),
+            FieldType::U64
+            | FieldType::I64
+            | FieldType::F64
+            | FieldType::UnixSeconds
+            | FieldType::UnixMillis => Some(8),
+            FieldType::Hex | FieldType::Utf8 => None,
+        }
+    }
+}
+
+/// A field at a fixed offset of the values.
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub offset: usize,
+    pub ty: FieldType,
+    /// The number of bytes of a bytes field, all the bytes up to the end of the value when 0.
+    pub len: usize,
+    pub big_endian: bool,
+}
+
+impl Field {
+    /// Formats the field of a value, `None` when the value is too short to have it.
+    pub fn parse(&self, data: &[u8]) -> Option<String> {
+        let bytes = data.get(self.offset..)?;
+        let bytes = match self.ty.width() {
+            Some(width) => bytes.get(..width)?,
+            None if self.len == 0 => bytes,
+            None => bytes.get(..self.len)?,
+        };
+
+        // The numbers are zero-extended to 8 bytes, the bytes fields have no number.
+        let mut padded = [0; 8];
+        let number = match self.ty.width() {
+            Some(width) if self.big_endian => {
+                padded[8 - width..].copy_from_slice(bytes);
+                u64::from_be_bytes(padded)
+            }
+            Some(width) => {
+                padded[..width].copy_from_slice(bytes);
+                u64::from_le_bytes(padded)
+            }
+            None => 0,
+        };
+        let timestamp = |duration: Duration| match duration.as_secs() {
+            secs if secs > MAX_TIMESTAMP_SECS => format!("{number} (out of range)"),
+            _ => humantime::format_rfc3339_millis(UNIX_EPOCH + duration).to_string(),
+        };
+
+        Some(match self.ty {
+            FieldType::U8 | FieldType::U16 | FieldType::U32 | FieldType::U64 => number.to_string(),
+            FieldType::I64 => (number as i64).to_string(),
+            FieldType::F64 => f64::from_bits(number).to_string(),
+            FieldType::UnixSeconds => timestamp(Duration::from_secs(number)),
+            FieldType::UnixMillis => timestamp(Duration::from_millis(number)),
+            FieldType::Hex => hex::encode(bytes),
+            FieldType::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        })
+    }
+}
+
+/// Describes the fields at the start of the values of a database, like a timestamp header,
+/// to show them in their own columns.
+#[derive(Debug, Clone, Default)]
+pub struct ValueLayout {
+    pub fields: Vec<Field>,
+}
+
+impl ValueLayout {
+    /// Edits the fields, each one after the previous one by default.
+    pub fn ui(&mut self, ui: &mut egui::Ui, id_source: impl std::hash::Hash + Copy) {
+        let mut removed = None;
+        egui::Grid::new((id_source, "fields")).striped(true).show(ui, |ui| {
+            ui.label("name");
+            ui.label("offset");
+            ui.label("type");
+            ui.label("length");
+            ui.label("big endian");
+            ui.end_row();
+
+            for (i, field) in self.fields.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut field.name).desired_width(100.0));
+                ui.add(egui::DragValue::new(&mut field.offset).suffix(" B"));
+                egui::ComboBox::from_id_source((id_source, "field_type", i))
+                    .selected_text(field.ty.label())
+                    .show_ui(ui, |ui| {
+                        for ty in FieldType::ALL {
+                            ui.selectable_value(&mut field.ty, ty, ty.label());
+                        }
+                    });
+                match field.ty.width() {
+                    Some(width) => ui.label(format!("{width} B")),
+                    None => ui
+                        .add(egui::DragValue::new(&mut field.len).suffix(" B"))
+                        .on_hover_text("0 for all the bytes up to the end of the value"),
+                };
+                ui.add_enabled(
+                    field.ty.width().is_some_and(|width| width > 1),
+                    egui::Checkbox::new(&mut field.big_endian, ""),
+                );
+                if ui.small_button("✕").clicked() {
+                    removed = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed {
+            self.fields.remove(i);
+        }
+
+        if ui.button("add a field").clicked() {
+            let offset = self
+                .fields
+                .last()
+                .map_or(0, |last| last.offset + last.ty.width().unwrap_or(last.len));
+            self.fields.push(Field {
+                name: format!("field {}", self.fields.len() + 1),
+                offset,
+                ty: FieldType::U64,
+                len: 0,
+                big_endian: false,
+            });
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::ops::Bound;
+
+use anyhow::Context;
+use heed::types::ByteSlice;
+use heed::{Database, RoRange, RoTxn};
+
+/// The maximum number of values whose size is sampled, to stay responsive on huge databases.
+const MAX_SAMPLES: usize = 10_000;
+
+/// The number of bytes of the keys seeked to that are interpolated, after the common prefix.
+const INTERPOLATED_BYTES: usize = 8;
+
+/// The bucket boundaries shown by default, in bytes.
+pub const DEFAULT_BOUNDARIES: &str = "16, 64, 256, 1024, 4096, 16384";
+
+/// The byte lengths of a sample of the values of a database.
+#[derive(Debug)]
+pub struct ValueSizes {
+    sizes: Vec<usize>,
+    /// Whether the values were sampled at keys spread between the first and the last key,
+    /// rather than all read.
+    pub seeked: bool,
+    /// The number of entries of the database when it was sampled.
+    pub entries: u64,
+}
+
+impl ValueSizes {
+    /// Reads all the values of a small database, or seeks to `MAX_SAMPLES` keys spread
+    /// evenly between the first and the last key: reading every entry of a huge database
+    /// takes too long. The sample is skewed when the keys aren't spread evenly.
+    pub fn sample(database: &Database<ByteSlice, ByteSlice>, rtxn: &RoTxn) -> heed::Result<Self> {
+        let entries = database.len(rtxn)?;
+        let (Some((first, _)), Some((last, _)), true) =
+            (database.first(rtxn)?, database.last(rtxn)?, entries > MAX_SAMPLES as u64)
+        else {
+            let sizes = database
+                .iter(rtxn)?
+                .map(|entry| entry.map(|(_, data)| data.len()))
+                .collect::<heed::Result<_>>()?;
+            return Ok(ValueSizes { sizes, seeked: false, entries });
+        };
+
+        // The bytes after the common prefix of the first and the last key are digits, between
+        // the bytes of the two keys: the decimal keys are spread over the ten digits only.
+        let common = first.iter().zip(last).take_while(|(a, b)| a == b).count();
+        let byte = |key: &[u8], i: usize| key.get(common + i).copied().unwrap_or(0);
+        let ranges: Vec<(u8, u8)> = (0..INTERPOLATED_BYTES)
+            .map(|i| (byte(first, i).min(byte(last, i)), byte(first, i).max(byte(last, i))))
+            .collect();
+        let mut sizes = Vec::with_capacity(MAX_SAMPLES);
+        let mut previous_key: Option<&[u8]> = None;
+        // The entries after the last key seeked to that was sampled.
+        let mut sampled_entries: Option<RoRange<ByteSlice, ByteSlice>> = None;
+        for i in 0..MAX_SAMPLES {
+            let mut key = first[..common].to_vec();
+            // The position of the sample, as a fraction of the key space digit by digit.
+            let mut fraction = i as f64 / MAX_SAMPLES as f64;
+            for &(low, high) in &ranges {
+                let radix = f64::from(high - low) + 1.0;
+                let digit = (fraction * radix).floor();
+                key.push(low + digit as u8);
+                fraction = fraction * radix - digit;
+            }
+            let mut seeked =
+                database.range(rtxn, &(Bound::Included(&key[..]), Bound::Unbounded))?;
+            let entry = match seeked.next().transpose()? {
+                // The sparse keys are seeked to many times, the entries after the one sampled
+                // last are then sampled.
+                Some((key, _)) if previous_key >= Some(key) => match &mut sampled_entries {
+                    Some(entries) => entries.next().transpose()?,
+                    None => None,
+                },
+                entry => {
+                    sampled_entries = Some(seeked);
+                    entry
+                }
+            };
+            let Some((key, data)) = entry else { break };
+            sizes.push(data.len());
+            previous_key = Some(key);
+        }
+        Ok(ValueSizes { sizes, seeked: true, entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Counts the sampled sizes in each bucket, the boundaries are the sorted exclusive
+    /// upper bounds of the buckets and the last bucket has the sizes above all of them.
+    pub fn buckets(&self, boundaries: &[usize]) -> Vec<u64> {
+        let mut counts = vec![0; boundaries.len() + 1];
+        for size in &self.sizes {
+            counts[boundaries.partition_point(|boundary| boundary <= size)] += 1;
+        }
+        counts
+    }
+}
+
+/// Parses bucket boundaries separated by commas, they are sorted and deduplicated.
+pub fn parse_boundaries(text: &str) -> anyhow::Result<Vec<usize>> {
+    let mut boundaries = text
+        .split(',')
+        .map(str::trim)
+        .filter(|boundary| !boundary.is_empty())
+        .map(|boundary| boundary.parse().with_context(|| format!("invalid boundary {boundary:?}")))
+        .collect::<anyhow::Result<Vec<usize>>>()?;
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    Ok(boundaries)
+}
+
+/// The label of each bucket delimited by the boundaries.
+pub fn bucket_labels(boundaries: &[usize]) -> Vec<String> {
+    let mut labels = Vec::with_capacity(boundaries.len() + 1);
+    let mut lower = 0;
+    for &boundary in boundaries {
+        labels.push(format!("{lower}..{boundary} B"));
+        lower = boundary;
+    }
+    labels.push(format!("≥ {lower} B"));
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bucket_labels, parse_boundaries, ValueSizes, MAX_SAMPLES};
+    use crate::test_env::TestEnv;
+
+    #[test]
+    fn parses_the_boundaries() {
+        assert_eq!(parse_boundaries("64, 16,,16 ").unwrap(), [16, 64]);
+        assert_eq!(parse_boundaries("").unwrap(), [0; 0]);
+        assert_eq!(parse_boundaries("1, x").unwrap_err().to_string(), "invalid boundary \"x\"");
+        assert_eq!(bucket_labels(&[16, 64]), ["0..16 B", "16..64 B", "≥ 64 B"]);
+    }
+
+    #[test]
+    fn reads_all_the_values_of_a_small_database() {
+        let env = TestEnv::new("value-sizes");
+        let (sixteen, hundred) = ("x".repeat(16), "x".repeat(100));
+        let database = env.database(None, &[("a", "x"), ("b", &sixteen), ("c", &hundred)]);
+        let rtxn = env.read_txn().unwrap();
+        let sizes = ValueSizes::sample(&database, &rtxn).unwrap();
+        assert!(!sizes.seeked);
+        assert_eq!((sizes.len(), sizes.entries), (3, 3));
+        assert_eq!(sizes.buckets(&[16, 64]), [1, 1, 1]);
+    }
+
+    #[test]
+    fn seeks_to_the_keys_spread_in_a_large_database() {
+        let env = TestEnv::new("value-sizes-seeked");
+        let keys: Vec<_> = (0..2 * MAX_SAMPLES).map(|i| format!("{i:05}")).collect();
+        let entries: Vec<_> = keys.iter().map(|key| (key.as_str(), "12345678")).collect();
+        let database = env.database(None, &entries);
+        let rtxn = env.read_txn().unwrap();
+        let sizes = ValueSizes::sample(&database, &rtxn).unwrap();
+        assert!(sizes.seeked);
+        assert_eq!((sizes.len(), sizes.entries), (MAX_SAMPLES, 2 * MAX_SAMPLES as u64));
+        assert_eq!(sizes.buckets(&[8]), [0, MAX_SAMPLES as u64]);
+    }
+}
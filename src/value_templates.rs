@@ -0,0 +1,89 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::ValueEncoding;
+
+/// The key under which the value templates are persisted.
+const VALUE_TEMPLATES_KEY: &str = "value_templates";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueTemplate {
+    pub name: String,
+    /// The data of the template, always in STFU-8 whatever the display encoding.
+    pub data: String,
+}
+
+/// The values the user saved to insert them again, in the order they were added.
+#[derive(Debug, Default)]
+pub struct ValueTemplates {
+    templates: Vec<ValueTemplate>,
+    /// The name of the next template, being typed.
+    new_name: String,
+}
+
+impl ValueTemplates {
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> ValueTemplates {
+        let templates = storage
+            .and_then(|storage| eframe::get_value(storage, VALUE_TEMPLATES_KEY))
+            .unwrap_or_default();
+        ValueTemplates { templates, new_name: String::new() }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, VALUE_TEMPLATES_KEY, &self.templates);
+    }
+
+    /// Shows the templates to choose from and a button to add the `current` data as a
+    /// template, when it is valid. Returns the data of the chosen template.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        id_source: impl std::hash::Hash,
+        current: Option<&[u8]>,
+    ) -> Option<Vec<u8>> {
+        let mut chosen = None;
+        ui.horizontal(|ui| {
+            let mut removed = None;
+            egui::ComboBox::from_id_source(id_source)
+                .selected_text("fill from a template")
+                .show_ui(ui, |ui| {
+                    if self.templates.is_empty() {
+                        ui.label("no templates yet");
+                    }
+                    for (i, template) in self.templates.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✕").on_hover_text("remove the template").clicked()
+                            {
+                                removed = Some(i);
+                            }
+                            let label = ui.selectable_label(false, &template.name);
+                            if label.on_hover_text(&template.data).clicked() {
+                                chosen = ValueEncoding::Stfu8.decode(&template.data).ok();
+                            }
+                        });
+                    }
+                });
+            if let Some(i) = removed {
+                self.templates.remove(i);
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_name)
+                    .hint_text("template name")
+                    .desired_width(100.0),
+            );
+            let can_add = current.is_some() && !self.new_name.is_empty();
+            let add = ui
+                .add_enabled(can_add, egui::Button::new("add current as template"))
+                .on_disabled_hover_text("name the template and enter valid data first");
+            if let (true, Some(current)) = (add.clicked(), current) {
+                let name = std::mem::take(&mut self.new_name);
+                // A template with the same name is replaced.
+                self.templates.retain(|template| template.name != name);
+                let data = ValueEncoding::Stfu8.encode(current);
+                self.templates.push(ValueTemplate { name, data });
+            }
+        });
+        chosen
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use heed::Env;
+
+/// How long the write lock is waited for before telling the user another process holds it.
+const CONTENDED_AFTER: Duration = Duration::from_millis(300);
+
+/// Waits for the write lock of the environment in a background thread, LMDB has a single
+/// writer across all the processes and opening a write txn blocks until the other one ends.
+///
+/// The thread opens and aborts a write txn, the write txn of the UI is then opened on its own
+/// thread: LMDB write txns can't be moved to another thread. Another writer could take the lock
+/// in between, the UI then blocks on it like before.
+pub struct WriteLockWait {
+    receiver: Receiver<heed::Result<()>>,
+    started: Instant,
+    /// Whether the user chose to keep reading, the thread can't be interrupted and is
+    /// still polled until it is done: the environment can't be reopened in the meantime.
+    pub cancelled: bool,
+}
+
+impl WriteLockWait {
+    pub fn spawn(env: &Env) -> WriteLockWait {
+        let (sender, receiver) = mpsc::channel();
+        let env = env.clone();
+        thread::spawn(move || {
+            let result = env.write_txn().map(drop);
+            let _ = sender.send(result);
+        });
+        WriteLockWait { receiver, started: Instant::now(), cancelled: false }
+    }
+
+    /// Returns whether the lock was free once the thread got it, or failed to.
+    pub fn poll(&self) -> Option<heed::Result<()>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(heed::Error::Io(std::io::Error::other(
+                "the thread waiting for the write lock stopped",
+            )))),
+        }
+    }
+
+    /// Whether it has been waiting long enough that another process must be writing.
+    pub fn is_contended(&self) -> bool {
+        self.started.elapsed() >= CONTENDED_AFTER
+    }
+
+    pub fn waited(&self) -> Duration {
+        self.started.elapsed()
+    }
+}